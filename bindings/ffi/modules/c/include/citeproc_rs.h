@@ -109,6 +109,11 @@ typedef struct citeproc_rs_driver citeproc_rs_driver;
 
 typedef struct citeproc_rs_locale_slot citeproc_rs_locale_slot;
 
+/**
+ * An opaque, boxed list of edits to apply with [citeproc_rs_driver_commit_transaction].
+ */
+typedef struct citeproc_rs_transaction citeproc_rs_transaction;
+
 /**
  * A callback signature that is expected to write a string into `slot` via
  * [citeproc_rs_locale_slot_write]
@@ -276,6 +281,18 @@ citeproc_rs_error_code citeproc_rs_driver_format_cluster(struct citeproc_rs_driv
 citeproc_rs_error_code citeproc_rs_driver_format_bibliography(struct citeproc_rs_driver *driver,
                                                               void *user_buf);
 
+/**
+ * Writes the problems that have appeared since the last call into a buffer, as a JSON array,
+ * using [citeproc::Processor::take_diagnostics]. Cluster ids are the u32s you passed in.
+ *
+ * # Safety
+ *
+ * `driver` must be a valid pointer to a Driver, and `user_buf` must match the expected user
+ * data in the BufferOps struct passed to driver's init call.
+ */
+citeproc_rs_error_code citeproc_rs_driver_take_diagnostics(struct citeproc_rs_driver *driver,
+                                                           void *user_buf);
+
 /**
  * Formats a bibliography entry for a given reference.
  *
@@ -515,6 +532,145 @@ citeproc_rs_error_code citeproc_rs_cluster_cite_set_suffix(struct citeproc_rs_cl
                                                            const char *suffix,
                                                            uintptr_t suffix_len);
 
+/**
+ * Creates an empty transaction. Free with [citeproc_rs_transaction_free].
+ */
+struct citeproc_rs_transaction *citeproc_rs_transaction_new(void);
+
+/**
+ * Deallocates a transaction.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed.
+ */
+citeproc_rs_error_code citeproc_rs_transaction_free(struct citeproc_rs_transaction *txn);
+
+/**
+ * Adds a [citeproc::Processor::insert_reference] to the transaction.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed.
+ *
+ * Either `ref_json` must refer to a byte array of length `ref_json_len`, or `ref_json_len` must be zero.
+ */
+citeproc_rs_error_code citeproc_rs_transaction_insert_reference(struct citeproc_rs_transaction *txn,
+                                                                const char *ref_json,
+                                                                uintptr_t ref_json_len);
+
+/**
+ * Adds a [citeproc::Processor::remove_reference] to the transaction.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed.
+ *
+ * Either `ref_id` must refer to a byte array of length `ref_id_len`, or `ref_id_len` must be zero.
+ */
+citeproc_rs_error_code citeproc_rs_transaction_remove_reference(struct citeproc_rs_transaction *txn,
+                                                                const char *ref_id,
+                                                                uintptr_t ref_id_len);
+
+/**
+ * Adds a [citeproc::Processor::insert_cluster] to the transaction. The cluster is copied, so
+ * you can reuse or free it afterwards.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed. The cluster must
+ * be from [citeproc_rs_cluster_new].
+ */
+citeproc_rs_error_code citeproc_rs_transaction_insert_cluster(struct citeproc_rs_transaction *txn,
+                                                              const struct citeproc_rs_cluster *cluster);
+
+/**
+ * Adds a [citeproc::Processor::remove_cluster] to the transaction.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed.
+ */
+citeproc_rs_error_code citeproc_rs_transaction_remove_cluster(struct citeproc_rs_transaction *txn,
+                                                              citeproc_rs_cluster_id cluster_id);
+
+/**
+ * Adds a [citeproc_rs_driver_set_cluster_order] to the transaction.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed.
+ *
+ * positions/positions_len must point to a valid array of ClusterPosition.
+ */
+citeproc_rs_error_code citeproc_rs_transaction_set_cluster_order(struct citeproc_rs_transaction *txn,
+                                                                 const struct citeproc_rs_cluster_position *positions,
+                                                                 uintptr_t positions_len);
+
+/**
+ * Applies every edit in the transaction at once with [citeproc::Processor::transaction], and
+ * empties it. If any edit fails, none are applied.
+ *
+ * Writes the clusters that changed into `user_buf`, as a JSON array of `[id, output]` pairs.
+ *
+ * # Safety
+ *
+ * `driver` must be a valid pointer to a Driver, the transaction must be from
+ * [citeproc_rs_transaction_new] and not freed, and `user_buf` must match the expected user
+ * data in the BufferOps struct passed to driver's init call.
+ */
+citeproc_rs_error_code citeproc_rs_driver_commit_transaction(struct citeproc_rs_driver *driver,
+                                                             struct citeproc_rs_transaction *txn,
+                                                             void *user_buf);
+
+/**
+ * Reverts the last transaction with [citeproc::Processor::undo]. Returns 0 if there was
+ * nothing to undo, or 1 after writing the clusters that changed into `user_buf` like
+ * [citeproc_rs_driver_commit_transaction].
+ *
+ * # Safety
+ *
+ * Same as [citeproc_rs_driver_commit_transaction].
+ */
+citeproc_rs_u32_or_error citeproc_rs_driver_undo(struct citeproc_rs_driver *driver, void *user_buf);
+
+/**
+ * Applies the last undone transaction again with [citeproc::Processor::redo]. Returns 0 if
+ * there was nothing to redo, or 1 after writing the clusters that changed into `user_buf`
+ * like [citeproc_rs_driver_commit_transaction].
+ *
+ * # Safety
+ *
+ * Same as [citeproc_rs_driver_commit_transaction].
+ */
+citeproc_rs_u32_or_error citeproc_rs_driver_redo(struct citeproc_rs_driver *driver, void *user_buf);
+
+/**
+ * Returns the number of transactions that can be undone, using
+ * [citeproc::Processor::revision]. Pass it to [citeproc_rs_driver_rollback_to] later to get
+ * back to the current state.
+ *
+ * # Safety
+ *
+ * `driver` must be a valid pointer to a Driver.
+ */
+citeproc_rs_u32_or_error citeproc_rs_driver_revision(struct citeproc_rs_driver *driver);
+
+/**
+ * Undoes transactions until the driver is back at a revision from
+ * [citeproc_rs_driver_revision], with [citeproc::Processor::rollback_to].
+ *
+ * Writes the clusters that changed into `user_buf` like
+ * [citeproc_rs_driver_commit_transaction].
+ *
+ * # Safety
+ *
+ * Same as [citeproc_rs_driver_commit_transaction].
+ */
+citeproc_rs_error_code citeproc_rs_driver_rollback_to(struct citeproc_rs_driver *driver,
+                                                      uint32_t revision,
+                                                      void *user_buf);
+
 /**
  * If you use this as your buffer_write_callback, then you must call [citeproc_rs_cstring_free] on
  * the resulting buffers, or the memory will leak.
@@ -107,6 +107,11 @@ typedef struct CRDriver CRDriver;
 
 typedef struct CRLocaleSlot CRLocaleSlot;
 
+/**
+ * An opaque, boxed list of edits to apply with [citeproc_rs_driver_commit_transaction].
+ */
+typedef struct CRTransaction CRTransaction;
+
 /**
  * A callback signature that is expected to write a string into `slot` via
  * [citeproc_rs_locale_slot_write]
@@ -274,6 +279,18 @@ CRErrorCode citeproc_rs_driver_format_cluster(struct CRDriver *driver,
 CRErrorCode citeproc_rs_driver_format_bibliography(struct CRDriver *driver,
                                                    void *user_buf) CF_SWIFT_NAME(citeproc_rs_driver_format_bibliography(driver:user_buf:));
 
+/**
+ * Writes the problems that have appeared since the last call into a buffer, as a JSON array,
+ * using [citeproc::Processor::take_diagnostics]. Cluster ids are the u32s you passed in.
+ *
+ * # Safety
+ *
+ * `driver` must be a valid pointer to a Driver, and `user_buf` must match the expected user
+ * data in the BufferOps struct passed to driver's init call.
+ */
+CRErrorCode citeproc_rs_driver_take_diagnostics(struct CRDriver *driver,
+                                                void *user_buf) CF_SWIFT_NAME(citeproc_rs_driver_take_diagnostics(driver:user_buf:));
+
 /**
  * Formats a bibliography entry for a given reference.
  *
@@ -524,6 +541,147 @@ CRErrorCode citeproc_rs_cluster_cite_set_suffix(struct CRCluster *cluster,
                                                 const char *suffix,
                                                 uintptr_t suffix_len) CF_SWIFT_NAME(citeproc_rs_cluster_cite_set_suffix(cluster:cite_index:suffix:suffix_len:));
 
+/**
+ * Creates an empty transaction. Free with [citeproc_rs_transaction_free].
+ */
+struct CRTransaction *citeproc_rs_transaction_new(void) CF_SWIFT_NAME(citeproc_rs_transaction_new());
+
+/**
+ * Deallocates a transaction.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed.
+ */
+CRErrorCode citeproc_rs_transaction_free(struct CRTransaction *txn) CF_SWIFT_NAME(citeproc_rs_transaction_free(txn:));
+
+/**
+ * Adds a [citeproc::Processor::insert_reference] to the transaction.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed.
+ *
+ * Either `ref_json` must refer to a byte array of length `ref_json_len`, or `ref_json_len` must be zero.
+ */
+CRErrorCode citeproc_rs_transaction_insert_reference(struct CRTransaction *txn,
+                                                     const char *ref_json,
+                                                     uintptr_t ref_json_len) CF_SWIFT_NAME(citeproc_rs_transaction_insert_reference(txn:ref_json:ref_json_len:));
+
+/**
+ * Adds a [citeproc::Processor::remove_reference] to the transaction.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed.
+ *
+ * Either `ref_id` must refer to a byte array of length `ref_id_len`, or `ref_id_len` must be zero.
+ */
+CRErrorCode citeproc_rs_transaction_remove_reference(struct CRTransaction *txn,
+                                                     const char *ref_id,
+                                                     uintptr_t ref_id_len) CF_SWIFT_NAME(citeproc_rs_transaction_remove_reference(txn:ref_id:ref_id_len:));
+
+/**
+ * Adds a [citeproc::Processor::insert_cluster] to the transaction. The cluster is copied, so
+ * you can reuse or free it afterwards.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed. The cluster must
+ * be from [citeproc_rs_cluster_new].
+ */
+CRErrorCode citeproc_rs_transaction_insert_cluster(struct CRTransaction *txn,
+                                                   const struct CRCluster *cluster) CF_SWIFT_NAME(citeproc_rs_transaction_insert_cluster(txn:cluster:));
+
+/**
+ * Adds a [citeproc::Processor::remove_cluster] to the transaction.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed.
+ */
+CRErrorCode citeproc_rs_transaction_remove_cluster(struct CRTransaction *txn,
+                                                   CRClusterId cluster_id) CF_SWIFT_NAME(citeproc_rs_transaction_remove_cluster(txn:cluster_id:));
+
+/**
+ * Adds a [citeproc_rs_driver_set_cluster_order] to the transaction.
+ *
+ * # Safety
+ *
+ * The transaction must be from [citeproc_rs_transaction_new] and not freed.
+ *
+ * positions/positions_len must point to a valid array of ClusterPosition.
+ */
+CRErrorCode citeproc_rs_transaction_set_cluster_order(struct CRTransaction *txn,
+                                                      const struct CRClusterPosition *positions,
+                                                      uintptr_t positions_len) CF_SWIFT_NAME(citeproc_rs_transaction_set_cluster_order(txn:positions:positions_len:));
+
+/**
+ * Applies every edit in the transaction at once with [citeproc::Processor::transaction], and
+ * empties it. If any edit fails, none are applied.
+ *
+ * Writes the clusters that changed into `user_buf`, as a JSON array of `[id, output]` pairs.
+ *
+ * # Safety
+ *
+ * `driver` must be a valid pointer to a Driver, the transaction must be from
+ * [citeproc_rs_transaction_new] and not freed, and `user_buf` must match the expected user
+ * data in the BufferOps struct passed to driver's init call.
+ */
+CRErrorCode citeproc_rs_driver_commit_transaction(struct CRDriver *driver,
+                                                  struct CRTransaction *txn,
+                                                  void *user_buf) CF_SWIFT_NAME(citeproc_rs_driver_commit_transaction(driver:txn:user_buf:));
+
+/**
+ * Reverts the last transaction with [citeproc::Processor::undo]. Returns 0 if there was
+ * nothing to undo, or 1 after writing the clusters that changed into `user_buf` like
+ * [citeproc_rs_driver_commit_transaction].
+ *
+ * # Safety
+ *
+ * Same as [citeproc_rs_driver_commit_transaction].
+ */
+CRU32OrError citeproc_rs_driver_undo(struct CRDriver *driver,
+                                     void *user_buf) CF_SWIFT_NAME(citeproc_rs_driver_undo(driver:user_buf:));
+
+/**
+ * Applies the last undone transaction again with [citeproc::Processor::redo]. Returns 0 if
+ * there was nothing to redo, or 1 after writing the clusters that changed into `user_buf`
+ * like [citeproc_rs_driver_commit_transaction].
+ *
+ * # Safety
+ *
+ * Same as [citeproc_rs_driver_commit_transaction].
+ */
+CRU32OrError citeproc_rs_driver_redo(struct CRDriver *driver,
+                                     void *user_buf) CF_SWIFT_NAME(citeproc_rs_driver_redo(driver:user_buf:));
+
+/**
+ * Returns the number of transactions that can be undone, using
+ * [citeproc::Processor::revision]. Pass it to [citeproc_rs_driver_rollback_to] later to get
+ * back to the current state.
+ *
+ * # Safety
+ *
+ * `driver` must be a valid pointer to a Driver.
+ */
+CRU32OrError citeproc_rs_driver_revision(struct CRDriver *driver) CF_SWIFT_NAME(citeproc_rs_driver_revision(driver:));
+
+/**
+ * Undoes transactions until the driver is back at a revision from
+ * [citeproc_rs_driver_revision], with [citeproc::Processor::rollback_to].
+ *
+ * Writes the clusters that changed into `user_buf` like
+ * [citeproc_rs_driver_commit_transaction].
+ *
+ * # Safety
+ *
+ * Same as [citeproc_rs_driver_commit_transaction].
+ */
+CRErrorCode citeproc_rs_driver_rollback_to(struct CRDriver *driver,
+                                           uint32_t revision,
+                                           void *user_buf) CF_SWIFT_NAME(citeproc_rs_driver_rollback_to(driver:revision:user_buf:));
+
 /**
  * If you use this as your buffer_write_callback, then you must call [citeproc_rs_cstring_free] on
  * the resulting buffers, or the memory will leak.
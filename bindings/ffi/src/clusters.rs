@@ -12,7 +12,7 @@ use crate::{Driver, ErrorCode, FFIError, U32OrError};
 pub type ClusterId = u32;
 
 /// An opaque, boxed wrapper for a [citeproc::prelude::Cluster].
-pub struct Cluster(rust::Cluster);
+pub struct Cluster(pub(crate) rust::Cluster);
 
 #[cfg(doc)]
 use super::*;
@@ -19,8 +19,10 @@ pub mod buffer;
 mod nullable;
 
 mod clusters;
+mod transaction;
 pub use clusters::*;
 pub use errors::*;
+pub use transaction::*;
 
 use thiserror::Error;
 
@@ -298,6 +300,24 @@ pub struct ClusterPosition {
     pub note_number: u32,
 }
 
+impl ClusterPosition {
+    fn to_rust(&self) -> Result<rust::ClusterPosition, FFIError> {
+        if self.is_preview_marker {
+            return Err(FFIError::Reordering(
+                citeproc::ReorderingError::ClusterOrderWithZero,
+            ));
+        }
+        Ok(rust::ClusterPosition {
+            id: Some(rust::ClusterId(self.id)),
+            note: if self.is_note {
+                Some(self.note_number)
+            } else {
+                None
+            },
+        })
+    }
+}
+
 ffi_fn_nullify! {
     /// [citeproc::Processor::set_cluster_order], but using an ffi-compatible [ClusterPosition]
     ///
@@ -314,19 +334,7 @@ ffi_fn_nullify! {
             driver.positions_scratch.clear();
             driver.positions_scratch.reserve(slice.len());
             for pos in slice {
-                let rustpos = rust::ClusterPosition {
-                    id: if pos.is_preview_marker {
-                        return Err(FFIError::Reordering(citeproc::ReorderingError::ClusterOrderWithZero));
-                    } else {
-                        Some(rust::ClusterId(pos.id))
-                    },
-                    note: if pos.is_note {
-                        Some(pos.note_number)
-                    } else {
-                        None
-                    },
-                };
-                driver.positions_scratch.push(rustpos);
+                driver.positions_scratch.push(pos.to_rust()?);
             }
             proc.set_cluster_order(&driver.positions_scratch)?;
             Ok(ErrorCode::None)
@@ -372,6 +380,38 @@ ffi_fn_nullify! {
     }
 }
 
+ffi_fn_nullify! {
+    /// Writes the problems that have appeared since the last call into a buffer, as a JSON array,
+    /// using [citeproc::Processor::take_diagnostics]. Cluster ids are the u32s you passed in.
+    ///
+    /// # Safety
+    ///
+    /// `driver` must be a valid pointer to a Driver, and `user_buf` must match the expected user
+    /// data in the BufferOps struct passed to driver's init call.
+    @safety unsafe fn citeproc_rs_driver_take_diagnostics(#[nullify_on_panic] driver: *mut Driver, user_buf: *mut c_void) -> ErrorCode {
+        result_to_error_code(|| {
+            let driver = unsafe { borrow_raw_ptr_mut(driver) } ?;
+            let proc = driver.processor.as_mut().ok_or(FFIError::Poisoned)?;
+            let mut buffer = unsafe { BufferWriter::new(driver.buffer_ops, user_buf) };
+            let diagnostics: Vec<citeproc::Diagnostic<u32>> = proc
+                .take_diagnostics()
+                .into_iter()
+                .map(|d| citeproc::Diagnostic {
+                    kind: d.kind,
+                    message: d.message,
+                    cluster_id: d.cluster_id.map(|id| id.0),
+                    cite_index: d.cite_index,
+                    ref_id: d.ref_id,
+                    style_location: d.style_location,
+                })
+                .collect();
+            buffer.clear();
+            serde_json::to_writer(&mut buffer, &diagnostics)?;
+            Ok(ErrorCode::None)
+        })
+    }
+}
+
 ffi_fn_nullify! {
     /// Formats a bibliography entry for a given reference.
     ///
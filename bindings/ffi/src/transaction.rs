@@ -0,0 +1,258 @@
+use libc::{c_char, c_void};
+
+use citeproc::prelude as rust;
+use rust::{Atom, Edit, Reference};
+
+use crate::buffer::BufferWriter;
+use crate::clusters::{Cluster, ClusterId};
+use crate::macros::nullify_on_panic;
+use crate::util::*;
+use crate::{ClusterPosition, Driver, ErrorCode, FFIError, U32OrError};
+
+/// An opaque, boxed list of edits to apply with [citeproc_rs_driver_commit_transaction].
+pub struct Transaction(Vec<Edit>);
+
+#[cfg(doc)]
+use super::*;
+
+ffi_fn_nullify! {
+    /// Creates an empty transaction. Free with [citeproc_rs_transaction_free].
+    fn citeproc_rs_transaction_new() -> *mut Transaction {
+        Box::into_raw(Box::new(Transaction(Vec::new())))
+    }
+}
+
+ffi_fn_nullify! {
+    /// Deallocates a transaction.
+    ///
+    /// # Safety
+    ///
+    /// The transaction must be from [citeproc_rs_transaction_new] and not freed.
+    @safety unsafe fn citeproc_rs_transaction_free(txn: *mut Transaction) -> ErrorCode {
+        result_to_error_code(|| {
+            if txn.is_null() {
+                return Err(FFIError::NullPointer);
+            }
+            let _ = unsafe { Box::from_raw(txn) };
+            Ok(ErrorCode::None)
+        })
+    }
+}
+
+ffi_fn_nullify! {
+    /// Adds a [citeproc::Processor::insert_reference] to the transaction.
+    ///
+    /// # Safety
+    ///
+    /// The transaction must be from [citeproc_rs_transaction_new] and not freed.
+    ///
+    /// Either `ref_json` must refer to a byte array of length `ref_json_len`, or `ref_json_len` must be zero.
+    @safety unsafe fn citeproc_rs_transaction_insert_reference(txn: *mut Transaction, ref_json: *const c_char, ref_json_len: usize) -> ErrorCode {
+        result_to_error_code(|| {
+            let txn = unsafe { borrow_raw_ptr_mut(txn) } ?;
+            let ref_json = unsafe { borrow_utf8_slice(ref_json, ref_json_len) } ?;
+            let reference: Reference = serde_json::from_str(ref_json)?;
+            txn.0.push(Edit::InsertReference(reference));
+            Ok(ErrorCode::None)
+        })
+    }
+}
+
+ffi_fn_nullify! {
+    /// Adds a [citeproc::Processor::remove_reference] to the transaction.
+    ///
+    /// # Safety
+    ///
+    /// The transaction must be from [citeproc_rs_transaction_new] and not freed.
+    ///
+    /// Either `ref_id` must refer to a byte array of length `ref_id_len`, or `ref_id_len` must be zero.
+    @safety unsafe fn citeproc_rs_transaction_remove_reference(txn: *mut Transaction, ref_id: *const c_char, ref_id_len: usize) -> ErrorCode {
+        result_to_error_code(|| {
+            let txn = unsafe { borrow_raw_ptr_mut(txn) } ?;
+            let ref_id = unsafe { borrow_utf8_slice(ref_id, ref_id_len) } ?;
+            txn.0.push(Edit::RemoveReference(Atom::from(ref_id)));
+            Ok(ErrorCode::None)
+        })
+    }
+}
+
+ffi_fn_nullify! {
+    /// Adds a [citeproc::Processor::insert_cluster] to the transaction. The cluster is copied, so
+    /// you can reuse or free it afterwards.
+    ///
+    /// # Safety
+    ///
+    /// The transaction must be from [citeproc_rs_transaction_new] and not freed. The cluster must
+    /// be from [citeproc_rs_cluster_new].
+    @safety unsafe fn citeproc_rs_transaction_insert_cluster(txn: *mut Transaction, cluster: *const Cluster) -> ErrorCode {
+        result_to_error_code(|| {
+            let txn = unsafe { borrow_raw_ptr_mut(txn) } ?;
+            let cluster = unsafe { borrow_raw_ptr(cluster) } ?;
+            txn.0.push(Edit::InsertCluster(cluster.0.clone()));
+            Ok(ErrorCode::None)
+        })
+    }
+}
+
+ffi_fn_nullify! {
+    /// Adds a [citeproc::Processor::remove_cluster] to the transaction.
+    ///
+    /// # Safety
+    ///
+    /// The transaction must be from [citeproc_rs_transaction_new] and not freed.
+    @safety unsafe fn citeproc_rs_transaction_remove_cluster(txn: *mut Transaction, cluster_id: ClusterId) -> ErrorCode {
+        result_to_error_code(|| {
+            let txn = unsafe { borrow_raw_ptr_mut(txn) } ?;
+            txn.0.push(Edit::RemoveCluster(rust::ClusterId(cluster_id)));
+            Ok(ErrorCode::None)
+        })
+    }
+}
+
+ffi_fn_nullify! {
+    /// Adds a [citeproc_rs_driver_set_cluster_order] to the transaction.
+    ///
+    /// # Safety
+    ///
+    /// The transaction must be from [citeproc_rs_transaction_new] and not freed.
+    ///
+    /// positions/positions_len must point to a valid array of ClusterPosition.
+    @safety unsafe fn citeproc_rs_transaction_set_cluster_order(txn: *mut Transaction, positions: *const ClusterPosition, positions_len: usize) -> ErrorCode {
+        result_to_error_code(|| {
+            let txn = unsafe { borrow_raw_ptr_mut(txn) } ?;
+            let slice = unsafe { borrow_slice(positions, positions_len) } ?;
+            let positions = slice
+                .iter()
+                .map(ClusterPosition::to_rust)
+                .collect::<Result<Vec<_>, _>>()?;
+            txn.0.push(Edit::SetClusterOrder(positions));
+            Ok(ErrorCode::None)
+        })
+    }
+}
+
+/// Writes the clusters in an update summary into a buffer, as a JSON array of `[id, output]`
+/// pairs.
+fn write_clusters(
+    buffer: &mut BufferWriter,
+    summary: &rust::UpdateSummary,
+) -> Result<(), FFIError> {
+    let clusters: Vec<(u32, &str)> = summary
+        .clusters
+        .iter()
+        .map(|(id, output)| (id.0, output.as_str()))
+        .collect();
+    buffer.clear();
+    serde_json::to_writer(buffer, &clusters)?;
+    Ok(())
+}
+
+ffi_fn_nullify! {
+    /// Applies every edit in the transaction at once with [citeproc::Processor::transaction], and
+    /// empties it. If any edit fails, none are applied.
+    ///
+    /// Writes the clusters that changed into `user_buf`, as a JSON array of `[id, output]` pairs.
+    ///
+    /// # Safety
+    ///
+    /// `driver` must be a valid pointer to a Driver, the transaction must be from
+    /// [citeproc_rs_transaction_new] and not freed, and `user_buf` must match the expected user
+    /// data in the BufferOps struct passed to driver's init call.
+    @safety unsafe fn citeproc_rs_driver_commit_transaction(#[nullify_on_panic] driver: *mut Driver, txn: *mut Transaction, user_buf: *mut c_void) -> ErrorCode {
+        result_to_error_code(|| {
+            let driver = unsafe { borrow_raw_ptr_mut(driver) } ?;
+            let proc = driver.processor.as_mut().ok_or(FFIError::Poisoned)?;
+            let txn = unsafe { borrow_raw_ptr_mut(txn) } ?;
+            let edits = std::mem::take(&mut txn.0);
+            let summary = proc.transaction(edits)?;
+            let mut buffer = unsafe { BufferWriter::new(driver.buffer_ops, user_buf) };
+            write_clusters(&mut buffer, &summary)?;
+            Ok(ErrorCode::None)
+        })
+    }
+}
+
+ffi_fn_nullify! {
+    /// Reverts the last transaction with [citeproc::Processor::undo]. Returns 0 if there was
+    /// nothing to undo, or 1 after writing the clusters that changed into `user_buf` like
+    /// [citeproc_rs_driver_commit_transaction].
+    ///
+    /// # Safety
+    ///
+    /// Same as [citeproc_rs_driver_commit_transaction].
+    @safety unsafe fn citeproc_rs_driver_undo(#[nullify_on_panic] driver: *mut Driver, user_buf: *mut c_void) -> U32OrError {
+        result_to_error_code(|| {
+            let driver = unsafe { borrow_raw_ptr_mut(driver) } ?;
+            let proc = driver.processor.as_mut().ok_or(FFIError::Poisoned)?;
+            let summary = match proc.undo() {
+                Some(summary) => summary,
+                None => return Ok(U32OrError(0)),
+            };
+            let mut buffer = unsafe { BufferWriter::new(driver.buffer_ops, user_buf) };
+            write_clusters(&mut buffer, &summary)?;
+            Ok(U32OrError(1))
+        })
+    }
+}
+
+ffi_fn_nullify! {
+    /// Applies the last undone transaction again with [citeproc::Processor::redo]. Returns 0 if
+    /// there was nothing to redo, or 1 after writing the clusters that changed into `user_buf`
+    /// like [citeproc_rs_driver_commit_transaction].
+    ///
+    /// # Safety
+    ///
+    /// Same as [citeproc_rs_driver_commit_transaction].
+    @safety unsafe fn citeproc_rs_driver_redo(#[nullify_on_panic] driver: *mut Driver, user_buf: *mut c_void) -> U32OrError {
+        result_to_error_code(|| {
+            let driver = unsafe { borrow_raw_ptr_mut(driver) } ?;
+            let proc = driver.processor.as_mut().ok_or(FFIError::Poisoned)?;
+            let summary = match proc.redo() {
+                Some(summary) => summary,
+                None => return Ok(U32OrError(0)),
+            };
+            let mut buffer = unsafe { BufferWriter::new(driver.buffer_ops, user_buf) };
+            write_clusters(&mut buffer, &summary)?;
+            Ok(U32OrError(1))
+        })
+    }
+}
+
+ffi_fn_nullify! {
+    /// Returns the number of transactions that can be undone, using
+    /// [citeproc::Processor::revision]. Pass it to [citeproc_rs_driver_rollback_to] later to get
+    /// back to the current state.
+    ///
+    /// # Safety
+    ///
+    /// `driver` must be a valid pointer to a Driver.
+    @safety unsafe fn citeproc_rs_driver_revision(#[nullify_on_panic] driver: *mut Driver) -> U32OrError {
+        result_to_error_code(|| {
+            let driver = unsafe { borrow_raw_ptr_mut(driver) } ?;
+            let proc = driver.processor.as_ref().ok_or(FFIError::Poisoned)?;
+            Ok(U32OrError(proc.revision() as i64))
+        })
+    }
+}
+
+ffi_fn_nullify! {
+    /// Undoes transactions until the driver is back at a revision from
+    /// [citeproc_rs_driver_revision], with [citeproc::Processor::rollback_to].
+    ///
+    /// Writes the clusters that changed into `user_buf` like
+    /// [citeproc_rs_driver_commit_transaction].
+    ///
+    /// # Safety
+    ///
+    /// Same as [citeproc_rs_driver_commit_transaction].
+    @safety unsafe fn citeproc_rs_driver_rollback_to(#[nullify_on_panic] driver: *mut Driver, revision: u32, user_buf: *mut c_void) -> ErrorCode {
+        result_to_error_code(|| {
+            let driver = unsafe { borrow_raw_ptr_mut(driver) } ?;
+            let proc = driver.processor.as_mut().ok_or(FFIError::Poisoned)?;
+            let summary = proc.rollback_to(revision as usize);
+            let mut buffer = unsafe { BufferWriter::new(driver.buffer_ops, user_buf) };
+            write_clusters(&mut buffer, &summary)?;
+            Ok(ErrorCode::None)
+        })
+    }
+}
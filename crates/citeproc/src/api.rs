@@ -9,7 +9,7 @@
 use super::processor::Interner;
 use citeproc_db::ClusterId;
 use citeproc_io::output::{markup::Markup, OutputFormat};
-use citeproc_io::{Cite, ClusterMode, SmartString};
+use citeproc_io::{Cite, ClusterMode, Reference, SmartString};
 use csl::Atom;
 use fnv::FnvHashMap;
 use serde::{Deserialize, Serialize};
@@ -48,9 +48,9 @@ use std::sync::Arc;
 ///               mode: Some(ClusterMode::Composite { infix: None, suppress_first: 2 }), },
 /// ]);
 /// ```
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(bound(
-    serialize = "Id: serde::Serialize",
+    serialize = "Id: serde::Serialize, O::Input: serde::Serialize",
     deserialize = "Id: serde::Deserialize<'de>"
 ))]
 pub struct Cluster<O: OutputFormat = Markup, Id = ClusterId> {
@@ -196,19 +196,34 @@ impl ReorderingError {
     }
 }
 
+/// One change in a [Processor::transaction](crate::Processor::transaction). Each does the same
+/// as the `Processor` method it is named after.
+#[derive(Debug, Clone)]
+pub enum Edit {
+    InsertReference(Reference),
+    RemoveReference(Atom),
+    InsertCluster(Cluster),
+    RemoveCluster(ClusterId),
+    SetClusterOrder(Vec<ClusterPosition>),
+}
+
 pub mod string_id {
     //! This is the API using string IDs only, useful for exposing citeproc-rs to non-Rust
     //! consumers.
     use super::{BibEntry, BibliographyUpdate};
     use citeproc_io::{
         output::{markup::Markup, OutputFormat},
-        SmartString,
+        Reference, SmartString,
     };
+    use csl::Atom;
     use fnv::FnvHashMap;
     use serde::{Deserialize, Serialize};
     use std::sync::Arc;
 
     pub type Cluster<O = Markup> = super::Cluster<O, SmartString>;
+    pub type Diagnostic = super::Diagnostic<SmartString>;
+    pub type BibliographySection = citeproc_db::BibliographySection<SmartString>;
+    pub type StylePreview = super::StylePreview<SmartString>;
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     pub struct ClusterPosition {
@@ -251,6 +266,12 @@ pub mod string_id {
         /// A list of clusters that were updated, paired with the formatted output for each
         pub clusters: Vec<(SmartString, Arc<O::Output>)>,
         pub bibliography: Option<BibliographyUpdate>,
+        /// Changes to each bibliography section, keyed by section name
+        #[serde(skip_serializing_if = "FnvHashMap::is_empty")]
+        pub bibliography_sections: FnvHashMap<SmartString, BibliographyUpdate>,
+        /// True if rendering was cancelled or ran out of time
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        pub interrupted: bool,
     }
 
     #[derive(Serialize, Default, Debug, Clone, PartialEq, Eq)]
@@ -260,6 +281,26 @@ pub mod string_id {
         pub bib_entries: Vec<BibEntry<Markup>>,
     }
 
+    /// ```
+    /// use citeproc::string_id::Edit;
+    /// let json = r#"
+    /// [ { "insertReference": { "id": "smith", "type": "book" } }
+    /// , { "insertCluster": { "id": "one", "cites": [{ "id": "smith" }] } }
+    /// , { "setClusterOrder": [{ "id": "one", "note": 1 }] }
+    /// , { "removeCluster": "two" }
+    /// ]"#;
+    /// let edits: Vec<Edit> = serde_json::from_str(json).unwrap();
+    /// ```
+    #[derive(Debug, Clone, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub enum Edit {
+        InsertReference(Reference),
+        RemoveReference(Atom),
+        InsertCluster(Cluster),
+        RemoveCluster(SmartString),
+        SetClusterOrder(Vec<ClusterPosition>),
+    }
+
     #[derive(Debug, thiserror::Error)]
     pub enum ReorderingError {
         #[error("{0}")]
@@ -320,6 +361,11 @@ pub struct UpdateSummary<O: OutputFormat = Markup> {
     /// A list of clusters that were updated, paired with the formatted output for each
     pub clusters: Vec<(ClusterId, Arc<O::Output>)>,
     pub bibliography: Option<BibliographyUpdate>,
+    /// Changes to each bibliography section, keyed by section name
+    pub bibliography_sections: FnvHashMap<SmartString, BibliographyUpdate>,
+    /// True if rendering was cancelled or ran out of time. The clusters that weren't rendered, and
+    /// the bibliography, will be in the next summary.
+    pub interrupted: bool,
 }
 
 #[derive(Serialize, Default, Debug, Clone, PartialEq, Eq)]
@@ -329,12 +375,104 @@ pub struct BibEntry<O: OutputFormat = Markup> {
     pub value: Arc<O::Output>,
 }
 
+/// What would change if the document were rendered with a different style. See
+/// [crate::Processor::preview_style_switch].
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[serde(bound(serialize = "Id: serde::Serialize"))]
+pub struct StylePreview<Id = ClusterId> {
+    /// Only the clusters whose output would change, in document order.
+    pub clusters: Vec<ClusterChange<Id>>,
+    /// Only the entries that would change, be added or be removed, in the candidate style's order
+    /// followed by any removed entries.
+    pub bibliography: Vec<BibEntryChange>,
+    /// None if the bibliography would have the same entries in the same order, otherwise all of
+    /// them in the candidate style's order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_ids: Option<Vec<Atom>>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[serde(bound(serialize = "Id: serde::Serialize"))]
+pub struct ClusterChange<Id = ClusterId> {
+    pub id: Id,
+    pub before: Arc<SmartString>,
+    pub after: Arc<SmartString>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BibEntryChange {
+    pub id: Atom,
+    /// None if the entry is new.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Arc<SmartString>>,
+    /// None if the entry would be removed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Arc<SmartString>>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct FullRender {
     pub all_clusters: FnvHashMap<ClusterId, Arc<SmartString>>,
     pub bib_entries: Vec<BibEntry<Markup>>,
 }
 
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// A cite refers to a reference id that hasn't been inserted. It renders as `???`.
+    MissingReference,
+    /// The locale for the style's `default-locale` (or the locale override) could not be fetched
+    /// or parsed, so the bundled en-US terms are being used.
+    LocaleUnavailable,
+    /// A `<text macro="...">` names a macro that doesn't exist. It renders as nothing.
+    UndefinedMacro,
+    /// A macro calls itself. The inner call renders as nothing.
+    RecursiveMacro,
+    /// A CSL-M `match="nand"`, which renders correctly but is ignored when disambiguating.
+    UnsupportedCondition,
+    /// A reference's raw date couldn't be parsed, so it renders as the raw string.
+    UnparsedRawDate,
+}
+
+/// A problem found while rendering, that the processor worked around instead of failing. Fetch
+/// these with [crate::Processor::take_diagnostics].
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+#[serde(bound(serialize = "Id: serde::Serialize"))]
+pub struct Diagnostic<Id = ClusterId> {
+    pub kind: DiagnosticKind,
+    pub message: String,
+    /// The cluster containing the problem cite, if it is specific to one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster_id: Option<Id>,
+    /// Index of the problem cite within the cluster.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cite_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ref_id: Option<Atom>,
+    /// `citation`, `bibliography`, or `macro "name"`, for problems with the style.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style_location: Option<SmartString>,
+}
+
+impl Diagnostic {
+    pub(crate) fn to_external(&self, interner: &Interner) -> string_id::Diagnostic {
+        Diagnostic {
+            kind: self.kind,
+            message: self.message.clone(),
+            cluster_id: self
+                .cluster_id
+                .and_then(|id| interner.resolve(id.raw()))
+                .map(SmartString::from),
+            cite_index: self.cite_index,
+            ref_id: self.ref_id.clone(),
+            style_location: self.style_location.clone(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, Ord, PartialOrd, PartialEq)]
 pub enum IncludeUncited {
     /// The default
@@ -401,6 +539,19 @@ impl<'de> serde::de::Deserialize<'de> for SupportedFormat {
     }
 }
 
+impl serde::Serialize for SupportedFormat {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(match self {
+            SupportedFormat::Html => "html",
+            SupportedFormat::Rtf => "rtf",
+            SupportedFormat::Plain => "plain",
+        })
+    }
+}
+
 pub enum PreviewPosition<'a> {
     /// Convenience, if your user is merely editing a cluster.
     ReplaceCluster(ClusterId),
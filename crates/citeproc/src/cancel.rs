@@ -0,0 +1,68 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Stops a render in progress, e.g. from another thread when a new keystroke makes it pointless.
+/// Get one from [Processor::cancel_handle](crate::Processor::cancel_handle).
+///
+/// Rendering checks it between clusters, so one cluster that takes a long time to disambiguate
+/// will still be finished first.
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+    /// Interrupts the render that is running, or the next one if there is none. Whatever has been
+    /// rendered so far is returned, and the rest is picked up by the next render.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// When one render has to stop.
+pub(crate) struct Budget {
+    cancel: CancelHandle,
+    deadline: Option<Instant>,
+    exhausted: AtomicBool,
+}
+
+impl Budget {
+    pub(crate) fn new(cancel: CancelHandle, deadline: Option<Instant>) -> Self {
+        Budget {
+            cancel,
+            deadline,
+            exhausted: AtomicBool::new(false),
+        }
+    }
+
+    /// Once this returns true, it keeps returning true, so every thread agrees on it.
+    pub(crate) fn is_exhausted(&self) -> bool {
+        if self.exhausted.load(Ordering::Relaxed) {
+            return true;
+        }
+        // Instant::now() panics on wasm32-unknown-unknown, so only call it if asked to.
+        let out_of_time = self.deadline.map_or(false, |d| Instant::now() >= d);
+        if out_of_time || self.cancel.is_cancelled() {
+            self.exhausted.store(true, Ordering::Relaxed);
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn was_exhausted(&self) -> bool {
+        self.exhausted.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,48 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use crate::prelude::*;
+use citeproc_db::{StyleFetchError, StyleFetcher};
+use csl::{StyleError, StyleMeta};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DependentStyleError {
+    #[error(transparent)]
+    Style(#[from] StyleError),
+    #[error("parent style {0:?} was not found")]
+    ParentNotFound(String),
+    #[error("could not fetch parent style {id:?}: {error}")]
+    Fetch { id: String, error: StyleFetchError },
+}
+
+impl Processor {
+    /// Like [Processor::new], but `options.style` may also be a dependent style. Its parent is
+    /// fetched by id through `style_fetcher`, and its `default-locale` is used as the locale
+    /// override unless `options.locale_override` is already set.
+    pub fn new_resolving_dependent(
+        options: InitOptions,
+        style_fetcher: &dyn StyleFetcher,
+    ) -> Result<Self, DependentStyleError> {
+        let meta = StyleMeta::parse(options.style)?;
+        let parent_id = match meta.independent_parent_id() {
+            Some(id) => id,
+            None => return Ok(Processor::new(options)?),
+        };
+        let parent = style_fetcher
+            .fetch_style(&parent_id)
+            .map_err(|error| DependentStyleError::Fetch {
+                id: parent_id.clone(),
+                error,
+            })?
+            .ok_or_else(|| DependentStyleError::ParentNotFound(parent_id))?;
+        // A parent that is itself dependent is rejected by Style::parse.
+        Ok(Processor::new(InitOptions {
+            style: &parent,
+            locale_override: options.locale_override.or(meta.default_locale),
+            ..options
+        })?)
+    }
+}
@@ -0,0 +1,123 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! Loading locales with an [AsyncLocaleFetcher].
+//!
+//! Salsa queries can't wait for anything, so locales are fetched ahead of time and stored as
+//! inputs, the same way the WASM driver's `fetchLocales` does it.
+
+use crate::prelude::*;
+use csl::Lang;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+impl Processor {
+    /// The languages from [Processor::get_langs_in_use] that have not been stored yet, and would
+    /// be requested by [Processor::fetch_locales].
+    pub fn locales_pending(&self) -> Vec<Lang> {
+        self.get_langs_in_use()
+            .into_iter()
+            .filter(|lang| !self.has_cached_locale(lang))
+            .collect()
+    }
+
+    /// Fetches every pending locale with the `async_fetcher` from [InitOptions], all at once, and
+    /// stores the ones that were found.
+    ///
+    /// Until this finishes, rendering falls back to the synchronous `fetcher` (by default, only
+    /// en-US), and [Processor::diagnostics] reports the missing locale. Clusters rendered in the
+    /// meantime are rendered again afterwards, and show up in the next
+    /// [Processor::batched_updates].
+    ///
+    /// Locales that were found are stored even if others failed; the first failure is returned.
+    /// Without an `async_fetcher`, this does nothing.
+    pub async fn fetch_locales(&mut self) -> Result<(), LocaleFetchError> {
+        let langs = self.locales_pending();
+        if langs.is_empty() {
+            return Ok(());
+        }
+        let fetcher = match self.async_fetcher.clone() {
+            Some(fetcher) => fetcher,
+            None => {
+                log::warn!(
+                    "no async locale fetcher was provided, not fetching locales {:?}",
+                    langs
+                );
+                return Ok(());
+            }
+        };
+        let fetches = langs
+            .into_iter()
+            .map(|lang| {
+                let future = fetcher.fetch_string(lang.clone());
+                Fetch {
+                    lang,
+                    future: Some(future),
+                    result: None,
+                }
+            })
+            .collect();
+        let results = FetchAll { fetches }.await;
+        let mut first_error = None;
+        let mut pairs = Vec::with_capacity(results.len());
+        for (lang, result) in results {
+            match result {
+                Ok(Some(xml)) => pairs.push((lang, xml)),
+                Ok(None) => log::warn!("locale {} was not found", lang),
+                Err(e) => {
+                    log::error!("failed to fetch locale {}: {:?}", lang, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        self.store_locales(pairs);
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+type FetchResult = Result<Option<String>, LocaleFetchError>;
+
+struct Fetch<'a> {
+    lang: Lang,
+    future: Option<LocaleFuture<'a>>,
+    result: Option<FetchResult>,
+}
+
+/// Polls every fetch until they have all finished, so slow fetchers (e.g. HTTP requests) run
+/// concurrently instead of one after another.
+struct FetchAll<'a> {
+    fetches: Vec<Fetch<'a>>,
+}
+
+impl<'a> Future for FetchAll<'a> {
+    type Output = Vec<(Lang, FetchResult)>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut finished = true;
+        for fetch in this.fetches.iter_mut() {
+            if let Some(future) = fetch.future.as_mut() {
+                match future.as_mut().poll(cx) {
+                    Poll::Ready(result) => {
+                        fetch.result = Some(result);
+                        fetch.future = None;
+                    }
+                    Poll::Pending => finished = false,
+                }
+            }
+        }
+        if !finished {
+            return Poll::Pending;
+        }
+        Poll::Ready(
+            this.fetches
+                .drain(..)
+                .filter_map(|fetch| Some((fetch.lang, fetch.result?)))
+                .collect(),
+        )
+    }
+}
@@ -8,28 +8,49 @@
 // extern crate log;
 
 pub(crate) mod api;
+pub(crate) mod cancel;
+pub(crate) mod dependent;
+pub(crate) mod fetch;
+pub(crate) mod persist;
 pub(crate) mod processor;
+pub(crate) mod style_switch;
+pub(crate) mod transaction;
 
 #[cfg(test)]
 mod test;
 
 pub use self::api::*;
 
+pub use self::cancel::CancelHandle;
+pub use self::dependent::DependentStyleError;
+pub use self::persist::{RestoreError, SavedOutput, SavedState, SAVED_STATE_VERSION};
 pub use self::processor::{InitOptions, Processor};
+pub use citeproc_db::{
+    BibliographyFilter, BibliographySection, ClusterRange, FieldMatch, PredefinedStyles,
+    StyleFetchError, StyleFetcher, StyleRepository,
+};
+pub use citeproc_proc::explain::{
+    DisambExplanation, DisambMethod, DisambStage, ExpandedName, ExpansionReason, NameExpansion,
+    NameListExpansion,
+};
+pub use citeproc_proc::CiteSpan;
 
 pub mod prelude {
     pub use crate::api::*;
     pub use crate::processor::{InitOptions, Processor};
-    pub use citeproc_db::PredefinedLocales;
+    pub use citeproc_db::{ParseCache, PredefinedLocales};
     pub use citeproc_db::{
-        CiteDatabase, CiteId, ClusterNumber, IntraNote, LocaleDatabase, LocaleFetchError,
-        LocaleFetcher, StyleDatabase,
+        AsyncLocaleFetcher, CiteDatabase, CiteId, ClusterNumber, IntraNote, LocaleDatabase,
+        LocaleFetchError, LocaleFetcher, LocaleFuture, StyleDatabase,
     };
     pub use citeproc_io::output::{markup::Markup, OutputFormat};
     pub use citeproc_io::{Cite, Reference, SmartString};
     pub use citeproc_proc::db::{ImplementationDetails, IrDatabase};
     pub use csl::Atom;
 
+    #[cfg(feature = "bundled-locales")]
+    pub use citeproc_db::BundledLocales;
+
     pub use citeproc_db::ClusterId;
     pub use citeproc_io::{CiteMode, ClusterMode, Locator, Locators, NumberLike};
 }
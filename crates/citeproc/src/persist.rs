@@ -0,0 +1,272 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! Saving a processor's inputs, so a document can be reopened without inserting everything again.
+//!
+//! The saved state is a plain serde type, so it can be written as JSON or any binary format serde
+//! supports. Salsa's memoized results can't be saved, so a restored processor computes everything
+//! again the first time it is asked. What can be saved is the last output handed out by
+//! `batched_updates`: the app can show that immediately, and the first `batched_updates` after
+//! restoring only reports what actually changed.
+
+use crate::api::{string_id, IncludeUncited, SupportedFormat};
+use crate::prelude::*;
+use crate::processor::SavedBib;
+use citeproc_db::{ClusterNumber, ClusterRange, Uncited};
+use citeproc_io::output::markup::FormatOptions;
+use citeproc_proc::BibNumber;
+use csl::{Features, Lang, StyleError};
+use fnv::FnvHashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Bumped whenever `SavedState` changes in a way that older versions can't read.
+pub const SAVED_STATE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedState {
+    /// Always [SAVED_STATE_VERSION] when saved by this version of citeproc-rs.
+    pub version: u32,
+    /// The XML of the style.
+    pub style: String,
+    /// Features enabled through `InitOptions`, on top of the ones the style declares.
+    #[serde(default)]
+    pub csl_features: Vec<String>,
+    #[serde(default)]
+    pub test_mode: bool,
+    #[serde(default)]
+    pub locale_override: Option<Lang>,
+    /// Every locale that has been fetched or stored, so restoring doesn't have to fetch any.
+    #[serde(default)]
+    pub locales: Vec<(Lang, String)>,
+    pub format: SupportedFormat,
+    pub link_anchors: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left_margin_width: Option<u32>,
+    #[serde(default)]
+    pub bibliography_no_sort: bool,
+    #[serde(default)]
+    pub parse_literal_names: bool,
+    pub references: Vec<Reference>,
+    pub clusters: Vec<string_id::Cluster>,
+    pub cluster_order: Vec<string_id::ClusterPosition>,
+    #[serde(default)]
+    pub uncited: IncludeUncited,
+    /// The saved output doesn't cover these, so the first `batched_updates` after restoring
+    /// reports every section in full.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bibliography_sections: Vec<string_id::BibliographySection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<SavedOutput>,
+}
+
+/// The output as of the last `batched_updates`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedOutput {
+    pub clusters: FnvHashMap<SmartString, Arc<SmartString>>,
+    pub bibliography_ids: Vec<Atom>,
+    /// The citation number of each entry in `bibliography_ids`.
+    pub bibliography_numbers: FnvHashMap<Atom, BibNumber>,
+    pub bibliography: FnvHashMap<Atom, Arc<SmartString>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RestoreError {
+    #[error("saved state has version {0}, which this version of citeproc-rs cannot read")]
+    UnsupportedVersion(u32),
+    #[error("unknown CSL feature {0:?}")]
+    UnknownFeature(String),
+    #[error(transparent)]
+    Style(#[from] StyleError),
+    #[error(transparent)]
+    Reordering(#[from] string_id::ReorderingError),
+}
+
+/// What the current style was parsed from, kept so it can be saved.
+#[derive(Default)]
+pub(crate) struct StyleSource {
+    pub xml: String,
+    pub features: Option<Features>,
+    pub test_mode: bool,
+}
+
+impl Processor {
+    /// Saves the style, locales, references, clusters and options, and if `include_output` is
+    /// set, the output as of the last `batched_updates`.
+    pub fn save_state(&self, include_output: bool) -> SavedState {
+        let source = &self.style_source;
+        let mut csl_features = Vec::new();
+        if let Some(features) = source.features.as_ref() {
+            features.walk_feature_fields(|name, enabled| {
+                if enabled {
+                    csl_features.push(name.replace('_', "-"));
+                }
+            });
+        }
+        let mut locales: Vec<(Lang, String)> = self
+            .locale_input_langs()
+            .iter()
+            .map(|lang| {
+                (
+                    lang.clone(),
+                    String::clone(&self.locale_input_xml(lang.clone())),
+                )
+            })
+            .collect();
+        locales.sort_by(|a, b| a.0.cmp(&b.0));
+        let (format, format_options) = match self.formatter {
+            Markup::Html(o) => (SupportedFormat::Html, o),
+            Markup::Rtf(o) => (SupportedFormat::Rtf, o),
+            Markup::Plain(o) => (SupportedFormat::Plain, o),
+        };
+        let references = self
+            .all_keys()
+            .iter()
+            .map(|key| Reference::clone(&self.reference_input(key.clone())))
+            .collect();
+
+        let interner = self.interner.read().unwrap();
+        let resolve = |id: ClusterId| interner.resolve(id).map(SmartString::from);
+        let mut clusters: Vec<string_id::Cluster> = self
+            .all_cluster_ids()
+            .iter()
+            .filter_map(|&id| {
+                let cites = self
+                    .cluster_cites(id)
+                    .iter()
+                    .map(|cite_id| Cite::clone(&cite_id.lookup(self)))
+                    .collect();
+                Some(Cluster::new(resolve(id)?, cites, self.cluster_mode(id)))
+            })
+            .collect();
+        clusters.sort_by(|a, b| a.id.cmp(&b.id));
+        let cluster_order = self
+            .clusters_ordered()
+            .iter()
+            .filter_map(|&id| {
+                let note = match self.cluster_note_number(id)? {
+                    ClusterNumber::Note(n) => Some(n.note_number()),
+                    _ => None,
+                };
+                Some(string_id::ClusterPosition {
+                    id: Some(resolve(id)?),
+                    note,
+                })
+            })
+            .collect();
+        let uncited = match &*self.all_uncited() {
+            Uncited::All => IncludeUncited::All,
+            Uncited::Enumerated(ids) if ids.is_empty() => IncludeUncited::None,
+            Uncited::Enumerated(ids) => {
+                IncludeUncited::Specific(ids.iter().map(|id| id.to_string()).collect())
+            }
+        };
+        let bibliography_sections = self
+            .bibliography_sections()
+            .iter()
+            .map(|section| string_id::BibliographySection {
+                name: section.name.clone(),
+                clusters: section.clusters.as_ref().and_then(|range| {
+                    Some(ClusterRange {
+                        first: resolve(range.first)?,
+                        last: resolve(range.last)?,
+                    })
+                }),
+                filter: section.filter.clone(),
+            })
+            .collect();
+        let output = if include_output {
+            let last_clusters = self.last_clusters.lock().unwrap();
+            let last_bibliography = self.last_bibliography.lock().unwrap();
+            Some(SavedOutput {
+                clusters: last_clusters
+                    .iter()
+                    .filter_map(|(&id, built)| Some((resolve(id)?, built.clone())))
+                    .collect(),
+                bibliography_ids: last_bibliography.sorted_refs.0.clone(),
+                bibliography_numbers: last_bibliography.sorted_refs.1.clone(),
+                bibliography: (*last_bibliography.bib_entries).clone(),
+            })
+        } else {
+            None
+        };
+
+        SavedState {
+            version: SAVED_STATE_VERSION,
+            style: source.xml.clone(),
+            csl_features,
+            test_mode: source.test_mode,
+            locale_override: self.default_lang_override(),
+            locales,
+            format,
+            link_anchors: format_options.link_anchors,
+            left_margin_width: format_options.left_margin_width,
+            bibliography_no_sort: self.bibliography_no_sort(),
+            parse_literal_names: self.parse_literal_names(),
+            references,
+            clusters,
+            cluster_order,
+            uncited,
+            bibliography_sections,
+            output,
+        }
+    }
+
+    /// Creates a processor from a [SavedState]. The fetcher is only used for locales that weren't
+    /// saved, e.g. if the style is edited to use a new language.
+    pub fn restore_state(
+        state: SavedState,
+        fetcher: Option<Arc<dyn LocaleFetcher>>,
+    ) -> Result<Self, RestoreError> {
+        if state.version != SAVED_STATE_VERSION {
+            return Err(RestoreError::UnsupportedVersion(state.version));
+        }
+        let csl_features = if state.csl_features.is_empty() {
+            None
+        } else {
+            let names = state.csl_features.iter().map(String::as_str);
+            let features = csl::version::read_features(names)
+                .map_err(|name| RestoreError::UnknownFeature(name.into()))?;
+            Some(features)
+        };
+        let mut db = Processor::new(InitOptions {
+            style: &state.style,
+            format: state.format,
+            format_options: FormatOptions {
+                link_anchors: state.link_anchors,
+                left_margin_width: state.left_margin_width,
+            },
+            locale_override: state.locale_override,
+            fetcher,
+            csl_features,
+            test_mode: state.test_mode,
+            bibliography_no_sort: state.bibliography_no_sort,
+            parse_literal_names: state.parse_literal_names,
+            ..Default::default()
+        })?;
+        db.store_locales(state.locales);
+        db.reset_references(state.references);
+        db.include_uncited(state.uncited);
+        db.init_clusters_str(state.clusters);
+        db.set_cluster_order_str(&state.cluster_order)?;
+        db.set_bibliography_sections_str(state.bibliography_sections);
+
+        if let Some(output) = state.output {
+            let mut last_clusters = db.last_clusters.lock().unwrap();
+            for (id, built) in output.clusters {
+                last_clusters.insert(db.cluster_id(id), built);
+            }
+            let mut last_bibliography = db.last_bibliography.lock().unwrap();
+            *last_bibliography = SavedBib {
+                sorted_refs: Arc::new((output.bibliography_ids, output.bibliography_numbers)),
+                bib_entries: Arc::new(output.bibliography),
+            };
+        }
+        Ok(db)
+    }
+}
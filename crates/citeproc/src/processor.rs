@@ -8,18 +8,24 @@
 #![allow(clippy::large_enum_variant)]
 #![allow(clippy::enum_variant_names)]
 
+use crate::cancel::{Budget, CancelHandle};
+use crate::persist::StyleSource;
 use crate::prelude::*;
+use crate::transaction::History;
 
 use crate::api::{
-    string_id, BibEntry, BibliographyMeta, BibliographyUpdate, ClusterPosition, IncludeUncited,
-    ReorderingError, SecondFieldAlign, UpdateSummary,
+    string_id, BibEntry, BibliographyMeta, BibliographyUpdate, ClusterPosition, Diagnostic,
+    DiagnosticKind, IncludeUncited, ReorderingError, SecondFieldAlign, UpdateSummary,
 };
 use citeproc_db::{
-    CiteData, CiteDatabaseStorage, HasFetcher, LocaleDatabaseStorage, StyleDatabaseStorage, Uncited,
+    BibliographySection, CiteData, CiteDatabaseStorage, ClusterRange, HasFetcher,
+    LocaleDatabaseStorage, StyleDatabaseStorage, Uncited,
 };
 use citeproc_io::output::markup::FormatOptions;
 use citeproc_proc::db::IrDatabaseStorage;
-use citeproc_proc::BibNumber;
+use citeproc_proc::diagnostics::{style_problems, StyleProblemKind};
+use citeproc_proc::explain::DisambExplanation;
+use citeproc_proc::{is_east_asian_lang, BibNumber, CiteSpan};
 use indexmap::set::IndexSet;
 
 use salsa::{Database, Durability, SweepStrategy};
@@ -27,8 +33,9 @@ use salsa::{Database, Durability, SweepStrategy};
 use salsa::{ParallelDatabase, Snapshot};
 use std::sync::Arc;
 use std::sync::{Mutex, RwLock};
+use std::time::Instant;
 
-use csl::{Lang, Style, StyleError};
+use csl::{Lang, LocaleSource, Style, StyleError};
 
 use citeproc_io::output::{markup::Markup, OutputFormat};
 use citeproc_io::{Cite, ClusterMode, Reference, SmartString};
@@ -44,9 +51,9 @@ type MarkupBuild = <Markup as OutputFormat>::Build;
 type MarkupOutput = <Markup as OutputFormat>::Output;
 use fnv::{FnvHashMap, FnvHashSet};
 
-struct SavedBib {
-    sorted_refs: Arc<(Vec<Atom>, FnvHashMap<Atom, BibNumber>)>,
-    bib_entries: Arc<FnvHashMap<Atom, Arc<MarkupOutput>>>,
+pub(crate) struct SavedBib {
+    pub(crate) sorted_refs: Arc<(Vec<Atom>, FnvHashMap<Atom, BibNumber>)>,
+    pub(crate) bib_entries: Arc<FnvHashMap<Atom, Arc<MarkupOutput>>>,
 }
 
 impl SavedBib {
@@ -56,6 +63,32 @@ impl SavedBib {
             bib_entries: Arc::new(Default::default()),
         }
     }
+
+    /// Replaces the saved bibliography, returning what changed.
+    fn save_and_diff(
+        &mut self,
+        sorted_refs: Arc<(Vec<Atom>, FnvHashMap<Atom, BibNumber>)>,
+        new: Arc<FnvHashMap<Atom, Arc<MarkupOutput>>>,
+    ) -> Option<BibliographyUpdate> {
+        let old = std::mem::replace(self, SavedBib::new());
+        let mut update = BibliographyUpdate::new();
+        for (k, v) in new.iter() {
+            let old_v = old.bib_entries.get(k);
+            if Some(v) != old_v {
+                update.updated_entries.insert(k.clone(), v.clone());
+            }
+        }
+        self.bib_entries = new;
+        if sorted_refs.0 != old.sorted_refs.0 {
+            update.entry_ids = Some(sorted_refs.0.clone());
+        }
+        self.sorted_refs = sorted_refs;
+        if update.updated_entries.is_empty() && update.entry_ids.is_none() {
+            None
+        } else {
+            Some(update)
+        }
+    }
 }
 
 #[salsa::database(
@@ -67,11 +100,19 @@ impl SavedBib {
 pub struct Processor {
     storage: salsa::Storage<Self>,
     pub fetcher: Arc<dyn LocaleFetcher>,
+    /// Used by [Processor::fetch_locales].
+    pub async_fetcher: Option<Arc<dyn AsyncLocaleFetcher>>,
+    parse_cache: Option<Arc<ParseCache>>,
     pub formatter: Markup,
     format_options: FormatOptions,
-    last_bibliography: Arc<Mutex<SavedBib>>,
-    last_clusters: Arc<Mutex<FnvHashMap<ClusterId, Arc<SmartString>>>>,
-    interner: Arc<RwLock<Interner>>,
+    pub(crate) last_bibliography: Arc<Mutex<SavedBib>>,
+    pub(crate) last_clusters: Arc<Mutex<FnvHashMap<ClusterId, Arc<SmartString>>>>,
+    pub(crate) last_sections: Arc<Mutex<FnvHashMap<SmartString, SavedBib>>>,
+    reported_diagnostics: Arc<Mutex<FnvHashSet<Diagnostic>>>,
+    pub(crate) interner: Arc<RwLock<Interner>>,
+    pub(crate) style_source: Arc<StyleSource>,
+    pub(crate) history: History,
+    cancel: CancelHandle,
     preview_cluster_id: ClusterId,
 }
 
@@ -83,11 +124,18 @@ impl ParallelDatabase for Processor {
         Snapshot::new(Processor {
             storage: self.storage.snapshot(),
             fetcher: self.fetcher.clone(),
+            async_fetcher: self.async_fetcher.clone(),
+            parse_cache: self.parse_cache.clone(),
             format_options: self.format_options.clone(),
             formatter: self.formatter.clone(),
             last_bibliography: self.last_bibliography.clone(),
             last_clusters: self.last_clusters.clone(),
+            last_sections: self.last_sections.clone(),
+            reported_diagnostics: self.reported_diagnostics.clone(),
             interner: self.interner.clone(),
+            style_source: self.style_source.clone(),
+            history: History::default(),
+            cancel: self.cancel.clone(),
             preview_cluster_id: self.preview_cluster_id,
         })
     }
@@ -97,6 +145,9 @@ impl HasFetcher for Processor {
     fn get_fetcher(&self) -> Arc<dyn LocaleFetcher> {
         self.fetcher.clone()
     }
+    fn get_parse_cache(&self) -> Option<Arc<ParseCache>> {
+        self.parse_cache.clone()
+    }
 }
 
 impl ImplementationDetails for Processor {
@@ -131,10 +182,17 @@ pub struct InitOptions<'a> {
     pub format_options: FormatOptions,
     /// A full independent style.
     pub style: &'a str,
-    /// You might get this from a dependent style via `StyleMeta::parse(dependent_xml_string)`
+    /// You might get this from a dependent style via `StyleMeta::parse(dependent_xml_string)`, or
+    /// let [Processor::new_resolving_dependent] do it for you.
     pub locale_override: Option<Lang>,
-    /// Mechanism for fetching the locale you provide, if necessary.
+    /// Mechanism for fetching the locale you provide, if necessary. Defaults to the bundled
+    /// en-US, or every bundled locale with the `bundled-locales` feature.
     pub fetcher: Option<Arc<dyn LocaleFetcher>>,
+    /// Mechanism for fetching locales asynchronously, with [Processor::fetch_locales].
+    pub async_fetcher: Option<Arc<dyn AsyncLocaleFetcher>>,
+    /// Parsed styles and locales shared with other processors. Without one, the style and
+    /// locales are parsed from scratch.
+    pub parse_cache: Option<Arc<ParseCache>>,
 
     /// Which csl features to enable globally. Using the `<features>` declaration is highly
     /// preferred, but unfortunately it is not part of CSL yet.
@@ -146,6 +204,11 @@ pub struct InitOptions<'a> {
     /// Disables sorting on the bibliography (enabled by default)
     pub bibliography_no_sort: bool,
 
+    /// Splits `{ "literal": "..." }` names into family and given names, particles and suffixes,
+    /// leaving anything that looks like an institution alone. Off by default, because it is
+    /// guesswork.
+    pub parse_literal_names: bool,
+
     #[doc(hidden)]
     pub use_default_default: private::CannotConstruct,
 }
@@ -163,12 +226,19 @@ impl Processor {
         let mut db = Processor {
             storage: Default::default(),
             fetcher,
+            async_fetcher: None,
+            parse_cache: None,
             formatter: Markup::default(),
             format_options: FormatOptions::default(),
             last_bibliography: Arc::new(Mutex::new(SavedBib::new())),
             last_clusters: Arc::new(Mutex::new(Default::default())),
+            last_sections: Arc::new(Mutex::new(Default::default())),
+            reported_diagnostics: Arc::new(Mutex::new(Default::default())),
             // This uses DefaultBackend, which is
             interner: Arc::new(RwLock::new(interner)),
+            style_source: Arc::new(StyleSource::default()),
+            history: History::default(),
+            cancel: CancelHandle::default(),
             preview_cluster_id,
         };
         citeproc_db::safe_default(&mut db);
@@ -185,29 +255,40 @@ impl Processor {
             style,
             locale_override,
             fetcher,
+            async_fetcher,
+            parse_cache,
             format,
             format_options,
             csl_features,
             test_mode,
             bibliography_no_sort,
+            parse_literal_names,
             use_default_default: _,
         } = options;
 
-        let fetcher =
-            fetcher.unwrap_or_else(|| Arc::new(citeproc_db::PredefinedLocales::bundled_en_us()));
+        let fetcher = fetcher.unwrap_or_else(default_locales);
         let mut db = Processor::safe_default(fetcher);
-        let style = Style::parse_with_opts(
-            &style,
+        db.async_fetcher = async_fetcher;
+        db.parse_cache = parse_cache;
+        let source = StyleSource {
+            xml: style.into(),
+            features: csl_features,
+            test_mode,
+        };
+        let style = db.parse_style(
+            &source.xml,
             csl::ParseOptions {
                 allow_no_info: test_mode,
-                features: csl_features,
+                features: source.features.clone(),
                 ..Default::default()
             },
         )?;
-        db.set_style_with_durability(Arc::new(style), Durability::HIGH);
+        db.set_style_with_durability(style, Durability::HIGH);
+        db.style_source = Arc::new(source);
         db.set_output_format(format, format_options);
         db.set_default_lang_override_with_durability(locale_override, Durability::HIGH);
         db.set_bibliography_no_sort_with_durability(bibliography_no_sort, Durability::HIGH);
+        db.set_parse_literal_names_with_durability(parse_literal_names, Durability::HIGH);
         Ok(db)
     }
 
@@ -225,20 +306,52 @@ impl Processor {
 
     /// Sets the CSL style to be used. Will require nearly everything to be recomputed, so call sparingly.
     pub fn set_style_text(&mut self, style_text: &str) -> Result<(), StyleError> {
-        let style = Style::parse(style_text)?;
-        self.set_style_with_durability(Arc::new(style), Durability::HIGH);
+        let style = self.parse_style(style_text, Default::default())?;
+        self.set_style_with_durability(style, Durability::HIGH);
+        self.style_source = Arc::new(StyleSource {
+            xml: style_text.into(),
+            ..Default::default()
+        });
         Ok(())
     }
 
+    fn parse_style(&self, xml: &str, options: csl::ParseOptions) -> Result<Arc<Style>, StyleError> {
+        match &self.parse_cache {
+            Some(cache) => cache.style(xml, options),
+            None => Ok(Arc::new(Style::parse_with_opts(xml, options)?)),
+        }
+    }
+
     #[cfg(feature = "rayon")]
     fn snap(&self) -> Snap {
         Snap(self.snapshot())
     }
 
+    /// A handle that can interrupt [Processor::compute] and the `batched_updates` family from
+    /// another thread.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Renders every cluster that changed. If the [CancelHandle] fires first, this returns the
+    /// clusters rendered so far and logs a warning; use [Processor::batched_updates_until] to find
+    /// out whether a render was interrupted.
+    pub fn compute(&self) -> Vec<(ClusterId, Arc<SmartString>)> {
+        let budget = Budget::new(self.cancel.clone(), None);
+        let result = self.compute_within(&budget);
+        if budget.was_exhausted() {
+            log::warn!(
+                "compute() was cancelled after rendering {} clusters",
+                result.len()
+            );
+        }
+        result
+    }
+
     // TODO: This might not play extremely well with Salsa's garbage collector,
     // which will have a new revision number for each built_cluster call.
     // Probably better to have this as a real query.
-    pub fn compute(&self) -> Vec<(ClusterId, Arc<SmartString>)> {
+    fn compute_within(&self, budget: &Budget) -> Vec<(ClusterId, Arc<SmartString>)> {
         fn upsert_diff(
             into_h: &mut FnvHashMap<ClusterId, Arc<SmartString>>,
             id: ClusterId,
@@ -273,12 +386,19 @@ impl Processor {
             cite_ids
                 .par_iter()
                 .for_each_with(self.snap(), |snap, &cite_id| {
-                    snap.0.ir_gen2_add_given_name(cite_id);
+                    if !budget.is_exhausted() {
+                        snap.0.ir_gen2_add_given_name(cite_id);
+                    }
                 });
-            self.year_suffixes();
+            if !budget.is_exhausted() {
+                self.year_suffixes();
+            }
             clusters
                 .par_iter()
                 .map_with(self.snap(), |snap, cluster| {
+                    if budget.is_exhausted() {
+                        return None;
+                    }
                     let built = snap.0.built_cluster(cluster.id);
                     let mut into_hashmap = snap.0.last_clusters.lock().unwrap();
                     upsert_diff(into_hashmap.deref_mut(), cluster.id, built)
@@ -291,6 +411,7 @@ impl Processor {
             let mut into_hashmap = self.last_clusters.lock().unwrap();
             clusters
                 .iter()
+                .take_while(|_| !budget.is_exhausted())
                 .filter_map(|cluster| {
                     let built = self.built_cluster(cluster.id);
                     upsert_diff(&mut into_hashmap, cluster.id, built)
@@ -300,34 +421,59 @@ impl Processor {
 
         // Run salsa GC.
         self.sweep_all(SweepStrategy::discard_outdated());
+        // A cancellation that arrives after this point is for the next render.
+        self.cancel.reset();
         result
     }
 
     pub fn batched_updates(&self) -> UpdateSummary {
-        let delta = self.compute();
+        self.batched_updates_until(None)
+    }
+
+    /// Like [Processor::batched_updates], but stops rendering at `deadline`, and marks the
+    /// summary `interrupted`. Whatever was not rendered in time will be in the next summary.
+    pub fn batched_updates_until(&self, deadline: Option<Instant>) -> UpdateSummary {
+        let budget = Budget::new(self.cancel.clone(), deadline);
+        let delta = self.compute_within(&budget);
+        if budget.was_exhausted() {
+            return UpdateSummary {
+                clusters: delta,
+                interrupted: true,
+                ..Default::default()
+            };
+        }
         UpdateSummary {
             clusters: delta,
             bibliography: self.save_and_diff_bibliography(),
+            bibliography_sections: self.save_and_diff_sections(),
+            interrupted: false,
         }
     }
 
     pub fn batched_updates_str(&self) -> string_id::UpdateSummary {
-        let delta = self.compute();
-        let mut delta_str = Vec::with_capacity(delta.len());
+        self.batched_updates_str_until(None)
+    }
+
+    pub fn batched_updates_str_until(&self, deadline: Option<Instant>) -> string_id::UpdateSummary {
+        let summary = self.batched_updates_until(deadline);
+        let mut clusters = Vec::with_capacity(summary.clusters.len());
         let interner = self.interner.read().unwrap();
-        for (cid, neu) in delta {
+        for (cid, neu) in summary.clusters {
             if let Some(resolved) = interner.resolve(cid) {
-                delta_str.push((SmartString::from(resolved), neu));
+                clusters.push((SmartString::from(resolved), neu));
             }
         }
         string_id::UpdateSummary {
-            clusters: delta_str,
-            bibliography: self.save_and_diff_bibliography(),
+            clusters,
+            bibliography: summary.bibliography,
+            bibliography_sections: summary.bibliography_sections,
+            interrupted: summary.interrupted,
         }
     }
 
+    /// Renders everything. The [CancelHandle] can't interrupt this.
     pub fn drain(&mut self) {
-        let _ = self.compute();
+        let _ = self.compute_within(&Budget::new(CancelHandle::default(), None));
     }
 
     pub fn clear_references(&mut self) {
@@ -536,7 +682,7 @@ impl Processor {
         self.set_cluster_mode(cluster_id, mode);
     }
 
-    fn intern_cluster(&mut self, cluster: string_id::Cluster) -> Cluster {
+    pub(crate) fn intern_cluster(&mut self, cluster: string_id::Cluster) -> Cluster {
         let string_id::Cluster { id, cites, mode } = cluster;
         let interned = self.cluster_id(id);
         Cluster {
@@ -573,6 +719,43 @@ impl Processor {
         }
     }
 
+    /// Like [Processor::get_cluster], but also says which part of the output each cite produced.
+    pub fn get_cluster_spans(
+        &self,
+        cluster_id: ClusterId,
+    ) -> Option<(Arc<MarkupOutput>, Vec<CiteSpan>)> {
+        if self.cluster_note_number(cluster_id).is_some() {
+            Some(citeproc_proc::built_cluster_spans(self, cluster_id))
+        } else {
+            None
+        }
+    }
+
+    /// Explains how the disambiguation passes treated a cite: which other references it could
+    /// be mistaken for after each one, which pass resolved it, and which names were expanded.
+    /// `cite_index` counts cites in the order they were inserted into the cluster.
+    ///
+    /// Returns None if the cluster has not been assigned a position in the document, or the cite
+    /// does not exist or cites a missing reference.
+    pub fn explain_disambiguation(
+        &self,
+        cluster_id: ClusterId,
+        cite_index: u32,
+    ) -> Option<DisambExplanation> {
+        self.cluster_note_number(cluster_id)?;
+        let cite_id = *self.cluster_cites(cluster_id).get(cite_index as usize)?;
+        citeproc_proc::explain::explain_disambiguation(self, cite_id)
+    }
+
+    pub fn explain_disambiguation_str(
+        &self,
+        cluster_id: &str,
+        cite_index: u32,
+    ) -> Option<DisambExplanation> {
+        let id = self.cluster_id(cluster_id);
+        self.explain_disambiguation(id, cite_index)
+    }
+
     pub fn get_cluster_note_number(&self, cluster_id: ClusterId) -> Option<ClusterNumber> {
         self.cluster_note_number(cluster_id)
     }
@@ -583,27 +766,38 @@ impl Processor {
         self.get_cluster(id)
     }
 
+    /// Returns None if the cluster has not been assigned a position in the document.
+    pub fn get_cluster_spans_str(
+        &self,
+        cluster_id: &str,
+    ) -> Option<(Arc<MarkupOutput>, Vec<CiteSpan>)> {
+        let id = self.cluster_id(cluster_id);
+        self.get_cluster_spans(id)
+    }
+
     pub fn get_bib_item(&self, ref_id: Atom) -> Arc<MarkupOutput> {
         self.bib_item(ref_id)
     }
 
     pub fn get_bibliography_meta(&self) -> Option<BibliographyMeta> {
         let style = self.get_style();
-        style.bibliography.as_ref().map(|bib| {
-            BibliographyMeta {
-                // TODO
-                max_offset: 0,
-                entry_spacing: bib.entry_spacing,
-                line_spacing: bib.line_spacing,
-                hanging_indent: bib.hanging_indent,
-                // To avoid serde derive in csl
-                second_field_align: bib.second_field_align.as_ref().map(|s| match s {
-                    csl::style::SecondFieldAlign::Flush => SecondFieldAlign::Flush,
-                    csl::style::SecondFieldAlign::Margin => SecondFieldAlign::Margin,
-                }),
-                format_meta: self.get_formatter().meta(),
-            }
-        })
+        let bib = style.bibliography.as_ref()?;
+        Some(self.bibliography_meta(bib, self.bibliography_max_offset()))
+    }
+
+    fn bibliography_meta(&self, bib: &csl::Bibliography, max_offset: u32) -> BibliographyMeta {
+        BibliographyMeta {
+            max_offset,
+            entry_spacing: bib.entry_spacing,
+            line_spacing: bib.line_spacing,
+            hanging_indent: bib.hanging_indent,
+            // To avoid serde derive in csl
+            second_field_align: bib.second_field_align.as_ref().map(|s| match s {
+                csl::style::SecondFieldAlign::Flush => SecondFieldAlign::Flush,
+                csl::style::SecondFieldAlign::Margin => SecondFieldAlign::Margin,
+            }),
+            format_meta: self.get_formatter().bibliography_meta(bib, max_offset),
+        }
     }
 
     fn save_and_diff_bibliography(&self) -> Option<BibliographyUpdate> {
@@ -611,26 +805,35 @@ impl Processor {
             return None;
         }
         let mut last_bibliography = self.last_bibliography.lock().unwrap();
-        let new = self.get_bibliography_map();
-        let old = std::mem::replace(&mut *last_bibliography, SavedBib::new());
-        let mut update = BibliographyUpdate::new();
-        for (k, v) in new.iter() {
-            let old_v = old.bib_entries.get(k);
-            if Some(v) != old_v {
-                update.updated_entries.insert(k.clone(), v.clone());
-            }
-        }
-        last_bibliography.bib_entries = new;
-        let sorted_refs = self.sorted_refs();
-        if sorted_refs.0 != old.sorted_refs.0 {
-            update.entry_ids = Some(sorted_refs.0.clone());
+        last_bibliography.save_and_diff(self.sorted_refs(), self.get_bibliography_map())
+    }
+
+    fn save_and_diff_sections(&self) -> FnvHashMap<SmartString, BibliographyUpdate> {
+        let mut updates = FnvHashMap::default();
+        let mut last_sections = self.last_sections.lock().unwrap();
+        if self.get_style().bibliography.is_none() {
+            last_sections.clear();
+            return updates;
         }
-        last_bibliography.sorted_refs = sorted_refs;
-        if update.updated_entries.is_empty() && update.entry_ids.is_none() {
-            None
-        } else {
-            Some(update)
+        let sections = self.bibliography_sections();
+        last_sections.retain(|name, _| sections.iter().any(|s| &s.name == name));
+        for section in sections.iter() {
+            let name = section.name.clone();
+            let (sorted_refs, bib_map) = match (
+                self.section_sorted_refs(name.clone()),
+                self.section_bibliography_map(name.clone()),
+            ) {
+                (Some(sorted_refs), Some(bib_map)) => (sorted_refs, bib_map),
+                _ => continue,
+            };
+            let saved = last_sections
+                .entry(name.clone())
+                .or_insert_with(SavedBib::new);
+            if let Some(update) = saved.save_and_diff(sorted_refs, bib_map) {
+                updates.insert(name, update);
+            }
         }
+        updates
     }
 
     pub fn all_clusters(&self) -> FnvHashMap<ClusterId, Arc<MarkupOutput>> {
@@ -661,22 +864,49 @@ impl Processor {
     }
 
     pub fn get_bibliography(&self) -> Vec<BibEntry> {
-        let bib_map = self.get_bibliography_map();
-        self.sorted_refs()
-            .0
-            .iter()
-            .filter_map(|k| bib_map.get(k).map(|v| (k, v)))
-            .map(|(k, v)| BibEntry {
-                id: k.clone(),
-                value: if v.is_empty() {
-                    Arc::new(SmartString::from(
-                        "[CSL STYLE ERROR: reference with no printed form.]",
-                    ))
-                } else {
-                    v.clone()
-                },
+        bib_entries(&self.sorted_refs().0, &self.get_bibliography_map())
+    }
+
+    /// Defines the bibliography sections, replacing any previous ones. Each section is sorted and
+    /// numbered on its own, and its changes are reported separately by `batched_updates`.
+    ///
+    /// Cites in a section's cluster range render the citation numbers from that section. Other
+    /// cites use the numbers from the full bibliography.
+    pub fn set_bibliography_sections(&mut self, sections: Vec<BibliographySection>) {
+        self.set_bibliography_sections_with_durability(Arc::new(sections), Durability::MEDIUM);
+    }
+
+    pub fn set_bibliography_sections_str(&mut self, sections: Vec<string_id::BibliographySection>) {
+        let sections = sections
+            .into_iter()
+            .map(|section| BibliographySection {
+                name: section.name,
+                clusters: section.clusters.map(|range| ClusterRange {
+                    first: self.cluster_id(range.first),
+                    last: self.cluster_id(range.last),
+                }),
+                filter: section.filter,
             })
-            .collect()
+            .collect();
+        self.set_bibliography_sections(sections);
+    }
+
+    /// Returns None if there is no section with that name.
+    pub fn get_bibliography_section(&self, name: &str) -> Option<Vec<BibEntry>> {
+        let name = SmartString::from(name);
+        let sorted_refs = self.section_sorted_refs(name.clone())?;
+        let bib_map = self.section_bibliography_map(name)?;
+        Some(bib_entries(&sorted_refs.0, &bib_map))
+    }
+
+    /// Returns None if there is no section with that name, or the style has no bibliography.
+    pub fn get_bibliography_section_meta(&self, name: &str) -> Option<BibliographyMeta> {
+        let style = self.get_style();
+        let bib = style.bibliography.as_ref()?;
+        // The widest left-margin field depends on which entries the section has, and how they
+        // are numbered within it.
+        let max_offset = self.section_bibliography_max_offset(SmartString::from(name))?;
+        Some(self.bibliography_meta(bib, max_offset))
     }
 
     pub fn get_reference(&self, ref_id: Atom) -> Option<Arc<Reference>> {
@@ -696,9 +926,18 @@ impl Processor {
         self.set_locale_input_langs(Arc::new(langs));
     }
 
+    /// The default language and its fallbacks, plus the language of any Chinese, Japanese or
+    /// Korean reference, whose own locale supplies the terms between its CJK names.
     pub fn get_langs_in_use(&self) -> Vec<Lang> {
         let dl = self.default_lang();
         let mut vec: Vec<Lang> = dl.iter_fetchable_langs().collect();
+        for key in self.all_keys().iter() {
+            let lang = self
+                .reference(key.clone())
+                .and_then(|refr| refr.language.clone())
+                .filter(is_east_asian_lang);
+            vec.extend(lang);
+        }
         vec.sort();
         vec.dedup();
         vec
@@ -708,6 +947,101 @@ impl Processor {
         let langs = self.locale_input_langs();
         langs.contains(lang)
     }
+
+    /// Every problem currently affecting the output: cites whose references are missing, a
+    /// locale that couldn't be loaded, and parts of the style that are broken or unsupported.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let missing = |ref_id: &Atom, cluster_id, cite_index| Diagnostic {
+            kind: DiagnosticKind::MissingReference,
+            message: format!("no reference with id `{}`", ref_id),
+            cluster_id,
+            cite_index,
+            ref_id: Some(ref_id.clone()),
+            style_location: None,
+        };
+        let keys = self.all_keys();
+        for &cluster_id in self.clusters_ordered().iter() {
+            for (ix, cite_id) in self.cluster_cites(cluster_id).iter().enumerate() {
+                let cite = cite_id.lookup(self);
+                if !keys.contains(&cite.ref_id) {
+                    diagnostics.push(missing(&cite.ref_id, Some(cluster_id), Some(ix as u32)));
+                }
+            }
+        }
+        if let Uncited::Enumerated(uncited) = &*self.all_uncited() {
+            for ref_id in uncited.iter().filter(|id| !keys.contains(*id)) {
+                diagnostics.push(missing(ref_id, None, None));
+            }
+        }
+        for ref_id in keys.iter() {
+            for error in self.raw_date_errors(ref_id.clone()).iter() {
+                diagnostics.push(Diagnostic {
+                    kind: DiagnosticKind::UnparsedRawDate,
+                    message: error.to_string(),
+                    cluster_id: None,
+                    cite_index: None,
+                    ref_id: Some(ref_id.clone()),
+                    style_location: None,
+                });
+            }
+        }
+        // en-US is bundled, and used as the fallback for everything else.
+        let lang = self.default_lang();
+        if lang != Lang::en_us()
+            && self
+                .parsed_locale(LocaleSource::File(lang.clone()))
+                .is_none()
+        {
+            diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::LocaleUnavailable,
+                message: format!("locale `{}` is not available", lang),
+                cluster_id: None,
+                cite_index: None,
+                ref_id: None,
+                style_location: None,
+            });
+        }
+        for problem in style_problems(&self.style()) {
+            let kind = match problem.kind {
+                StyleProblemKind::UndefinedMacro(_) => DiagnosticKind::UndefinedMacro,
+                StyleProblemKind::RecursiveMacro(_) => DiagnosticKind::RecursiveMacro,
+                StyleProblemKind::UnsupportedMatchNand => DiagnosticKind::UnsupportedCondition,
+            };
+            diagnostics.push(Diagnostic {
+                kind,
+                message: problem.message(),
+                cluster_id: None,
+                cite_index: None,
+                ref_id: None,
+                style_location: Some(problem.location),
+            });
+        }
+        diagnostics
+    }
+
+    /// The [Processor::diagnostics] that weren't returned by the last call to this. A problem
+    /// that is fixed and later comes back is returned again.
+    pub fn take_diagnostics(&self) -> Vec<Diagnostic> {
+        let current = self.diagnostics();
+        let mut reported = self.reported_diagnostics.lock().unwrap();
+        let fresh = current
+            .iter()
+            .filter(|d| !reported.contains(*d))
+            .cloned()
+            .collect();
+        *reported = current.into_iter().collect();
+        fresh
+    }
+
+    pub fn take_diagnostics_str(&self) -> Vec<string_id::Diagnostic> {
+        let diagnostics = self.take_diagnostics();
+        let interner = self.interner.read().unwrap();
+        diagnostics
+            .iter()
+            .map(|d| d.to_external(&interner))
+            .collect()
+    }
 }
 
 /// Stores all the relevant #[salsa::input] entries from CiteDatabase.
@@ -1011,3 +1345,30 @@ impl Processor {
         Ok(())
     }
 }
+
+fn bib_entries(sorted: &[Atom], bib_map: &FnvHashMap<Atom, Arc<MarkupOutput>>) -> Vec<BibEntry> {
+    sorted
+        .iter()
+        .filter_map(|k| bib_map.get(k).map(|v| (k, v)))
+        .map(|(k, v)| BibEntry {
+            id: k.clone(),
+            value: if v.is_empty() {
+                Arc::new(SmartString::from(
+                    "[CSL STYLE ERROR: reference with no printed form.]",
+                ))
+            } else {
+                v.clone()
+            },
+        })
+        .collect()
+}
+
+#[cfg(feature = "bundled-locales")]
+fn default_locales() -> Arc<dyn LocaleFetcher> {
+    Arc::new(citeproc_db::PredefinedLocales::bundled_all())
+}
+
+#[cfg(not(feature = "bundled-locales"))]
+fn default_locales() -> Arc<dyn LocaleFetcher> {
+    Arc::new(citeproc_db::PredefinedLocales::bundled_en_us())
+}
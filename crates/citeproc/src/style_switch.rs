@@ -0,0 +1,125 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! Rendering the whole document with a candidate style, to show what switching would change.
+//!
+//! Salsa inputs can only be set on the one database, and setting the style there would throw away
+//! everything computed for the current one. So the candidate is rendered by a second processor
+//! built from the same inputs, the same way [Processor::restore_state] builds one.
+
+use crate::api::{string_id, BibEntryChange, ClusterChange, StylePreview};
+use crate::persist::RestoreError;
+use crate::prelude::*;
+use csl::StyleMeta;
+use fnv::FnvHashMap;
+use std::sync::Arc;
+
+impl Processor {
+    /// Renders every cluster in the document, and the bibliography, as they would be with
+    /// `style`, and reports the differences from the current output. The processor itself is not
+    /// changed.
+    ///
+    /// This renders the whole document from scratch, so it costs about as much as opening it.
+    pub fn preview_style_switch(&self, style: &str) -> Result<StylePreview, RestoreError> {
+        let preview = self.preview_style_switch_str(style)?;
+        Ok(StylePreview {
+            clusters: preview
+                .clusters
+                .into_iter()
+                .map(|change| ClusterChange {
+                    id: self.cluster_id(change.id),
+                    before: change.before,
+                    after: change.after,
+                })
+                .collect(),
+            bibliography: preview.bibliography,
+            entry_ids: preview.entry_ids,
+        })
+    }
+
+    pub fn preview_style_switch_str(
+        &self,
+        style: &str,
+    ) -> Result<string_id::StylePreview, RestoreError> {
+        let meta = StyleMeta::parse(style)?;
+        let mut state = self.save_state(false);
+        state.style = style.into();
+        // Like set_style_text, keep a locale override the user set, and drop the features that
+        // came with the current style.
+        state.locale_override = state.locale_override.or(meta.default_locale);
+        state.csl_features = Vec::new();
+        let candidate = Processor::restore_state(state, Some(self.fetcher.clone()))?;
+
+        let mut clusters = Vec::new();
+        {
+            let interner = self.interner.read().unwrap();
+            for &id in self.clusters_ordered().iter() {
+                let name = match interner.resolve(id) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                let before = self.get_cluster(id);
+                let after = candidate.get_cluster(candidate.cluster_id(name));
+                if let (Some(before), Some(after)) = (before, after) {
+                    if before != after {
+                        clusters.push(ClusterChange {
+                            id: SmartString::from(name),
+                            before,
+                            after,
+                        });
+                    }
+                }
+            }
+        }
+
+        let bib_before = self.bibliography_if_any();
+        let bib_after = candidate.bibliography_if_any();
+        let before_map: FnvHashMap<&Atom, &Arc<SmartString>> =
+            bib_before.iter().map(|e| (&e.id, &e.value)).collect();
+        let after_map: FnvHashMap<&Atom, &Arc<SmartString>> =
+            bib_after.iter().map(|e| (&e.id, &e.value)).collect();
+        let mut bibliography = Vec::new();
+        for entry in &bib_after {
+            let before = before_map.get(&entry.id).cloned();
+            if before != Some(&entry.value) {
+                bibliography.push(BibEntryChange {
+                    id: entry.id.clone(),
+                    before: before.cloned(),
+                    after: Some(entry.value.clone()),
+                });
+            }
+        }
+        for entry in &bib_before {
+            if !after_map.contains_key(&entry.id) {
+                bibliography.push(BibEntryChange {
+                    id: entry.id.clone(),
+                    before: Some(entry.value.clone()),
+                    after: None,
+                });
+            }
+        }
+        let ids = |entries: &[BibEntry]| entries.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        let entry_ids = if ids(&bib_before) != ids(&bib_after) {
+            Some(ids(&bib_after))
+        } else {
+            None
+        };
+
+        Ok(StylePreview {
+            clusters,
+            bibliography,
+            entry_ids,
+        })
+    }
+
+    fn bibliography_if_any(&self) -> Vec<BibEntry> {
+        if self.get_style().bibliography.is_some() {
+            self.get_bibliography()
+        } else {
+            Vec::new()
+        }
+    }
+}
@@ -417,3 +417,985 @@ mod terms {
         )
     }
 }
+
+mod diagnostics {
+    use super::*;
+
+    #[test]
+    fn missing_reference() {
+        let mut db = test_db(None);
+        insert_basic_refs(&mut db, &["one"]);
+        insert_ascending_notes(&mut db, &["one", "two"]);
+        let two = cid(&mut db, 2);
+        let found = db.take_diagnostics();
+        assert_eq!(
+            found,
+            vec![Diagnostic {
+                kind: DiagnosticKind::MissingReference,
+                message: "no reference with id `two`".into(),
+                cluster_id: Some(two),
+                cite_index: Some(0),
+                ref_id: Some(Atom::from("two")),
+                style_location: None,
+            }]
+        );
+        // already reported
+        assert_eq!(db.take_diagnostics(), vec![]);
+        assert_eq!(db.diagnostics().len(), 1);
+        insert_basic_refs(&mut db, &["two"]);
+        assert_eq!(db.diagnostics(), vec![]);
+    }
+
+    #[test]
+    fn style_problems() {
+        let db = test_db(Some(
+            r#"<style version="1.0" class="in-text">
+                <macro name="loop"><text macro="loop" /></macro>
+                <citation><layout><text macro="loop" /></layout></citation>
+            </style>"#,
+        ));
+        let found = db.take_diagnostics_str();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, DiagnosticKind::RecursiveMacro);
+        assert_eq!(found[0].style_location.as_deref(), Some("macro \"loop\""));
+    }
+
+    #[test]
+    fn unparsed_raw_date() {
+        let mut db = test_db(None);
+        let mut refr = Reference::empty(Atom::from("one"), CslType::Book);
+        refr.raw_dates
+            .insert(DateVariable::Issued, "sometime soon".into());
+        db.insert_reference(refr);
+        let found = db.take_diagnostics();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].kind, DiagnosticKind::UnparsedRawDate);
+        assert_eq!(found[0].ref_id, Some(Atom::from("one")));
+    }
+}
+
+mod cluster_spans {
+    use super::*;
+
+    #[test]
+    fn prefixes_and_suffixes() {
+        let mut db = test_db(Some(
+            r#"<style version="1.0" class="in-text">
+                <citation>
+                    <layout prefix="(" suffix=")" delimiter="; ">
+                        <text variable="title" />
+                    </layout>
+                </citation>
+            </style>"#,
+        ));
+        insert_basic_refs(&mut db, &["one", "two"]);
+        let id = cid(&mut db, 1);
+        let mut two = Cite::basic("two");
+        two.prefix = Some("see ".into());
+        db.insert_cluster(Cluster::new(id, vec![Cite::basic("one"), two], None));
+        db.set_cluster_order(&[ClusterPosition::note(id, 1)])
+            .unwrap();
+        let (output, spans) = db.get_cluster_spans(id).unwrap();
+        assert_eq!(output, db.get_cluster(id).unwrap());
+        assert_eq!(output.as_str(), "(Book one; see Book two)");
+        let found: Vec<_> = spans
+            .iter()
+            .map(|span| {
+                (
+                    span.cite_index,
+                    span.ref_id.as_ref(),
+                    &output[span.range.clone()],
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![(0, "one", "Book one"), (1, "two", "see Book two")]
+        );
+    }
+}
+
+mod explain_disambiguation {
+    use super::*;
+    use crate::DisambMethod;
+
+    #[test]
+    fn conditionals() {
+        let mut db = test_db(Some(
+            r#"<style version="1.0" class="in-text">
+                <citation>
+                    <layout delimiter="; ">
+                        <choose>
+                            <if disambiguate="true"><text variable="title" /></if>
+                            <else><text value="Book" /></else>
+                        </choose>
+                    </layout>
+                </citation>
+            </style>"#,
+        ));
+        insert_basic_refs(&mut db, &["one", "two"]);
+        insert_ascending_notes(&mut db, &["one", "two"]);
+        let explanation = db.explain_disambiguation_str("1", 0).unwrap();
+        assert_eq!(explanation.ref_id, "one");
+        assert!(explanation.was_ambiguous());
+        assert_eq!(
+            explanation.stages[0].ambiguous_with,
+            vec![Atom::from("two")]
+        );
+        assert_eq!(explanation.resolved_by, Some(DisambMethod::Conditionals));
+        assert!(explanation.still_ambiguous_with().is_empty());
+        assert!(explanation
+            .to_string()
+            .contains("resolved by disambiguate=\"true\" conditions"));
+        assert!(db.explain_disambiguation_str("1", 1).is_none());
+    }
+}
+
+mod style_switch {
+    use super::*;
+    use crate::RestoreError;
+    use citeproc_proc::BibNumber;
+
+    #[test]
+    fn preview_style_switch() {
+        let mut db = test_db(Some(
+            r#"<style version="1.0" class="note">
+                <citation><layout><text variable="title" /></layout></citation>
+            </style>"#,
+        ));
+        insert_basic_refs(&mut db, &["one", "two"]);
+        insert_ascending_notes(&mut db, &["one", "two"]);
+        db.include_uncited(IncludeUncited::All);
+        let one = cid(&mut db, 1);
+        let before = db.get_cluster(one);
+
+        let preview = db
+            .preview_style_switch(
+                r#"<style version="1.0" class="note">
+                    <citation><layout><text variable="title" /></layout></citation>
+                    <bibliography><layout><text variable="title" /></layout></bibliography>
+                </style>"#,
+            )
+            .unwrap();
+        assert!(preview.clusters.is_empty());
+        assert_eq!(preview.bibliography.len(), 2);
+        assert_eq!(preview.bibliography[0].before, None);
+        assert_eq!(
+            preview.bibliography[0].after.as_deref().map(|x| x.as_str()),
+            Some("Book one")
+        );
+        assert_eq!(preview.entry_ids, Some(vec!["one".into(), "two".into()]));
+
+        let preview = db
+            .preview_style_switch_str(
+                r#"<style version="1.0" class="note">
+                    <citation><layout prefix="See "><text variable="title" /></layout></citation>
+                </style>"#,
+            )
+            .unwrap();
+        let changed: Vec<_> = preview
+            .clusters
+            .iter()
+            .map(|c| (c.id.as_str(), c.before.as_str(), c.after.as_str()))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                ("1", "Book one", "See Book one"),
+                ("2", "Book two", "See Book two")
+            ]
+        );
+        assert!(preview.bibliography.is_empty());
+        assert_eq!(preview.entry_ids, None);
+
+        // the current style is untouched
+        assert_eq!(db.get_cluster(one), before);
+        assert!(db.get_style().bibliography.is_none());
+    }
+
+    #[test]
+    fn preview_keeps_current_locale_override() {
+        let fr_fr = Lang::Iso(IsoLang::French, Some(IsoCountry::FR));
+        let mut locales = HashMap::new();
+        locales.insert(Lang::en_us(), csl::locale::EN_US.to_owned());
+        locales.insert(
+            fr_fr.clone(),
+            r#"<locale xmlns="http://purl.org/net/xbiblio/csl" version="1.0" xml:lang="fr-FR">
+                <terms><term name="and">et</term></terms>
+            </locale>"#
+                .to_owned(),
+        );
+        let style = r#"<style version="1.0" class="note">
+            <citation><layout><text term="and" /></layout></citation>
+        </style>"#;
+        let mut db = Processor::new(InitOptions {
+            style,
+            format: SupportedFormat::Plain,
+            locale_override: Some(fr_fr),
+            fetcher: Some(Arc::new(citeproc_db::PredefinedLocales(locales))),
+            test_mode: true,
+            ..Default::default()
+        })
+        .unwrap();
+        insert_basic_refs(&mut db, &["one"]);
+        insert_ascending_notes(&mut db, &["one"]);
+        let one = cid(&mut db, 1);
+        assert_eq!(
+            db.get_cluster(one).as_deref().map(|x| x.as_str()),
+            Some("et")
+        );
+
+        let preview = db.preview_style_switch(style).unwrap();
+        assert!(preview.clusters.is_empty());
+        db.set_style_text(style).unwrap();
+        assert_eq!(
+            db.get_cluster(one).as_deref().map(|x| x.as_str()),
+            Some("et")
+        );
+    }
+
+    #[test]
+    fn preview_invalid_style() {
+        let db = test_db(None);
+        assert!(matches!(
+            db.preview_style_switch("<style"),
+            Err(RestoreError::Style(_))
+        ));
+    }
+}
+
+mod dependent_style {
+    use super::*;
+    use crate::{DependentStyleError, PredefinedStyles};
+
+    const PARENT: &str = r#"<style version="1.0" class="note">
+        <info>
+            <id>http://www.zotero.org/styles/parent</id>
+            <title>Parent</title>
+            <updated>2020-01-01T00:00:00Z</updated>
+        </info>
+        <citation><layout><text variable="title" /></layout></citation>
+    </style>"#;
+
+    const DEPENDENT: &str = r#"<style version="1.0" class="note" default-locale="en-AU">
+        <info>
+            <id>http://www.zotero.org/styles/dependent</id>
+            <title>Dependent</title>
+            <updated>2020-01-01T00:00:00Z</updated>
+            <link rel="independent-parent" href="http://www.zotero.org/styles/parent" />
+        </info>
+    </style>"#;
+
+    fn fetcher() -> PredefinedStyles {
+        let mut styles = HashMap::new();
+        styles.insert(
+            "http://www.zotero.org/styles/parent".to_owned(),
+            PARENT.to_owned(),
+        );
+        PredefinedStyles(styles)
+    }
+
+    fn options(style: &str) -> InitOptions {
+        InitOptions {
+            style,
+            format: SupportedFormat::Plain,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn resolves_parent_and_locale() {
+        let mut db = Processor::new_resolving_dependent(options(DEPENDENT), &fetcher()).unwrap();
+        assert_eq!(db.default_lang(), "en-AU".parse().unwrap());
+        insert_basic_refs(&mut db, &["one"]);
+        insert_ascending_notes(&mut db, &["one"]);
+        let id = cid(&mut db, 1);
+        assert_cluster!(db.get_cluster(id), Some("Book one"));
+    }
+
+    #[test]
+    fn explicit_override_wins() {
+        let db = Processor::new_resolving_dependent(
+            InitOptions {
+                locale_override: Some(Lang::en_us()),
+                ..options(DEPENDENT)
+            },
+            &fetcher(),
+        )
+        .unwrap();
+        assert_eq!(db.default_lang(), Lang::en_us());
+    }
+
+    #[test]
+    fn independent_passes_through() {
+        let db = Processor::new_resolving_dependent(options(PARENT), &fetcher()).unwrap();
+        assert_eq!(db.default_lang(), Lang::en_us());
+    }
+
+    #[test]
+    fn missing_parent() {
+        let empty = PredefinedStyles(HashMap::new());
+        match Processor::new_resolving_dependent(options(DEPENDENT), &empty) {
+            Err(DependentStyleError::ParentNotFound(id)) => {
+                assert_eq!(id, "http://www.zotero.org/styles/parent")
+            }
+            _ => panic!("expected ParentNotFound"),
+        }
+    }
+}
+
+mod async_locales {
+    use super::*;
+    use std::future::Future;
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    /// Runs a future that never has to wait on anything.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        fn raw() -> RawWaker {
+            fn clone(_: *const ()) -> RawWaker {
+                raw()
+            }
+            fn noop(_: *const ()) {}
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        let waker = unsafe { Waker::from_raw(raw()) };
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future was not ready"),
+        }
+    }
+
+    struct Remote(HashMap<Lang, String>);
+
+    impl AsyncLocaleFetcher for Remote {
+        fn fetch_string(&self, lang: Lang) -> LocaleFuture<'_> {
+            let found = self.0.get(&lang).cloned();
+            Box::pin(async move { Ok(found) })
+        }
+    }
+
+    fn de_de() -> Lang {
+        Lang::Iso(IsoLang::Deutsch, Some(IsoCountry::DE))
+    }
+
+    fn processor() -> Processor {
+        let mut locales = HashMap::new();
+        locales.insert(
+            de_de(),
+            r#"<?xml version="1.0" encoding="utf-8"?>
+            <locale xmlns="http://purl.org/net/xbiblio/csl" version="1.0" xml:lang="de-DE">
+            <terms><term name="and">und</term></terms></locale>"#
+                .to_owned(),
+        );
+        Processor::new(InitOptions {
+            style: r#"<style version="1.0" class="note">
+                <citation><layout><text term="and" /></layout></citation>
+            </style>"#,
+            format: SupportedFormat::Plain,
+            locale_override: Some(de_de()),
+            async_fetcher: Some(Arc::new(Remote(locales))),
+            test_mode: true,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn fetch_then_render() {
+        let mut db = processor();
+        assert!(db.locales_pending().contains(&de_de()));
+        block_on(db.fetch_locales()).unwrap();
+        assert!(!db.locales_pending().contains(&de_de()));
+        insert_basic_refs(&mut db, &["one"]);
+        insert_ascending_notes(&mut db, &["one"]);
+        let id = cid(&mut db, 1);
+        assert_cluster!(db.get_cluster(id), Some("und"));
+    }
+
+    #[test]
+    fn render_before_fetching() {
+        let mut db = processor();
+        insert_basic_refs(&mut db, &["one"]);
+        insert_ascending_notes(&mut db, &["one"]);
+        let id = cid(&mut db, 1);
+        assert_cluster!(db.get_cluster(id), Some("and"));
+        assert!(db
+            .diagnostics()
+            .iter()
+            .any(|d| d.kind == DiagnosticKind::LocaleUnavailable));
+        db.batched_updates();
+
+        block_on(db.fetch_locales()).unwrap();
+        let updates = db.batched_updates();
+        assert_eq!(updates.clusters, vec![(id, Arc::new("und".into()))]);
+        assert!(db.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn east_asian_reference_languages_are_in_use() {
+        let mut db = processor();
+        let zh_cn = Lang::Iso(IsoLang::Chinese, Some(IsoCountry::CN));
+        let mut refr = Reference::empty(Atom::from("one"), CslType::Book);
+        refr.language = Some(zh_cn.clone());
+        db.insert_reference(refr);
+        let mut refr = Reference::empty(Atom::from("two"), CslType::Book);
+        refr.language = Some(Lang::Iso(IsoLang::English, Some(IsoCountry::GB)));
+        db.insert_reference(refr);
+        let langs = db.get_langs_in_use();
+        assert!(langs.contains(&zh_cn));
+        assert!(!langs.contains(&Lang::Iso(IsoLang::English, Some(IsoCountry::GB))));
+    }
+}
+
+mod transaction {
+    use super::*;
+
+    fn book(id: &str) -> Reference {
+        let mut refr = Reference::empty(Atom::from(id), CslType::Book);
+        let title = "Book ".to_string() + id;
+        refr.ordinary.insert(Variable::Title, title);
+        refr
+    }
+
+    fn output(db: &Processor, ids: &[ClusterId]) -> Vec<Option<String>> {
+        ids.iter()
+            .map(|&id| db.get_cluster(id).map(|s| s.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn one_summary_then_undo_redo() {
+        let mut db = test_db(Some(
+            r#"<style class="note" version="1.0.1">
+                <citation><layout><text variable="title" /></layout></citation>
+            </style>"#,
+        ));
+        let one = cid(&mut db, 1);
+        let two = cid(&mut db, 2);
+        let summary = db
+            .transaction(vec![
+                Edit::InsertReference(book("a")),
+                Edit::InsertReference(book("b")),
+                Edit::InsertCluster(Cluster::new(one, vec![Cite::basic("a")], None)),
+                Edit::InsertCluster(Cluster::new(two, vec![Cite::basic("b")], None)),
+                Edit::SetClusterOrder(vec![
+                    ClusterPosition::note(one, 1),
+                    ClusterPosition::note(two, 2),
+                ]),
+            ])
+            .unwrap();
+        assert_eq!(summary.clusters.len(), 2);
+        assert_eq!(db.revision(), 1);
+
+        db.transaction(vec![
+            Edit::InsertReference(book("c")),
+            Edit::InsertCluster(Cluster::new(one, vec![Cite::basic("c")], None)),
+            Edit::RemoveReference(Atom::from("b")),
+        ])
+        .unwrap();
+        let edited = output(&db, &[one, two]);
+        assert_eq!(edited[0].as_deref(), Some("Book c"));
+        assert_eq!(db.revision(), 2);
+
+        let undone = db.undo().unwrap();
+        assert_eq!(undone.clusters.len(), 2);
+        assert_eq!(
+            output(&db, &[one, two]),
+            vec![Some("Book a".into()), Some("Book b".into())]
+        );
+
+        db.redo().unwrap();
+        assert_eq!(output(&db, &[one, two]), edited);
+        assert!(db.redo().is_none());
+
+        db.rollback_to(0);
+        assert_eq!(output(&db, &[one, two]), vec![None, None]);
+        assert!(db.undo().is_none());
+        db.redo().unwrap();
+        assert_eq!(
+            output(&db, &[one, two]),
+            vec![Some("Book a".into()), Some("Book b".into())]
+        );
+    }
+
+    #[test]
+    fn failed_transaction_changes_nothing() {
+        let mut db = test_db(None);
+        insert_basic_refs(&mut db, &["a"]);
+        insert_ascending_notes(&mut db, &["a"]);
+        db.batched_updates();
+        let one = cid(&mut db, 1);
+        let two = cid(&mut db, 2);
+        let three = cid(&mut db, 3);
+        let result = db.transaction(vec![
+            Edit::InsertCluster(Cluster::new(two, vec![Cite::basic("a")], None)),
+            Edit::RemoveCluster(one),
+            Edit::SetClusterOrder(vec![
+                ClusterPosition::note(two, 1),
+                ClusterPosition::note(three, 2),
+            ]),
+        ]);
+        assert_eq!(
+            result.unwrap_err(),
+            ReorderingError::NonExistentCluster(three)
+        );
+        assert_eq!(db.revision(), 0);
+        assert!(db.batched_updates().clusters.is_empty());
+        assert_eq!(db.clusters_ordered(), Arc::new(vec![one]));
+        assert!(db.get_cluster(two).is_none());
+    }
+
+    #[test]
+    fn undo_keeps_edits_made_outside() {
+        let mut db = test_db(None);
+        let one = cid(&mut db, 1);
+        db.transaction(vec![
+            Edit::InsertReference(book("a")),
+            Edit::InsertCluster(Cluster::new(one, vec![Cite::basic("a")], None)),
+        ])
+        .unwrap();
+        insert_basic_refs(&mut db, &["b"]);
+        let two = cid(&mut db, 2);
+        db.insert_cluster(Cluster::new(two, vec![Cite::basic("b")], None));
+
+        db.undo().unwrap();
+        assert!(!db.all_keys().contains(&Atom::from("a")));
+        assert!(db.all_keys().contains(&Atom::from("b")));
+        assert!(!db.all_cluster_ids().contains(&one));
+        assert!(db.all_cluster_ids().contains(&two));
+
+        db.remove_reference(Atom::from("b"));
+        db.remove_cluster(two);
+        db.redo().unwrap();
+        assert!(db.all_keys().contains(&Atom::from("a")));
+        assert!(!db.all_keys().contains(&Atom::from("b")));
+        assert!(db.all_cluster_ids().contains(&one));
+        assert!(!db.all_cluster_ids().contains(&two));
+    }
+
+    #[test]
+    fn string_ids() {
+        let mut db = test_db(None);
+        let edits: Vec<string_id::Edit> = serde_json::from_str(
+            r#"[
+                { "insertReference": { "id": "a", "type": "book" } },
+                { "insertCluster": { "id": "one", "cites": [{ "id": "a" }] } },
+                { "setClusterOrder": [{ "id": "one", "note": 1 }] }
+            ]"#,
+        )
+        .unwrap();
+        let summary = db.transaction_str(edits).unwrap();
+        assert_eq!(summary.clusters.len(), 1);
+        assert_eq!(summary.clusters[0].0, "one");
+        let summary = db.undo_str().unwrap();
+        assert!(db.get_cluster_str("one").is_none());
+        assert!(summary.clusters.is_empty());
+    }
+}
+
+mod cancel {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    const STYLE: &str = r#"<style class="note" version="1.0.1">
+        <citation><layout><text variable="title" /></layout></citation>
+        <bibliography><layout><text variable="title" /></layout></bibliography>
+    </style>"#;
+
+    #[test]
+    fn cancelled_render_resumes() {
+        let mut db = test_db(Some(STYLE));
+        insert_basic_refs(&mut db, &["a", "b"]);
+        insert_ascending_notes(&mut db, &["a", "b"]);
+        let handle = db.cancel_handle();
+        handle.cancel();
+        let summary = db.batched_updates();
+        assert!(summary.interrupted);
+        assert!(summary.clusters.is_empty());
+        assert!(summary.bibliography.is_none());
+        assert!(!handle.is_cancelled());
+
+        let summary = db.batched_updates();
+        assert!(!summary.interrupted);
+        assert_eq!(summary.clusters.len(), 2);
+        assert!(summary.bibliography.is_some());
+    }
+
+    #[test]
+    fn drain_ignores_cancel() {
+        let mut db = test_db(Some(STYLE));
+        insert_basic_refs(&mut db, &["a", "b"]);
+        insert_ascending_notes(&mut db, &["a", "b"]);
+        let handle = db.cancel_handle();
+        handle.cancel();
+        db.drain();
+        assert!(!handle.is_cancelled());
+        // everything was rendered by drain
+        assert!(db.compute().is_empty());
+    }
+
+    #[test]
+    fn deadline() {
+        let mut db = test_db(Some(STYLE));
+        insert_basic_refs(&mut db, &["a", "b"]);
+        insert_ascending_notes(&mut db, &["a", "b"]);
+        let summary = db.batched_updates_str_until(Some(Instant::now()));
+        assert!(summary.interrupted);
+        assert!(summary.clusters.is_empty());
+        let later = Instant::now() + Duration::from_secs(60);
+        let summary = db.batched_updates_str_until(Some(later));
+        assert!(!summary.interrupted);
+        assert_eq!(summary.clusters.len(), 2);
+    }
+}
+
+mod parse_cache {
+    use super::*;
+
+    const STYLE: &str = r#"<style class="note" version="1.0.1">
+        <features><feature name="text-case-normal" /></features>
+        <locale xml:lang="en-GB"><terms><term name="and">plus</term></terms></locale>
+        <citation><layout><text variable="title" text-case="normal" /></layout></citation>
+    </style>"#;
+
+    fn with_cache(cache: &Arc<ParseCache>) -> Processor {
+        Processor::new(InitOptions {
+            style: STYLE,
+            parse_cache: Some(cache.clone()),
+            test_mode: true,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn shared_between_processors() {
+        let cache = Arc::new(ParseCache::new());
+        let a = with_cache(&cache);
+        let b = with_cache(&cache);
+        assert!(Arc::ptr_eq(&a.style(), &b.style()));
+        assert!(Arc::ptr_eq(
+            &a.parsed_locale(LocaleSource::File(Lang::en_us())).unwrap(),
+            &b.parsed_locale(LocaleSource::File(Lang::en_us())).unwrap(),
+        ));
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let style = with_cache(&Arc::new(ParseCache::new())).style();
+        let bytes = bincode::serialize(&*style).unwrap();
+        let back: Style = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(&back, &*style);
+        assert!(back.features.text_case_normal);
+
+        let locale = Locale::parse(csl::locale::EN_US).unwrap();
+        let bytes = bincode::serialize(&locale).unwrap();
+        let back: Locale = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(back, locale);
+
+        for uri in &[
+            csl::Uri::from("http://www.zotero.org/styles/apa"),
+            csl::Uri::from("apa"),
+        ] {
+            let bytes = bincode::serialize(uri).unwrap();
+            let back: csl::Uri = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(&back, uri);
+        }
+    }
+}
+
+mod persist {
+    use super::*;
+    use crate::RestoreError;
+    use citeproc_proc::BibNumber;
+
+    #[test]
+    fn save_and_restore() {
+        let mut db = test_db(Some(
+            r#"<style version="1.0" class="note">
+                <citation><layout delimiter="; "><text variable="title" /></layout></citation>
+                <bibliography><layout><text variable="title" /></layout></bibliography>
+            </style>"#,
+        ));
+        insert_basic_refs(&mut db, &["one", "two", "three"]);
+        insert_ascending_notes(&mut db, &["one", "two"]);
+        db.include_uncited(IncludeUncited::Specific(vec!["three".into()]));
+        db.batched_updates();
+
+        let saved = db.save_state(true);
+        let json = serde_json::to_string(&saved).unwrap();
+        let saved: SavedState = serde_json::from_str(&json).unwrap();
+        let numbers = &saved.output.as_ref().unwrap().bibliography_numbers;
+        assert_eq!(
+            numbers.get(&Atom::from("three")),
+            Some(&BibNumber::Uncited(3))
+        );
+        let restored = Processor::restore_state(saved.clone(), None).unwrap();
+
+        // including the citation numbers
+        assert_eq!(restored.save_state(true), saved);
+        let one = cid(&mut db, 1);
+        assert_eq!(restored.get_cluster(one), db.get_cluster(one));
+        assert_eq!(restored.get_bibliography(), db.get_bibliography());
+        // the saved output is already up to date
+        let summary = restored.batched_updates();
+        assert!(summary.clusters.is_empty());
+        assert!(summary.bibliography.is_none());
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let db = test_db(None);
+        let mut saved = db.save_state(false);
+        saved.version = SAVED_STATE_VERSION + 1;
+        assert!(matches!(
+            Processor::restore_state(saved, None),
+            Err(RestoreError::UnsupportedVersion(_))
+        ));
+    }
+}
+
+mod bibliography_meta {
+    use super::*;
+
+    /// A bibliography with `bib_attrs`, whose first fields are `[1]` to `[n]`.
+    fn meta(format: SupportedFormat, bib_attrs: &str, n: u32) -> BibliographyMeta {
+        let style = format!(
+            r#"<style version="1.0" class="in-text">
+                <citation><layout><text variable="citation-number" /></layout></citation>
+                <bibliography {}>
+                    <layout>
+                        <text variable="citation-number" prefix="[" suffix="]" display="left-margin" />
+                        <text variable="title" display="right-inline" />
+                    </layout>
+                </bibliography>
+            </style>"#,
+            bib_attrs
+        );
+        let mut db = Processor::new(InitOptions {
+            style: &style,
+            format,
+            test_mode: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let ids: Vec<String> = (1..=n).map(|i| i.to_string()).collect();
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        insert_basic_refs(&mut db, &ids);
+        insert_ascending_notes(&mut db, &ids);
+        db.get_bibliography_meta().unwrap()
+    }
+
+    fn rtf_markup_pre(bib_attrs: &str) -> String {
+        let meta = meta(SupportedFormat::Rtf, bib_attrs, 2);
+        let format_meta = serde_json::to_value(&meta.format_meta).unwrap();
+        format_meta["markupPre"].as_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn max_offset() {
+        assert_eq!(meta(SupportedFormat::Plain, "", 9).max_offset, 3);
+        assert_eq!(meta(SupportedFormat::Plain, "", 10).max_offset, 4);
+    }
+
+    #[test]
+    fn rtf_paragraph_settings() {
+        // "[1]" is 3 characters, so the second field starts at 24 + 3 * 120 twips
+        assert_eq!(
+            rtf_markup_pre(r#"second-field-align="flush""#),
+            "{\\pard \\tx384 \\li384 \\fi-384 \\sl240 \\slmult1 \\sa240 "
+        );
+        assert_eq!(
+            rtf_markup_pre(r#"second-field-align="margin""#),
+            "{\\pard \\tx0 \\li0 \\fi-384 \\sl240 \\slmult1 \\sa240 "
+        );
+        assert_eq!(
+            rtf_markup_pre(r#"hanging-indent="true" line-spacing="2" entry-spacing="0""#),
+            "{\\pard \\li720 \\fi-720 \\sl480 \\slmult1 \\sa0 "
+        );
+        assert_eq!(
+            rtf_markup_pre(""),
+            "{\\pard \\li0 \\fi0 \\sl240 \\slmult1 \\sa240 "
+        );
+    }
+}
+
+mod bibliography_sections {
+    use super::*;
+    use crate::{BibliographyFilter, BibliographySection, ClusterRange, FieldMatch};
+
+    fn mk_db() -> Processor {
+        let mut db = test_db(Some(
+            r#"<style version="1.0" class="note">
+                <citation><layout delimiter="; "><text variable="title" /></layout></citation>
+                <bibliography>
+                    <layout>
+                        <text variable="citation-number" suffix=". " />
+                        <text variable="title" />
+                    </layout>
+                </bibliography>
+            </style>"#,
+        ));
+        insert_basic_refs(&mut db, &["one", "two", "three"]);
+        let mut case = Reference::empty("case".into(), CslType::LegalCase);
+        case.ordinary.insert(Variable::Title, "A v B".into());
+        db.insert_reference(case);
+        insert_ascending_notes(&mut db, &["one", "two", "case", "three"]);
+        db
+    }
+
+    fn entries(db: &Processor, name: &str) -> Vec<String> {
+        db.get_bibliography_section(name)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.value.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn cluster_ranges_and_filters() {
+        let mut db = mk_db();
+        let (first, last) = (cid(&mut db, 2), cid(&mut db, 4));
+        db.set_bibliography_sections(vec![
+            BibliographySection {
+                name: "chapter".into(),
+                clusters: Some(ClusterRange { first, last }),
+                filter: BibliographyFilter::default(),
+            },
+            BibliographySection {
+                name: "primary".into(),
+                clusters: None,
+                filter: BibliographyFilter {
+                    select: vec![FieldMatch::new("type", "legal_case")],
+                    ..Default::default()
+                },
+            },
+        ]);
+        assert_eq!(
+            entries(&db, "chapter"),
+            vec!["1. Book two", "2. A v B", "3. Book three"]
+        );
+        assert_eq!(entries(&db, "primary"), vec!["1. A v B"]);
+        assert!(db.get_bibliography_section("nonexistent").is_none());
+        // the full bibliography keeps its own numbering
+        assert_eq!(db.get_bibliography()[2].value.as_str(), "3. A v B");
+    }
+
+    #[test]
+    fn cites_in_a_range_use_section_numbers() {
+        let mut db = test_db(Some(
+            r#"<style version="1.0" class="in-text">
+                <citation><layout><text variable="citation-number" /></layout></citation>
+                <bibliography>
+                    <layout>
+                        <text variable="citation-number" suffix=". " />
+                        <text variable="title" />
+                    </layout>
+                </bibliography>
+            </style>"#,
+        ));
+        insert_basic_refs(&mut db, &["one", "two", "three"]);
+        insert_ascending_notes(&mut db, &["one", "two", "one", "three"]);
+        let (first, last) = (cid(&mut db, 2), cid(&mut db, 4));
+        db.set_bibliography_sections(vec![BibliographySection {
+            name: "chapter".into(),
+            clusters: Some(ClusterRange { first, last }),
+            filter: BibliographyFilter::default(),
+        }]);
+        // without cs:sort, the order of first citation within the range
+        assert_eq!(
+            entries(&db, "chapter"),
+            vec!["1. Book two", "2. Book one", "3. Book three"]
+        );
+        let cluster = |db: &mut Processor, n| {
+            let id = cid(db, n);
+            db.get_cluster(id).unwrap().to_string()
+        };
+        assert_eq!(cluster(&mut db, 1), "1");
+        assert_eq!(cluster(&mut db, 2), "1");
+        assert_eq!(cluster(&mut db, 3), "2");
+        assert_eq!(cluster(&mut db, 4), "3");
+    }
+
+    #[test]
+    fn max_offset_per_section() {
+        let mut db = test_db(Some(
+            r#"<style version="1.0" class="note">
+                <citation><layout><text variable="title" /></layout></citation>
+                <bibliography second-field-align="flush">
+                    <layout>
+                        <text variable="title" display="left-margin" />
+                        <text value="entry" display="right-inline" />
+                    </layout>
+                </bibliography>
+            </style>"#,
+        ));
+        insert_basic_refs(&mut db, &["one", "three"]);
+        let mut case = Reference::empty("case".into(), CslType::LegalCase);
+        case.ordinary.insert(Variable::Title, "A v B".into());
+        db.insert_reference(case);
+        insert_ascending_notes(&mut db, &["one", "case", "three"]);
+        db.set_bibliography_sections(vec![
+            BibliographySection {
+                name: "primary".into(),
+                clusters: None,
+                filter: BibliographyFilter {
+                    select: vec![FieldMatch::new("type", "legal_case")],
+                    ..Default::default()
+                },
+            },
+            BibliographySection {
+                name: "secondary".into(),
+                clusters: None,
+                filter: BibliographyFilter {
+                    exclude: vec![FieldMatch::new("type", "legal_case")],
+                    ..Default::default()
+                },
+            },
+        ]);
+        let max_offset = |name| db.get_bibliography_section_meta(name).unwrap().max_offset;
+        // "A v B"
+        assert_eq!(max_offset("primary"), 5);
+        // "Book three"
+        assert_eq!(max_offset("secondary"), 10);
+        assert_eq!(db.get_bibliography_meta().unwrap().max_offset, 10);
+        assert!(db.get_bibliography_section_meta("nonexistent").is_none());
+    }
+
+    #[test]
+    fn batched_updates_per_section() {
+        let mut db = mk_db();
+        db.set_bibliography_sections_str(vec![string_id::BibliographySection {
+            name: "secondary".into(),
+            clusters: None,
+            filter: BibliographyFilter {
+                exclude: vec![FieldMatch::new("type", "legal_case")],
+                ..Default::default()
+            },
+        }]);
+        let summary = db.batched_updates();
+        let update = &summary.bibliography_sections["secondary"];
+        assert_eq!(
+            update.entry_ids,
+            Some(vec!["one".into(), "two".into(), "three".into()])
+        );
+        assert!(db.batched_updates().bibliography_sections.is_empty());
+
+        let mut refr = Reference::empty("two".into(), CslType::Book);
+        refr.ordinary.insert(Variable::Title, "Book 2".into());
+        db.insert_reference(refr);
+        let summary = db.batched_updates();
+        let update = &summary.bibliography_sections["secondary"];
+        assert_eq!(update.entry_ids, None);
+        assert_eq!(
+            update
+                .updated_entries
+                .get(&Atom::from("two"))
+                .map(|x| x.as_str()),
+            Some("2. Book 2")
+        );
+    }
+}
@@ -0,0 +1,316 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! Applying a batch of edits at once, and undoing it.
+//!
+//! Before a transaction is applied, the salsa inputs it is going to touch are saved, and again
+//! afterwards. Undo and redo just set one or the other back. They are all Arcs, so this is cheap,
+//! and salsa only recomputes what those inputs actually changed. The sets of all reference and
+//! cluster ids are not saved whole; only the ids the transaction touched are added to or removed
+//! from them, so references and clusters inserted or removed outside a transaction stay as they
+//! are.
+
+use crate::api::{string_id, ClusterPosition, Edit, ReorderingError, UpdateSummary};
+use crate::prelude::*;
+use fnv::FnvHashSet;
+use indexmap::set::IndexSet;
+use salsa::Durability;
+use std::sync::Arc;
+
+#[derive(Default)]
+pub(crate) struct History {
+    undo: Vec<Revision>,
+    redo: Vec<Revision>,
+}
+
+struct Revision {
+    before: InputState,
+    after: InputState,
+}
+
+/// The inputs touched by one transaction.
+struct InputState {
+    /// None if the reference was not in all_keys.
+    references: Vec<(Atom, Option<Arc<Reference>>)>,
+    /// None if the cluster was not in all_cluster_ids.
+    clusters: Vec<(ClusterId, Option<ClusterInputs>)>,
+    /// Only saved if the transaction reorders the document.
+    order: Option<OrderInputs>,
+}
+
+struct ClusterInputs {
+    cites: Arc<Vec<CiteId>>,
+    note_number: Option<ClusterNumber>,
+    mode: Option<ClusterMode>,
+}
+
+struct OrderInputs {
+    clusters_ordered: Arc<Vec<ClusterId>>,
+    /// Every cluster in all_cluster_ids, as reordering can renumber any of them.
+    note_numbers: Vec<(ClusterId, Option<ClusterNumber>)>,
+}
+
+/// What a list of edits is going to touch.
+struct Touched {
+    references: Vec<Atom>,
+    clusters: Vec<ClusterId>,
+    order: bool,
+}
+
+impl Touched {
+    fn new(edits: &[Edit]) -> Self {
+        let mut references = IndexSet::new();
+        let mut clusters = IndexSet::new();
+        let mut order = false;
+        for edit in edits {
+            match edit {
+                Edit::InsertReference(refr) => {
+                    references.insert(refr.id.clone());
+                }
+                Edit::RemoveReference(id) => {
+                    references.insert(id.clone());
+                }
+                Edit::InsertCluster(cluster) => {
+                    clusters.insert(cluster.id);
+                }
+                Edit::RemoveCluster(id) => {
+                    clusters.insert(*id);
+                }
+                Edit::SetClusterOrder(_) => order = true,
+            }
+        }
+        Touched {
+            references: references.into_iter().collect(),
+            clusters: clusters.into_iter().collect(),
+            order,
+        }
+    }
+}
+
+impl Processor {
+    /// Applies all of `edits` in order, and returns the changes they made as one
+    /// [UpdateSummary]. If any of them fails, none of them are applied.
+    ///
+    /// A transaction can be undone with [Processor::undo]. Edits made with the other `Processor`
+    /// methods are not recorded, and undoing a transaction that touched the same clusters or
+    /// references will overwrite them.
+    pub fn transaction(&mut self, edits: Vec<Edit>) -> Result<UpdateSummary, ReorderingError> {
+        self.apply_transaction(edits)?;
+        Ok(self.batched_updates())
+    }
+
+    pub fn transaction_str(
+        &mut self,
+        edits: Vec<string_id::Edit>,
+    ) -> Result<string_id::UpdateSummary, string_id::ReorderingError> {
+        let edits = edits
+            .into_iter()
+            .map(|edit| self.intern_edit(edit))
+            .collect();
+        self.apply_transaction(edits).map_err(|e| {
+            let reader = self.interner.read().unwrap();
+            e.to_external(&reader)
+        })?;
+        Ok(self.batched_updates_str())
+    }
+
+    /// The number of transactions that can be undone. Pass it to [Processor::rollback_to] later
+    /// to get back to the current state.
+    pub fn revision(&self) -> usize {
+        self.history.undo.len()
+    }
+
+    /// Reverts the last transaction. Returns None if there is nothing to undo.
+    pub fn undo(&mut self) -> Option<UpdateSummary> {
+        self.step_back()?;
+        Some(self.batched_updates())
+    }
+
+    /// Applies the last undone transaction again. Returns None if there is nothing to redo, or a
+    /// transaction has been applied since the last undo.
+    pub fn redo(&mut self) -> Option<UpdateSummary> {
+        self.step_forward()?;
+        Some(self.batched_updates())
+    }
+
+    /// Undoes transactions until [Processor::revision] is `revision`. The undone transactions can
+    /// be redone.
+    pub fn rollback_to(&mut self, revision: usize) -> UpdateSummary {
+        while self.revision() > revision && self.step_back().is_some() {}
+        self.batched_updates()
+    }
+
+    pub fn undo_str(&mut self) -> Option<string_id::UpdateSummary> {
+        self.step_back()?;
+        Some(self.batched_updates_str())
+    }
+
+    pub fn redo_str(&mut self) -> Option<string_id::UpdateSummary> {
+        self.step_forward()?;
+        Some(self.batched_updates_str())
+    }
+
+    pub fn rollback_to_str(&mut self, revision: usize) -> string_id::UpdateSummary {
+        while self.revision() > revision && self.step_back().is_some() {}
+        self.batched_updates_str()
+    }
+
+    fn step_back(&mut self) -> Option<()> {
+        let revision = self.history.undo.pop()?;
+        self.restore_inputs(&revision.before);
+        self.history.redo.push(revision);
+        Some(())
+    }
+
+    fn step_forward(&mut self) -> Option<()> {
+        let revision = self.history.redo.pop()?;
+        self.restore_inputs(&revision.after);
+        self.history.undo.push(revision);
+        Some(())
+    }
+
+    fn intern_edit(&mut self, edit: string_id::Edit) -> Edit {
+        match edit {
+            string_id::Edit::InsertReference(refr) => Edit::InsertReference(refr),
+            string_id::Edit::RemoveReference(id) => Edit::RemoveReference(id),
+            string_id::Edit::InsertCluster(cluster) => {
+                Edit::InsertCluster(self.intern_cluster(cluster))
+            }
+            string_id::Edit::RemoveCluster(id) => Edit::RemoveCluster(self.cluster_id(id)),
+            string_id::Edit::SetClusterOrder(positions) => Edit::SetClusterOrder(
+                positions
+                    .into_iter()
+                    .map(|pos| ClusterPosition {
+                        id: pos.id.map(|id| self.cluster_id(id)),
+                        note: pos.note,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn apply_transaction(&mut self, edits: Vec<Edit>) -> Result<(), ReorderingError> {
+        let touched = Touched::new(&edits);
+        let before = self.save_inputs(&touched);
+        for edit in edits {
+            match edit {
+                Edit::InsertReference(refr) => self.insert_reference(refr),
+                Edit::RemoveReference(id) => self.remove_reference(id),
+                Edit::InsertCluster(cluster) => self.insert_cluster(cluster),
+                Edit::RemoveCluster(id) => self.remove_cluster(id),
+                Edit::SetClusterOrder(positions) => {
+                    let result = if positions.iter().any(|pos| pos.id.is_none()) {
+                        Err(ReorderingError::ClusterOrderWithZero)
+                    } else {
+                        self.set_cluster_order(&positions)
+                    };
+                    if let Err(e) = result {
+                        self.restore_inputs(&before);
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        let after = self.save_inputs(&touched);
+        self.history.undo.push(Revision { before, after });
+        self.history.redo.clear();
+        Ok(())
+    }
+
+    fn save_inputs(&self, touched: &Touched) -> InputState {
+        let all_keys = self.all_keys();
+        let all_cluster_ids = self.all_cluster_ids();
+        let references = touched
+            .references
+            .iter()
+            .map(|id| {
+                let refr = if all_keys.contains(id) {
+                    Some(self.reference_input(id.clone()))
+                } else {
+                    None
+                };
+                (id.clone(), refr)
+            })
+            .collect();
+        let clusters = touched
+            .clusters
+            .iter()
+            .map(|&id| {
+                let inputs = if all_cluster_ids.contains(&id) {
+                    Some(ClusterInputs {
+                        cites: self.cluster_cites(id),
+                        note_number: self.cluster_note_number(id),
+                        mode: self.cluster_mode(id),
+                    })
+                } else {
+                    None
+                };
+                (id, inputs)
+            })
+            .collect();
+        let order = if touched.order {
+            Some(OrderInputs {
+                clusters_ordered: self.clusters_ordered(),
+                note_numbers: all_cluster_ids
+                    .iter()
+                    .map(|&id| (id, self.cluster_note_number(id)))
+                    .collect(),
+            })
+        } else {
+            None
+        };
+        InputState {
+            references,
+            clusters,
+            order,
+        }
+    }
+
+    fn restore_inputs(&mut self, state: &InputState) {
+        let mut all_keys = IndexSet::clone(&self.all_keys());
+        for (id, refr) in &state.references {
+            match refr {
+                Some(refr) => {
+                    self.set_reference_input_with_durability(
+                        id.clone(),
+                        refr.clone(),
+                        Durability::MEDIUM,
+                    );
+                    all_keys.insert(id.clone());
+                }
+                None => {
+                    all_keys.remove(id);
+                }
+            }
+        }
+        self.set_all_keys_with_durability(Arc::new(all_keys), Durability::MEDIUM);
+        let mut all_cluster_ids = FnvHashSet::clone(&self.all_cluster_ids());
+        for (id, inputs) in &state.clusters {
+            match inputs {
+                Some(inputs) => {
+                    self.set_cluster_cites(*id, inputs.cites.clone());
+                    self.set_cluster_note_number(*id, inputs.note_number);
+                    self.set_cluster_mode(*id, inputs.mode.clone());
+                    all_cluster_ids.insert(*id);
+                }
+                // Same as remove_cluster
+                None => {
+                    self.set_cluster_cites(*id, Arc::new(Vec::new()));
+                    self.set_cluster_note_number(*id, None);
+                    self.set_cluster_mode(*id, None);
+                    all_cluster_ids.remove(id);
+                }
+            }
+        }
+        self.set_all_cluster_ids(Arc::new(all_cluster_ids));
+        if let Some(order) = &state.order {
+            for &(id, note_number) in &order.note_numbers {
+                self.set_cluster_note_number(id, note_number);
+            }
+            self.set_clusters_ordered(order.clusters_ordered.clone());
+        }
+    }
+}
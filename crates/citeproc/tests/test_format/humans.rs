@@ -457,6 +457,7 @@ pub fn parse_human_test(contents: &str, csl_features: Option<csl::Features>) ->
         format_options: FormatOptions {
             // disable these for txt format tests
             link_anchors: false,
+            left_margin_width: None,
         },
         csl_features,
         bibliography_no_sort: mode.map_or(false, |(_, _, nosort)| nosort),
@@ -38,6 +38,8 @@ fn bool_true() -> bool {
 struct KebabFormatOpts {
     #[serde(default = "bool_true")]
     link_anchors: bool,
+    #[serde(default)]
+    left_margin_width: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, PartialEq, Default, Clone)]
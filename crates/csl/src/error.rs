@@ -178,7 +178,7 @@ impl NeedVarType {
                              Severity::Error),
 
             CondLocator => (wrong_type_var, "Hint: `locator` only matches locator types".to_string(), Severity::Error),
-            CondIsPlural => (wrong_type_var, "Hint: `is-plural` only matches name variables".to_string(), Severity::Error),
+            CondIsPlural => (wrong_type_var, "Hint: `is-plural` only matches name and number variables".to_string(), Severity::Error),
             Date => (wrong_type_var, "<date variable=\"...\"> can only render dates".to_string(), Severity::Error),
             Name => (wrong_type_var, "Hint: <names> can only render name variables".to_string(), Severity::Error),
         }
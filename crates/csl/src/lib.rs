@@ -686,6 +686,11 @@ impl FromNode for Group {
             delimiter: attribute_option(node, "delimiter", info)?,
             affixes: Option::from_node(node, info)?,
             display: attribute_option(node, "display", info)?,
+            text_case: if info.features.text_case_normal {
+                TextCase::from_node(node, info)?
+            } else {
+                TextCase::None
+            },
             // TODO: CSL-M only
             is_parallel: bool::attribute_default_val(node, "is-parallel", info, false)?,
         })
@@ -753,15 +758,68 @@ impl ConditionParser {
         } else {
             Default::default()
         };
+        // `context` was always parsed, so styles that use it without the feature keep working.
+        let context = attribute_option(node, "context", info)?;
+        let page = if info.features.condition_page {
+            attribute_array_var(node, "page", NeedVarType::CondLocator, info)?
+        } else if node.has_attribute("page") {
+            return Err(InvalidCsl::new(
+                node,
+                "You must opt-in to the `condition-page` feature to use `page`",
+            )
+            .into());
+        } else {
+            Vec::new()
+        };
+        let genre: Vec<SmartString> = match node.attribute("genre") {
+            Some(genres) if info.features.condition_genre => genres
+                .split(' ')
+                .filter(|g| !g.is_empty())
+                .map(|g| g.to_lowercase().replace('-', " ").into())
+                .collect(),
+            Some(_) => {
+                return Err(InvalidCsl::new(
+                    node,
+                    "You must opt-in to the `condition-genre` feature to use `genre`",
+                )
+                .into())
+            }
+            None => Vec::new(),
+        };
+        // Name variables test the number of names, number variables the number of numbers
+        let is_plural: Vec<AnyVariable> =
+            attribute_array_var(node, "is-plural", NeedVarType::CondIsPlural, info)?;
+        let raw_vars = node
+            .attribute("is-plural")
+            .unwrap_or("")
+            .split(' ')
+            .filter(|a| !a.is_empty());
+        for (raw, var) in raw_vars.zip(&is_plural) {
+            match var {
+                AnyVariable::Name(_) | AnyVariable::Number(_) => {}
+                _ => {
+                    return Err(InvalidCsl::wrong_var_type(
+                        node,
+                        "is-plural",
+                        raw,
+                        NeedVarType::CondIsPlural,
+                        Some(*var),
+                    )
+                    .into())
+                }
+            }
+        }
         let cond = ConditionParser {
             match_type: Match::from_node(node, info)?,
             jurisdiction: attribute_option(node, "jurisdiction", info)?,
             subjurisdictions: attribute_option_int(node, "subjurisdictions")?,
-            context: attribute_option(node, "context", info)?,
+            context,
+            page,
+            genre,
             disambiguate: bool::attribute_option(node, "disambiguate", info)?,
             variable: attribute_array_var(node, "variable", NeedVarType::Any, info)?,
             position: attribute_array_var(node, "position", NeedVarType::CondPosition, info)?,
-            is_plural: attribute_array_var(node, "is-plural", NeedVarType::CondIsPlural, info)?,
+            is_plural,
             csl_type: attribute_array_var(node, "type", NeedVarType::CondType, info)?,
             locator: attribute_array_var(node, "locator", NeedVarType::CondLocator, info)?,
             is_uncertain_date: attribute_array_var(
@@ -1163,7 +1221,15 @@ impl FromNode for Names {
             let tag_name = child.tag_name().name();
             match tag_name {
                 "name" => write_slot_once(&child, info, &mut name)?,
-                "institution" => write_slot_once(&child, info, &mut institution)?,
+                "institution" if info.features.institutions => {
+                    write_slot_once(&child, info, &mut institution)?
+                }
+                // Styles with <institution> parsed before the feature existed, so keep ignoring it.
+                "institution" => {
+                    log::warn!(
+                        "ignoring <institution>; opt in to the `institutions` feature to use it"
+                    )
+                }
                 "et-al" => write_slot_once(&child, info, &mut et_al)?,
                 "label" => {
                     write_slot_once(&child, info, &mut label)?;
@@ -1622,6 +1688,11 @@ impl Style {
             default_locale: attribute_option(node, "default-locale", &parse_info)?,
             name_inheritance: Name::from_node(&node, &parse_info)?,
             page_range_format: attribute_option(node, "page-range-format", &parse_info)?,
+            year_range_format: if parse_info.features.year_range_format {
+                attribute_option(node, "year-range-format", &parse_info)?
+            } else {
+                None
+            },
             demote_non_dropping_particle: attribute_optional(
                 node,
                 "demote-non-dropping-particle",
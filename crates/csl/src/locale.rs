@@ -12,7 +12,10 @@ use crate::{attr::*, CslError, Severity};
 use crate::{AttrChecker, FromNode, FromNodeResult, ParseInfo, SmartString};
 use fnv::FnvHashMap;
 use roxmltree::{Document, Node};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use std::sync::Arc;
 
 mod lang;
 pub use self::lang::{IsoCountry, IsoLang, Lang, LocaleSource};
@@ -20,9 +23,15 @@ pub use self::lang::{IsoCountry, IsoLang, Lang, LocaleSource};
 pub const EN_US: &str = include_str!("locales-en-US.xml");
 
 #[derive(Default, Debug, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct LocaleOptionsNode {
     pub limit_day_ordinals_to_day_1: Option<bool>,
     pub punctuation_in_quote: Option<bool>,
+    /// CSL-M `leading-noise-words`, e.g. `"a,an,the"`. Ignored at the start of titles when
+    /// sorting.
+    pub leading_noise_words: Option<WordList>,
+    /// CSL-M `skip-words`. Replaces the built-in list of words that title case leaves alone.
+    pub skip_words: Option<WordList>,
 }
 
 impl LocaleOptionsNode {
@@ -31,9 +40,29 @@ impl LocaleOptionsNode {
             .limit_day_ordinals_to_day_1
             .or(self.limit_day_ordinals_to_day_1);
         self.punctuation_in_quote = other.punctuation_in_quote.or(self.punctuation_in_quote);
+        if other.leading_noise_words.is_some() {
+            self.leading_noise_words = other.leading_noise_words.clone();
+        }
+        if other.skip_words.is_some() {
+            self.skip_words = other.skip_words.clone();
+        }
     }
 }
+
+/// A comma-separated list of words in a locale attribute, lowercased.
+pub type WordList = Arc<[SmartString]>;
+
+fn word_list(attr: Option<SmartString>) -> Option<WordList> {
+    attr.map(|list| {
+        list.split(',')
+            .map(str::trim)
+            .filter(|word| !word.is_empty())
+            .map(|word| SmartString::from(word.to_lowercase()))
+            .collect()
+    })
+}
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct LocaleOptions {
     pub limit_ordinals_to_day_1: bool,
     pub punctuation_in_quote: bool,
@@ -64,6 +93,7 @@ impl Default for LocaleOptions {
 pub type DateMapping = FnvHashMap<DateForm, LocaleDate>;
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Locale {
     pub version: String,
     pub lang: Option<Lang>,
@@ -193,6 +223,7 @@ impl FromNode for Locale {
 
 /// A date element defined inside a `<cs:locale>`
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct LocaleDate {
     pub form: DateForm,
     pub date_parts: Vec<DatePart>,
@@ -288,6 +319,8 @@ impl FromNode for LocaleOptionsNode {
                 info,
             )?,
             punctuation_in_quote: attribute_option(node, "punctuation-in-quote", info)?,
+            leading_noise_words: word_list(attribute_option(node, "leading-noise-words", info)?),
+            skip_words: word_list(attribute_option(node, "skip-words", info)?),
         })
     }
 }
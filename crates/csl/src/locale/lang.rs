@@ -111,6 +111,19 @@ impl Lang {
             _ => false,
         }
     }
+
+    /// The articles to ignore at the start of a title when sorting, when a locale does not
+    /// define `leading-noise-words`.
+    pub fn default_leading_noise_words(&self) -> &'static [&'static str] {
+        match self {
+            Lang::Iso(IsoLang::English, _) => &["a", "an", "the"],
+            Lang::Iso(IsoLang::Deutsch, _) => &["der", "die", "das", "ein", "eine"],
+            Lang::Iso(IsoLang::French, _) => &["le", "la", "les", "l'", "l\u{2019}", "un", "une"],
+            Lang::Iso(IsoLang::Spanish, _) => &["el", "la", "los", "las", "un", "una"],
+            Lang::Iso(IsoLang::Portuguese, _) => &["o", "a", "os", "as", "um", "uma"],
+            _ => &[],
+        }
+    }
 }
 
 use crate::attr::GetAttribute;
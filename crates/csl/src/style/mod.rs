@@ -15,7 +15,7 @@ use crate::version::{CslVersionReq, Features};
 use crate::SmartString;
 use fnv::{FnvHashMap, FnvHashSet};
 #[cfg(feature = "serde")]
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -29,6 +29,7 @@ type StripPeriods = bool;
 type Quotes = bool;
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum TextSource {
     Macro(SmartString),
     Value(SmartString),
@@ -42,6 +43,7 @@ impl Default for TextSource {
 }
 
 #[derive(Default, Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct TextElement {
     pub source: TextSource,
     pub formatting: Option<Formatting>,
@@ -53,6 +55,7 @@ pub struct TextElement {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct LabelElement {
     pub variable: NumberVariable,
     pub form: TermForm,
@@ -64,6 +67,7 @@ pub struct LabelElement {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct NumberElement {
     pub variable: NumberVariable,
     pub form: NumericForm,
@@ -74,6 +78,7 @@ pub struct NumberElement {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum Element {
     /// <cs:text>
     Text(TextElement),
@@ -94,17 +99,22 @@ pub enum Element {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Group {
     pub formatting: Option<Formatting>,
     pub delimiter: Option<SmartString>,
     pub affixes: Option<Affixes>,
     pub elements: Vec<Element>,
     pub display: Option<DisplayMode>,
+    /// CSL-M only, with the `text_case_normal` feature. Applies to everything in the group,
+    /// except elements with `text-case="normal"`.
+    pub text_case: TextCase,
     /// CSL-M only
     pub is_parallel: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum BodyDate {
     Indep(IndependentDate),
     Local(LocalizedDate),
@@ -121,6 +131,7 @@ impl BodyDate {
 
 /// e.g. for <text variable="title" form="short" />
 #[derive(AsRefStr, EnumString, EnumProperty, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum VariableForm {
     Long,
@@ -135,6 +146,7 @@ impl Default for VariableForm {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum NumericForm {
     Numeric,
@@ -151,6 +163,7 @@ impl Default for NumericForm {
 }
 
 #[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Affixes {
     pub prefix: SmartString,
     pub suffix: SmartString,
@@ -165,18 +178,13 @@ impl Default for Affixes {
     }
 }
 
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[derive(Eq, Copy, Clone, Default, PartialEq, Hash)]
 pub struct Formatting {
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub font_style: Option<FontStyle>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub font_variant: Option<FontVariant>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub font_weight: Option<FontWeight>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub vertical_alignment: Option<VerticalAlignment>,
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub text_decoration: Option<TextDecoration>,
     // TODO: put this somewhere else, like directly on text nodes?
     // pub hyperlink: String,
@@ -245,7 +253,7 @@ impl fmt::Debug for Formatting {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum DisplayMode {
     Block,
@@ -256,6 +264,7 @@ pub enum DisplayMode {
 impl EnumGetAttribute for DisplayMode {}
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum TextCase {
     None,
@@ -265,6 +274,10 @@ pub enum TextCase {
     CapitalizeAll,
     Sentence,
     Title,
+    /// CSL-M: leaves this element in its original case, even when an enclosing group or macro
+    /// has a `text-case`.
+    #[strum(props(feature = "text_case_normal"))]
+    Normal,
 }
 
 impl EnumGetAttribute for TextCase {}
@@ -275,7 +288,7 @@ impl Default for TextCase {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum FontStyle {
     Normal,
@@ -291,7 +304,7 @@ impl Default for FontStyle {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum FontVariant {
     Normal,
@@ -306,7 +319,7 @@ impl Default for FontVariant {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum FontWeight {
     Normal,
@@ -322,7 +335,7 @@ impl Default for FontWeight {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum TextDecoration {
     None,
@@ -337,7 +350,7 @@ impl Default for TextDecoration {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum VerticalAlignment {
     #[strum(serialize = "baseline")]
     Baseline,
@@ -355,6 +368,7 @@ impl Default for VerticalAlignment {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum Plural {
     Contextual,
@@ -377,12 +391,14 @@ impl IsIndependent for Cond {
             Cond::Locator(_) => true,
             Cond::Variable(v) => v.is_independent(),
             Cond::IsNumeric(v) => v.is_independent(),
+            Cond::IsPlural(v) => v.is_independent(),
             _ => false,
         }
     }
 }
 
 #[derive(Debug, Eq, Hash, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum Cond {
     IsNumeric(AnyVariable),
     Variable(AnyVariable),
@@ -395,12 +411,18 @@ pub enum Cond {
     HasMonthOrSeason(DateVariable),
     HasDay(DateVariable),
     Context(Context),
-    IsPlural(NameVariable),
+    /// A name variable with more than one name, or a number variable with more than one number
+    IsPlural(AnyVariable),
+    /// The label at the start of the `page` variable, `page` if it has none
+    Page(LocatorType),
+    /// Lowercase, with spaces for hyphens
+    Genre(SmartString),
     Jurisdiction(SmartString),
     SubJurisdiction(u32),
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct CondSet {
     pub match_type: Match,
     pub conds: FnvHashSet<Cond>,
@@ -426,6 +448,8 @@ impl From<ConditionParser> for CondSet {
         for x in cp.has_year_only { conds.insert(Cond::HasYearOnly(x)); }
         for x in cp.has_month_or_season { conds.insert(Cond::HasMonthOrSeason(x)); }
         for x in cp.has_day { conds.insert(Cond::HasDay(x)); }
+        for x in cp.page { conds.insert(Cond::Page(x)); }
+        for x in cp.genre { conds.insert(Cond::Genre(x)); }
         for x in cp.jurisdiction { conds.insert(Cond::Jurisdiction(x)); }
         for x in cp.subjurisdictions { conds.insert(Cond::SubJurisdiction(x)); }
 
@@ -469,15 +493,19 @@ pub(crate) struct ConditionParser {
     /// Original CSL-M is "has-to-month-or-season" which makes no sense.
     pub has_month_or_season: Vec<DateVariable>,
     pub context: Option<Context>,
+    pub page: Vec<LocatorType>,
+    pub genre: Vec<SmartString>,
 
     // undocumented CSL-M features
     // are there are more of these lurking in the citeproc-js codebase?
 
     // https://github.com/Juris-M/citeproc-js/blob/30ceaf50a0ef86517a9a8cd46362e450133c7f91/src/attributes.js#L599-L627
-    pub is_plural: Vec<NameVariable>,
+    /// Also accepts number variables, e.g. `is-plural="locator"`
+    pub is_plural: Vec<AnyVariable>,
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum Context {
     Citation,
@@ -501,10 +529,13 @@ impl ConditionParser {
             && self.subjurisdictions.is_none()
             && self.is_plural.is_empty()
             && self.context.is_none()
+            && self.page.is_empty()
+            && self.genre.is_empty()
     }
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum Match {
     Any,
@@ -523,19 +554,24 @@ impl Default for Match {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 // in CSL 1.0.1, conditions.len() == 1
 pub struct IfThen(pub Conditions, pub Vec<Element>);
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Conditions(pub Match, pub Vec<CondSet>);
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Else(pub Vec<Element>);
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Choose(pub IfThen, pub Vec<IfThen>, pub Else);
 
 #[derive(Debug, Default, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Names {
     // inheritable.
     pub delimiter: Option<SmartString>,
@@ -563,6 +599,7 @@ pub struct Names {
 /// cs:name. Similarly, names-delimiter corresponds to the delimiter attribute on cs:names.
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum NameAnd {
     Text,
@@ -573,12 +610,14 @@ impl EnumGetAttribute for NameAnd {}
 
 /// It is not entirely clear which attributes `<cs:with>` supports.
 #[derive(Debug, Eq, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct NameWith {
     pub formatting: Option<Formatting>,
     pub affixes: Option<Affixes>,
 }
 
 #[derive(Debug, Eq, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Institution {
     pub and: Option<NameAnd>,
     pub delimiter: Option<SmartString>,
@@ -601,6 +640,7 @@ pub struct Institution {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct InstitutionPart {
     pub name: InstitutionPartName,
     pub formatting: Option<Formatting>,
@@ -612,6 +652,7 @@ pub struct InstitutionPart {
 type IfShort = bool;
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum InstitutionPartName {
     Long(IfShort),
@@ -627,6 +668,7 @@ impl Default for InstitutionPartName {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum InstitutionParts {
     Long,
@@ -644,6 +686,7 @@ impl Default for InstitutionParts {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum InstitutionUseFirst {
     /// Set with `use-first="1"`
@@ -656,6 +699,7 @@ pub enum InstitutionUseFirst {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Name {
     pub and: Option<NameAnd>,
     /// Between individual names for the same variable
@@ -795,6 +839,7 @@ impl Name {
     }
 }
 #[derive(Debug, Default, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct NameLabelInput {
     pub form: Option<TermFormExtended>,
     pub formatting: Option<Formatting>,
@@ -838,6 +883,7 @@ impl NameLabelInput {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct NameLabel {
     pub form: TermFormExtended,
     pub formatting: Option<Formatting>,
@@ -849,6 +895,7 @@ pub struct NameLabel {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct NameEtAl {
     // TODO: only accept "et-al" or "and others"
     pub term: String,
@@ -856,6 +903,7 @@ pub struct NameEtAl {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum DemoteNonDroppingParticle {
     Never,
@@ -871,6 +919,7 @@ impl Default for DemoteNonDroppingParticle {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum DelimiterPrecedes {
     Contextual,
@@ -887,6 +936,7 @@ impl Default for DelimiterPrecedes {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum NameForm {
     Long,
@@ -896,6 +946,7 @@ pub enum NameForm {
 impl EnumGetAttribute for NameForm {}
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum NameAsSortOrder {
     First,
@@ -904,6 +955,7 @@ pub enum NameAsSortOrder {
 impl EnumGetAttribute for NameAsSortOrder {}
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum NamePartName {
     Given,
@@ -912,6 +964,7 @@ pub enum NamePartName {
 impl EnumGetAttribute for NamePartName {}
 
 #[derive(Debug, Eq, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct NamePart {
     pub name: NamePartName,
     pub affixes: Option<Affixes>,
@@ -920,9 +973,11 @@ pub struct NamePart {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Substitute(pub Vec<Element>);
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum GivenNameDisambiguationRule {
     AllNames,
@@ -941,6 +996,7 @@ impl Default for GivenNameDisambiguationRule {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum Collapse {
     CitationNumber,
@@ -951,6 +1007,7 @@ pub enum Collapse {
 impl EnumGetAttribute for Collapse {}
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Citation {
     pub disambiguate_add_names: bool,
     pub disambiguate_add_givenname: bool,
@@ -1014,6 +1071,7 @@ impl Citation {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Bibliography {
     pub sort: Option<Sort>,
     pub layout: Layout,
@@ -1029,6 +1087,7 @@ pub struct Bibliography {
 
 /// cs:intext element
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct InText {
     pub layout: Layout,
     pub and: Option<NameAnd>,
@@ -1037,6 +1096,7 @@ pub struct InText {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum SecondFieldAlign {
     Flush,
@@ -1045,6 +1105,7 @@ pub enum SecondFieldAlign {
 impl EnumGetAttribute for SecondFieldAlign {}
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum SubsequentAuthorSubstituteRule {
     CompleteAll,
@@ -1061,11 +1122,13 @@ impl Default for SubsequentAuthorSubstituteRule {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Sort {
     pub keys: Vec<SortKey>,
 }
 
 #[derive(Debug, Eq, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct SortKey {
     pub sort_source: SortSource,
     pub names_min: Option<u32>,
@@ -1094,12 +1157,14 @@ impl SortKey {
 
 /// You must sort on either a variable or a macro
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum SortSource {
     Variable(AnyVariable),
     Macro(SmartString),
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum SortDirection {
     Ascending,
@@ -1115,6 +1180,7 @@ impl Default for SortDirection {
 
 // TODO: Multiple layouts in CSL-M with locale="en es de" etc
 #[derive(Default, Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Layout {
     pub affixes: Option<Affixes>,
     pub formatting: Option<Formatting>,
@@ -1126,13 +1192,14 @@ pub struct Layout {
 
 // Not actually part of a style tree, just a useful place to implement FromNode.
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct MacroMap {
     pub name: SmartString,
     pub elements: Vec<Element>,
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[strum(serialize_all = "kebab_case")]
 pub enum StyleClass {
@@ -1148,6 +1215,7 @@ impl Default for StyleClass {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Style {
     pub class: StyleClass,
     pub macros: FnvHashMap<SmartString, Vec<Element>>,
@@ -1163,6 +1231,8 @@ pub struct Style {
     pub default_locale: Option<Lang>,
     pub version_req: CslVersionReq,
     pub page_range_format: Option<PageRangeFormat>,
+    /// CSL-M only
+    pub year_range_format: Option<PageRangeFormat>,
     pub demote_non_dropping_particle: DemoteNonDroppingParticle,
     pub initialize_with_hyphen: bool, // default is true
 }
@@ -1183,6 +1253,7 @@ impl Default for Style {
             default_locale: None,
             version_req: CslVersionReq::current_csl(),
             page_range_format: None,
+            year_range_format: None,
             demote_non_dropping_particle: Default::default(),
             initialize_with_hyphen: true,
         }
@@ -1224,6 +1295,7 @@ impl Style {
 }
 
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct RangeDelimiter(pub SmartString);
 
 impl Default for RangeDelimiter {
@@ -1246,6 +1318,7 @@ impl FromStr for RangeDelimiter {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum DateParts {
     YearMonthDay,
@@ -1271,6 +1344,7 @@ pub(crate) enum DatePartName {
 impl EnumGetAttribute for DatePartName {}
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum DayForm {
     Numeric,
@@ -1285,6 +1359,7 @@ impl Default for DayForm {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum MonthForm {
     Long,
@@ -1300,10 +1375,14 @@ impl Default for MonthForm {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum YearForm {
     Long,
     Short,
+    /// CSL-M only. The era name and the year of the era, e.g. 平成15 for 2003.
+    #[strum(props(feature = "date_form_imperial"))]
+    Imperial,
 }
 impl EnumGetAttribute for YearForm {}
 impl Default for YearForm {
@@ -1313,14 +1392,19 @@ impl Default for YearForm {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum DateForm {
     Text,
     Numeric,
+    /// CSL-M only. Uses the locale's `<date form="imperial">`, or else e.g. 平成15年3月2日.
+    #[strum(props(feature = "date_form_imperial"))]
+    Imperial,
 }
 impl EnumGetAttribute for DateForm {}
 
 #[derive(Debug, Display, Eq, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum DatePartForm {
     Day(DayForm),
     Month(MonthForm, StripPeriods),
@@ -1352,6 +1436,7 @@ impl PartialOrd for DatePartForm {
 }
 
 #[derive(Debug, Default, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct DatePart {
     pub form: DatePartForm,
     pub affixes: Option<Affixes>,
@@ -1370,6 +1455,7 @@ impl Default for DatePartForm {
 /// A date element that fully defines its own output.
 /// It is 'independent' of any localization.
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct IndependentDate {
     pub variable: DateVariable,
     // TODO: limit each <date-part name="XXX"> to one per?
@@ -1383,6 +1469,7 @@ pub struct IndependentDate {
 
 /// A date element in the main body of a style that refers to a `LocaleDate`
 #[derive(Debug, Eq, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct LocalizedDate {
     pub variable: DateVariable,
     pub parts_selector: DateParts,
@@ -1395,6 +1482,7 @@ pub struct LocalizedDate {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum Position {
     First,
@@ -1448,6 +1536,7 @@ impl Position {
 
 /// [Spec](https://docs.citationstyles.org/en/stable/specification.html#appendix-v-page-range-formats)
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum PageRangeFormat {
     Chicago,
@@ -1458,6 +1547,7 @@ pub enum PageRangeFormat {
 impl EnumGetAttribute for PageRangeFormat {}
 
 #[derive(AsRefStr, EnumProperty, EnumIter, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum CslType {
     Article,
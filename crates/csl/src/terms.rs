@@ -15,6 +15,7 @@ use super::attr::{EnumGetAttribute, GetAttribute};
 use super::variables::{NameVariable, NumberVariable};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum TextTermSelector {
     Simple(SimpleTermSelector),
     Gendered(GenderedTermSelector),
@@ -103,6 +104,7 @@ impl GetAttribute for AnyTermName {
 
 /// TermSelector is used
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum SimpleTermSelector {
     Misc(MiscTerm, TermFormExtended),
     Category(Category, TermForm),
@@ -129,6 +131,7 @@ impl SimpleTermSelector {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct OrdinalTermSelector(pub OrdinalTerm, pub Gender);
 
 struct OrdinalTermIter(Option<OrdinalTerm>);
@@ -179,6 +182,7 @@ impl OrdinalTermSelector {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum GenderedTermSelector {
     /// Edition is the only MiscTerm that can have a gender, so it's here instead
     Number(NumberVariable, TermForm),
@@ -267,6 +271,7 @@ impl GenderedTermSelector {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct RoleTermSelector(pub RoleTerm, pub TermFormExtended);
 
 impl RoleTermSelector {
@@ -280,9 +285,11 @@ impl RoleTermSelector {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct GenderedTerm(pub TermPlurality, pub Gender);
 
 #[derive(AsRefStr, EnumString, EnumProperty, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum TermForm {
     Long,
@@ -319,6 +326,7 @@ impl Iterator for TermFallbackIter {
 }
 /// Includes the extra Verb and VerbShort variants
 #[derive(AsRefStr, EnumString, EnumProperty, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum TermFormExtended {
     Long,
@@ -360,6 +368,7 @@ impl Iterator for TermFallbackExtendedIter {
 }
 
 #[derive(AsRefStr, EnumString, EnumProperty, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum TermPlurality {
     Pluralized { single: String, multiple: String },
@@ -430,6 +439,7 @@ impl TermPlurality {
 ///    2. Would also look up OridnalMatch::LastTwoDigits Neuter
 ///
 #[derive(AsStaticStr, EnumString, EnumProperty, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum Gender {
     Masculine,
@@ -447,6 +457,7 @@ impl Default for Gender {
 /// [Spec](https://docs.citationstyles.org/en/stable/specification.html#ordinal-suffixes)
 /// LastTwoDigits is the default
 #[derive(AsStaticStr, EnumString, EnumProperty, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum OrdinalMatch {
     /// Default for `Mod100(n) if n < 10`. Matches 9, 29, 109, 129.
@@ -464,8 +475,8 @@ impl Default for OrdinalMatch {
 }
 
 /// [Spec](https://docs.citationstyles.org/en/stable/specification.html#locators)
-#[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Deserialize))]
+#[derive(AsRefStr, EnumProperty, EnumIter, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[non_exhaustive]
@@ -521,6 +532,7 @@ impl Default for LocatorType {
 
 /// [Spec](https://docs.citationstyles.org/en/stable/specification.html#quotes)
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 pub enum QuoteTerm {
     OpenQuote,
@@ -530,6 +542,7 @@ pub enum QuoteTerm {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 // Strum's auto kebab_case doesn't hyphenate to "season-01", so manual it is
 pub enum SeasonTerm {
     #[strum(serialize = "season-01")]
@@ -545,6 +558,7 @@ pub enum SeasonTerm {
 /// Yes, this differs slightly from NameVariable.
 /// It includes "editortranslator" for the names special case.
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 #[non_exhaustive]
 pub enum RoleTerm {
@@ -627,7 +641,7 @@ impl RoleTerm {
 /// here](https://docs.citationstyles.org/en/stable/specification.html#gender-specific-ordinals)
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 #[non_exhaustive]
@@ -666,6 +680,7 @@ pub enum Category {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 #[non_exhaustive]
 pub enum MiscTerm {
@@ -713,6 +728,7 @@ pub enum MiscTerm {
 
 /// [Spec](https://docs.citationstyles.org/en/stable/specification.html#months)
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[non_exhaustive]
 pub enum MonthTerm {
     #[strum(serialize = "month-01")]
@@ -764,6 +780,7 @@ impl MonthTerm {
 
 /// [Spec](https://docs.citationstyles.org/en/stable/specification.html#quotes)
 #[derive(EnumProperty, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum OrdinalTerm {
     Ordinal,
     Mod100(u32, OrdinalMatch),
@@ -170,3 +170,35 @@ fn wrong_tag_name() {
     "#
     );
 }
+
+#[test]
+fn gated_conditions() {
+    let parse = |attr: &str| {
+        let xml = format!(
+            r#"<style version="1.0" class="in-text">
+                <citation><layout>
+                    <choose><if {}><text value="x" /></if></choose>
+                </layout></citation>
+            </style>"#,
+            attr
+        );
+        Style::parse_for_test(&xml, None)
+    };
+    // context was accepted before it was a feature
+    assert!(parse(r#"context="bibliography""#).is_ok());
+    assert!(parse(r#"page="chapter""#).is_err());
+    assert!(parse(r#"genre="phd-thesis""#).is_err());
+}
+
+#[test]
+fn institution_without_feature() {
+    let style = Style::parse_for_test(
+        r#"<style version="1.0" class="in-text">
+            <citation><layout>
+                <names variable="author"><name /><institution /></names>
+            </layout></citation>
+        </style>"#,
+        None,
+    );
+    assert!(style.is_ok());
+}
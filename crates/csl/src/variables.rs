@@ -6,6 +6,9 @@
 
 use std::str::FromStr;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use super::attr::{EnumGetAttribute, GetAttribute};
 use super::error::*;
 use super::version::Features;
@@ -13,6 +16,7 @@ use super::IsIndependent;
 use super::Style;
 
 #[derive(Debug, Eq, Copy, Clone, PartialEq, EnumProperty, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum AnyVariable {
     Ordinary(Variable),
     Name(NameVariable),
@@ -64,6 +68,7 @@ impl GetAttribute for AnyVariable {
 /// [Spec](https://docs.citationstyles.org/en/stable/specification.html#number-variables)
 
 #[derive(Debug, Eq, Copy, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum StandardVariable {
     Ordinary(Variable),
     Number(NumberVariable),
@@ -121,6 +126,7 @@ impl IsIndependent for Variable {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 #[non_exhaustive]
 pub enum Variable {
@@ -283,6 +289,24 @@ impl Variable {
     pub fn should_replace_hyphens(self) -> bool {
         false
     }
+    /// The variables that CSL-M `leading-noise-words` are stripped from when sorting.
+    pub fn is_title(self) -> bool {
+        match self {
+            Variable::Title
+            | Variable::TitleShort
+            | Variable::ContainerTitle
+            | Variable::ContainerTitleShort
+            | Variable::CollectionTitle
+            | Variable::CollectionTitleShort
+            | Variable::OriginalTitle
+            | Variable::ReviewedTitle
+            | Variable::EventTitle
+            | Variable::PartTitle
+            | Variable::VolumeTitle
+            | Variable::VolumeTitleShort => true,
+            _ => false,
+        }
+    }
     pub fn hyperlink(self, value: &str) -> Option<&str> {
         match self {
             Variable::URL => Some(value),
@@ -305,6 +329,7 @@ impl IsIndependent for NumberVariable {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 #[non_exhaustive]
 pub enum NumberVariable {
@@ -358,6 +383,14 @@ impl NumberVariable {
             _ => false,
         }
     }
+    /// Rendered with `<number>`'s logic even through `<text variable>`, so page ranges are
+    /// formatted, and sorted as numbers in macros.
+    pub fn is_page_like(self) -> bool {
+        match self {
+            NumberVariable::Locator | NumberVariable::Page | NumberVariable::PageFirst => true,
+            _ => false,
+        }
+    }
     pub fn is_quantity(self) -> bool {
         match self {
             NumberVariable::NumberOfVolumes => true,
@@ -370,6 +403,7 @@ impl NumberVariable {
 #[derive(
     AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd,
 )]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 #[non_exhaustive]
 pub enum NameVariable {
@@ -407,8 +441,8 @@ pub enum NameVariable {
     )]
     EditorTranslator,
 
-    /// CSL-M only
-    #[strum(props(feature = "cslm_legal"))]
+    /// CSL-M only. An institution; a plain-string `authority` in the input is rendered as one.
+    #[strum(props(feature = "institutions"))]
     Authority,
 
     /// The dummy name variable is always empty. Use it to force all name variables called through
@@ -441,6 +475,7 @@ impl Default for NameVariable {
 }
 
 #[derive(AsRefStr, EnumProperty, EnumString, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[strum(serialize_all = "kebab_case")]
 #[non_exhaustive]
 pub enum DateVariable {
@@ -38,6 +38,19 @@ impl serde::Serialize for CslVersionReq {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CslVersionReq {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+        VersionReq::parse(&s)
+            .map(CslVersionReq)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct CslCslMVersionReq(pub CslVariant, pub VersionReq);
@@ -103,22 +116,13 @@ macro_rules! declare_features {
             &[(&str, &str, Option<u32>, Option<()>, fn(&mut Features))] =
             &[$((stringify!($feature), $ver, $issue, $edition, set!($feature))),+];
 
-        #[cfg(feature = "serde")]
-        fn is_false(b: &bool) -> bool {
-            !*b
-        }
-
         /// A set of features declared / enabled by a style.
-        #[derive(Clone, Eq, PartialEq, Default)]
-        #[cfg_attr(feature = "serde", derive(serde::Serialize))]
-        #[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+        #[derive(Clone, Eq, PartialEq, Hash, Default)]
         pub struct Features {
             /// `(name, Option<since_version>)`: already accepted features that have nevertheless been declared by a style
-            #[cfg_attr(feature = "serde", serde(skip_serializing))]
             pub declared_lang_features: Vec<(Atom, Option<Atom>)>,
             $(
                 $(#[$feat_meta])*
-                #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_false"))]
                 pub $feature: bool,
             )+
         }
@@ -204,28 +208,16 @@ declare_features!(
     (placeholder, parallel_citations, "1.0.1", None, None),
     // includes legal_case form=short abbreviations, for now
     (placeholder, abbreviations, "1.0.1", None, None),
-    (placeholder, condition_page, "1.0.1", None, None),
-    (placeholder, condition_context, "1.0.1", None, None),
-    (placeholder, condition_genre, "1.0.1", None, None),
-    // should include Authority being an institutional author?
-    (placeholder, institutions, "1.0.1", None, None),
     // layout locale matching, default-locale-sort, name-as-sort-order languages, name-never-sort
     (placeholder, multilingual, "1.0.1", None, None),
     (placeholder, hereinafter, "1.0.1", None, None),
-    (placeholder, date_form_imperial, "1.0.1", None, None),
     // (currently includes the dodgy macro label-form="..." business)
     (placeholder, multiple_locators, "1.0.1", None, None),
     (placeholder, locator_extras, "1.0.1", None, None),
-    (placeholder, leading_noise_words, "1.0.1", None, None),
     (placeholder, name_as_reverse_order, "1.0.1", None, None),
-    (placeholder, skip_words, "1.0.1", None, None),
     (placeholder, subgroup_delimiter, "1.0.1", None, None),
     (placeholder, suppress_min_max, "1.0.1", None, None),
-    (placeholder, text_case_normal, "1.0.1", None, None),
-    (placeholder, year_range_format, "1.0.1", None, None),
     (placeholder, jurisdictions, "1.0.1", None, None),
-    // E.g. page and page-first become numeric variables
-    (placeholder, more_numerics, "1.0.1", None, None),
     (placeholder, var_license, "1.0.1", None, None),
     (placeholder, var_document_name, "1.0.1", None, None),
     (placeholder, var_part_number, "1.0.1", None, None),
@@ -245,6 +237,15 @@ declare_features!(
     (active, conditions, "1.0.1", None, None),
     /// includes condition matchers `has-day="issued [date vars...]"`/`has-year-only="issued"`/`has-month-or-season="issued"`
     (active, condition_date_parts, "1.0.1", None, None),
+    /// `page="page chapter ..."`, testing the label written at the start of the `page` variable,
+    /// e.g. `chap. 5`. Without a label it is `page`.
+    (active, condition_page, "1.0.1", None, None),
+    /// `context="citation"` and `context="bibliography"`. These are also accepted without the
+    /// feature, as they always have been.
+    (active, condition_context, "1.0.1", None, None),
+    /// `genre="phd-thesis ..."`, matching the `genre` variable ignoring case, with hyphens
+    /// standing in for spaces
+    (active, condition_genre, "1.0.1", None, None),
     /// `issued: "1981-09"`; `issued: "198X"` etc. Also via `"issued": { "edtf": "..." }`.
     (active, edtf_dates, "1.1", None, None),
     /// includes types: gazette, hearing, regulation
@@ -266,6 +267,26 @@ declare_features!(
     (active, legal_locators, "1.0.1", None, None),
     /// `<text term="unpublished">`
     (active, term_unpublished, "1.0.1", None, None),
+    /// `<institution>` in `<names>`, for rendering `{ "institution": [...] }` names. Also renders
+    /// a plain-string `authority` through `<names variable="authority">`.
+    (active, institutions, "1.0.1", None, None),
+    /// `text-case="title"` for German, French and Spanish items, with their own stopwords, and
+    /// their conventions for `text-case="sentence"`. Otherwise only English items are title-cased.
+    (active, language_text_case, "1.0.1", None, None),
+    /// `leading-noise-words` on a locale's `<style-options>`, stripped from titles when sorting.
+    /// Without them, the articles for the reference's language are used.
+    (active, leading_noise_words, "1.0.1", None, None),
+    /// `skip-words` on a locale's `<style-options>`, which title case leaves in lowercase
+    /// instead of its built-in stopwords
+    (active, skip_words, "1.0.1", None, None),
+    /// `text-case="normal"`, and `text-case` on `<group>`
+    (active, text_case_normal, "1.0.1", None, None),
+    /// `<date form="imperial">` and `<date-part name="year" form="imperial">`, for Japanese era
+    /// years
+    (active, date_form_imperial, "1.0.1", None, None),
+    /// `year-range-format` on `<style>`, which crops the end year of a date range like
+    /// `page-range-format` does
+    (active, year_range_format, "1.0.1", None, None),
 );
 
 // status, name, first added version, tracking issue, edition, None
@@ -275,6 +296,8 @@ declare_features!(
     (accepted, standard_type, "1.0.2", None, None),
     (accepted, software_type, "1.0.2", None, None),
     (accepted, periodical_type, "1.0.2", None, None),
+    // `page` and `page-first` are number variables
+    (accepted, more_numerics, "1.0.2", None, None),
 );
 
 // status, name, first added version, tracking issue, None, reason(str)
@@ -348,6 +371,27 @@ pub fn read_features_into<'a>(
 #[cfg(feature = "serde")]
 use serde::de::{DeserializeSeed, Deserializer, Error, Unexpected, Visitor};
 
+/// The same list of kebab-case feature names that `Deserialize` reads, including any accepted
+/// features the style declared.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Features {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut names = Vec::new();
+        self.walk_feature_fields(|name, enabled| {
+            if enabled {
+                names.push(name.replace('_', "-"));
+            }
+        });
+        for (name, _) in &self.declared_lang_features {
+            names.push(name.replace('_', "-"));
+        }
+        serializer.collect_seq(names)
+    }
+}
+
 #[cfg(feature = "serde")]
 impl<'de> serde::Deserialize<'de> for Features {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -0,0 +1,51 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! With the `bundled-locales` feature, compresses every `locales/locales-xx-XX.xml` into
+//! `OUT_DIR`, and writes `bundled_locales.rs` listing them for `src/bundled.rs` to include.
+
+fn main() {
+    println!("cargo:rerun-if-changed=locales");
+    bundle_locales();
+}
+
+#[cfg(feature = "bundled-locales")]
+fn bundle_locales() {
+    use std::env;
+    use std::fs;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    let mut entries: Vec<(String, PathBuf)> = Vec::new();
+    for entry in fs::read_dir(manifest_dir.join("locales")).expect("locales directory") {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let lang = match name
+            .strip_prefix("locales-")
+            .and_then(|rest| rest.strip_suffix(".xml"))
+        {
+            Some(lang) => lang.to_owned(),
+            None => continue,
+        };
+        let xml = fs::read(&path).unwrap();
+        let compressed = miniz_oxide::deflate::compress_to_vec(&xml, 10);
+        let out_path = out_dir.join(format!("{}.xml.deflate", lang));
+        fs::write(&out_path, compressed).unwrap();
+        entries.push((lang, out_path));
+    }
+    entries.sort();
+    let mut file = fs::File::create(out_dir.join("bundled_locales.rs")).unwrap();
+    writeln!(file, "static COMPRESSED: &[(&str, &[u8])] = &[").unwrap();
+    for (lang, path) in &entries {
+        writeln!(file, "    ({:?}, include_bytes!({:?})),", lang, path).unwrap();
+    }
+    writeln!(file, "];").unwrap();
+}
+
+#[cfg(not(feature = "bundled-locales"))]
+fn bundle_locales() {}
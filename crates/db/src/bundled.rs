@@ -0,0 +1,80 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! Every locale from `locales/`, embedded by `build.rs` with the `bundled-locales` feature.
+
+use crate::xml::{LocaleFetchError, LocaleFetcher, PredefinedLocales};
+use csl::Lang;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+include!(concat!(env!("OUT_DIR"), "/bundled_locales.rs"));
+
+/// Decompressed the first time anything asks for them, not at startup.
+static BUNDLED: Lazy<HashMap<Lang, String>> = Lazy::new(|| {
+    COMPRESSED
+        .iter()
+        .filter_map(|&(lang, compressed)| {
+            let lang = match Lang::from_str(lang) {
+                Ok(lang) => lang,
+                Err(_) => {
+                    warn!("bundled locale {} has an invalid language tag", lang);
+                    return None;
+                }
+            };
+            let xml = miniz_oxide::inflate::decompress_to_vec(compressed)
+                .expect("bundled locale was compressed by build.rs");
+            let xml = String::from_utf8(xml).expect("bundled locale is utf-8");
+            Some((lang, xml))
+        })
+        .collect()
+});
+
+/// A [LocaleFetcher] for every locale in the CSL locales repository, as of the snapshot in
+/// `locales/`. Run `script/update-locales.sh` to refresh it.
+///
+/// The locales are shared by every processor using this fetcher; only the one asked for is
+/// copied out.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct BundledLocales;
+
+impl LocaleFetcher for BundledLocales {
+    fn fetch_string(&self, lang: &Lang) -> Result<Option<String>, LocaleFetchError> {
+        Ok(BUNDLED.get(lang).cloned())
+    }
+}
+
+impl PredefinedLocales {
+    /// Every locale in the CSL locales repository, as of the snapshot in `locales/`. Run
+    /// `script/update-locales.sh` to refresh it.
+    pub fn bundled_all() -> BundledLocales {
+        BundledLocales
+    }
+}
+
+#[test]
+fn bundled_has_en_us() {
+    assert_eq!(
+        PredefinedLocales::bundled_all()
+            .fetch_string(&Lang::en_us())
+            .unwrap()
+            .as_deref(),
+        Some(csl::locale::EN_US)
+    );
+}
+
+#[test]
+fn bundled_has_other_locales() {
+    for tag in &["de-DE", "fr-FR", "zh-CN"] {
+        let lang = Lang::from_str(tag).unwrap();
+        assert!(
+            BundledLocales.fetch_locale(&lang).is_some(),
+            "{} is not bundled",
+            tag
+        );
+    }
+}
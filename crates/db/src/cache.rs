@@ -0,0 +1,93 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use csl::{Features, Locale, ParseOptions, Style, StyleError};
+use fnv::FnvHashMap;
+use std::sync::{Arc, RwLock};
+
+/// Parsed styles and locales, keyed by their XML, to be shared by many processors. Pass one to
+/// each processor as `InitOptions::parse_cache`, and every style or locale is only parsed once.
+///
+/// With the `serde1` feature on `csl`, `Style` and `Locale` can be serialized, and loaded back
+/// in with [ParseCache::insert_style] and [ParseCache::insert_locale] to skip parsing
+/// altogether, e.g. from a bincode file.
+#[derive(Default)]
+pub struct ParseCache {
+    styles: RwLock<FnvHashMap<StyleKey, Arc<Style>>>,
+    locales: RwLock<FnvHashMap<String, Arc<Locale>>>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct StyleKey {
+    xml: String,
+    allow_no_info: bool,
+    features: Option<Features>,
+}
+
+impl StyleKey {
+    fn new(xml: &str, options: &ParseOptions) -> Self {
+        StyleKey {
+            xml: xml.into(),
+            allow_no_info: options.allow_no_info,
+            features: options.features.clone(),
+        }
+    }
+}
+
+impl ParseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Like `Style::parse_with_opts`, but only the first call for each `xml` and `options`
+    /// parses anything.
+    pub fn style(&self, xml: &str, options: ParseOptions) -> Result<Arc<Style>, StyleError> {
+        let key = StyleKey::new(xml, &options);
+        if let Some(style) = self.styles.read().unwrap().get(&key) {
+            return Ok(style.clone());
+        }
+        let style = Arc::new(Style::parse_with_opts(xml, options)?);
+        self.styles.write().unwrap().insert(key, style.clone());
+        Ok(style)
+    }
+
+    /// Like `Locale::parse`, but only the first call for each `xml` parses anything.
+    pub fn locale(&self, xml: &str) -> Result<Arc<Locale>, StyleError> {
+        if let Some(locale) = self.locales.read().unwrap().get(xml) {
+            return Ok(locale.clone());
+        }
+        let locale = Arc::new(Locale::parse(xml)?);
+        self.locales
+            .write()
+            .unwrap()
+            .insert(xml.into(), locale.clone());
+        Ok(locale)
+    }
+
+    /// Stores a style that was parsed from `xml` with `options` elsewhere, e.g. deserialized.
+    pub fn insert_style(&self, xml: &str, options: &ParseOptions, style: Arc<Style>) {
+        let key = StyleKey::new(xml, options);
+        self.styles.write().unwrap().insert(key, style);
+    }
+
+    /// Stores a locale that was parsed from `xml` elsewhere, e.g. deserialized.
+    pub fn insert_locale(&self, xml: &str, locale: Arc<Locale>) {
+        self.locales.write().unwrap().insert(xml.into(), locale);
+    }
+
+    pub fn clear(&self) {
+        self.styles.write().unwrap().clear();
+        self.locales.write().unwrap().clear();
+    }
+}
+
+#[test]
+fn parses_once() {
+    let cache = ParseCache::new();
+    let a = cache.locale(csl::locale::EN_US).unwrap();
+    let b = cache.locale(csl::locale::EN_US).unwrap();
+    assert!(Arc::ptr_eq(&a, &b));
+}
@@ -5,12 +5,13 @@
 // Copyright © 2019 Corporation for Digital Scholarship
 
 use super::cluster::*;
+use super::section::BibliographySection;
 use super::xml::{LocaleDatabase, StyleDatabase};
 
 use std::sync::Arc;
 
 use citeproc_io::output::markup::Markup;
-use citeproc_io::{Cite, ClusterMode, Reference};
+use citeproc_io::{Cite, ClusterMode, RawDateError, RawDateParser, Reference, SmartString};
 use csl::Atom;
 
 use fnv::FnvHashSet;
@@ -22,6 +23,13 @@ pub trait CiteDatabase: LocaleDatabase + StyleDatabase {
     #[salsa::input]
     fn reference_input(&self, key: Atom) -> Arc<Reference>;
     fn reference(&self, key: Atom) -> Option<Arc<Reference>>;
+    /// The raw dates on a reference that couldn't be parsed in English or in the reference's
+    /// own language. They render as their fallback values.
+    fn raw_date_errors(&self, key: Atom) -> Arc<Vec<RawDateError>>;
+
+    /// Whether to split literal names into given/family parts. Off by default.
+    #[salsa::input]
+    fn parse_literal_names(&self) -> bool;
 
     #[salsa::input]
     fn all_keys(&self) -> Arc<IndexSet<Atom>>;
@@ -63,6 +71,13 @@ pub trait CiteDatabase: LocaleDatabase + StyleDatabase {
     fn all_cite_ids(&self) -> Arc<Vec<CiteId>>;
 
     fn clusters_sorted(&self) -> Arc<Vec<ClusterData>>;
+
+    #[salsa::input]
+    fn bibliography_sections(&self) -> Arc<Vec<BibliographySection>>;
+
+    /// The references in a named bibliography section, before sorting.
+    #[salsa::invoke(crate::section::section_keys)]
+    fn section_keys(&self, name: SmartString) -> Option<Arc<IndexSet<Atom>>>;
 }
 
 #[macro_export]
@@ -118,11 +133,50 @@ pub struct ClusterData {
 }
 
 fn reference(db: &dyn CiteDatabase, key: Atom) -> Option<Arc<Reference>> {
-    if db.all_keys().contains(&key) {
-        Some(db.reference_input(key))
-    } else {
-        None
+    if !db.all_keys().contains(&key) {
+        return None;
+    }
+    let input = db.reference_input(key);
+    let parse_names = db.parse_literal_names();
+    if input.raw_dates.is_empty() && !parse_names {
+        return Some(input);
+    }
+    let mut reference = (*input).clone();
+    if !reference.raw_dates.is_empty() {
+        // Some raw dates weren't English. Try again with the reference's own month and season
+        // names. The ones that still fail are reported by raw_date_errors.
+        let parser = raw_date_parser(db, &reference);
+        reference.reparse_raw_dates(&parser);
     }
+    if parse_names {
+        reference.parse_literal_names();
+    }
+    Some(Arc::new(reference))
+}
+
+fn raw_date_parser(db: &dyn CiteDatabase, reference: &Reference) -> RawDateParser {
+    let lang = reference
+        .language
+        .clone()
+        .unwrap_or_else(|| db.default_lang());
+    let mut parser = RawDateParser::english().clone();
+    parser.add_locale(&db.merged_locale(lang));
+    parser
+}
+
+fn raw_date_errors(db: &dyn CiteDatabase, key: Atom) -> Arc<Vec<RawDateError>> {
+    let reference = match db.reference(key) {
+        Some(reference) if !reference.raw_dates.is_empty() => reference,
+        _ => return Arc::new(Vec::new()),
+    };
+    let parser = raw_date_parser(db, &reference);
+    let mut raw_dates: Vec<_> = reference.raw_dates.iter().collect();
+    raw_dates.sort_by(|a, b| AsRef::<str>::as_ref(a.0).cmp(AsRef::<str>::as_ref(b.0)));
+    let errors = raw_dates
+        .into_iter()
+        .filter_map(|(_, raw)| parser.parse(raw).err())
+        .collect();
+    Arc::new(errors)
 }
 
 /// Type to represent which references should appear in a bibiliography even if they are not cited
@@ -1,13 +1,23 @@
 #[macro_use]
 extern crate log;
 
+#[cfg(feature = "bundled-locales")]
+mod bundled;
+mod cache;
 mod cite;
 mod cluster;
+mod section;
+mod style_fetcher;
 mod xml;
 
+#[cfg(feature = "bundled-locales")]
+pub use bundled::BundledLocales;
+pub use cache::ParseCache;
 pub use cite::*;
 use citeproc_io::output::markup::Markup;
 pub use cluster::*;
+pub use section::*;
+pub use style_fetcher::*;
 pub use xml::*;
 
 use salsa::Durability;
@@ -20,6 +30,8 @@ pub fn safe_default(db: &mut (impl cite::CiteDatabase + xml::LocaleDatabase + xm
     db.set_all_uncited(Default::default());
     db.set_all_cluster_ids(Arc::new(Default::default()));
     db.set_clusters_ordered(Arc::new(Default::default()));
+    db.set_bibliography_sections(Arc::new(Default::default()));
     db.set_locale_input_langs_with_durability(Default::default(), Durability::HIGH);
     db.set_default_lang_override_with_durability(Default::default(), Durability::HIGH);
+    db.set_parse_literal_names_with_durability(false, Durability::HIGH);
 }
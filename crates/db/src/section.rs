@@ -0,0 +1,202 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use super::cite::{CiteDatabase, ClusterData};
+use super::cluster::ClusterId;
+
+use std::sync::Arc;
+
+use citeproc_io::{Reference, SmartString};
+use csl::{AnyVariable, Atom, Variable};
+use serde_derive::{Deserialize, Serialize};
+
+use indexmap::set::IndexSet;
+
+/// A part of the bibliography that is sorted and numbered on its own, e.g. the references for one
+/// chapter, or the primary sources in a legal work.
+///
+/// A section starts with every reference that would be in the full bibliography, or only the ones
+/// cited in a range of clusters, and then applies its filter.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BibliographySection<Id = ClusterId> {
+    pub name: SmartString,
+    /// Only the references cited in these clusters, in document order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clusters: Option<ClusterRange<Id>>,
+    #[serde(flatten)]
+    pub filter: BibliographyFilter,
+}
+
+/// From `first` to `last` inclusive, in document order. Clusters that have not been given a
+/// position with `set_cluster_order` are not in any range.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ClusterRange<Id = ClusterId> {
+    pub first: Id,
+    pub last: Id,
+}
+
+impl ClusterRange {
+    /// The positions of `first` and `last` in document order, if both have one.
+    fn positions(&self, clusters: &[ClusterData]) -> Option<(usize, usize)> {
+        let position = |id: &ClusterId| clusters.iter().position(|c| c.id == *id);
+        Some((position(&self.first)?, position(&self.last)?))
+    }
+
+    /// Whether a cluster is within the range.
+    pub fn contains(&self, db: &dyn CiteDatabase, id: ClusterId) -> bool {
+        let clusters = db.clusters_sorted();
+        match (
+            self.positions(&clusters),
+            clusters.iter().position(|c| c.id == id),
+        ) {
+            (Some((first, last)), Some(pos)) => first <= pos && pos <= last,
+            _ => false,
+        }
+    }
+}
+
+/// The citeproc-js bibliography filters. Empty lists don't filter anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BibliographyFilter {
+    /// A reference must match all of these.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub select: Vec<FieldMatch>,
+    /// A reference must match at least one of these.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<FieldMatch>,
+    /// A reference must not match any of these.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<FieldMatch>,
+    /// A reference must not match all of these.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quash: Vec<FieldMatch>,
+}
+
+/// `field` is `type`, `keyword` (which matches any one of a `;` or `,` separated list), or the
+/// name of an ordinary or number variable, whose value must equal `value` exactly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FieldMatch {
+    pub field: SmartString,
+    pub value: SmartString,
+}
+
+impl FieldMatch {
+    pub fn new(field: &str, value: &str) -> Self {
+        FieldMatch {
+            field: field.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn matches(&self, refr: &Reference) -> bool {
+        let value = self.value.as_str();
+        match self.field.as_str() {
+            "type" => refr.csl_type.as_ref() == value,
+            "keyword" => refr
+                .ordinary
+                .get(&Variable::Keyword)
+                .map_or(false, |keywords| {
+                    keywords.split(&[';', ','][..]).any(|k| k.trim() == value)
+                }),
+            field => match field.parse() {
+                Ok(AnyVariable::Ordinary(v)) => {
+                    refr.ordinary.get(&v).map_or(false, |x| x.as_str() == value)
+                }
+                Ok(AnyVariable::Number(v)) => refr
+                    .number
+                    .get(&v)
+                    .map_or(false, |x| x.clone().into_string() == value),
+                _ => false,
+            },
+        }
+    }
+}
+
+impl BibliographyFilter {
+    pub fn matches(&self, refr: &Reference) -> bool {
+        let any = |list: &[FieldMatch]| list.iter().any(|m| m.matches(refr));
+        let all = |list: &[FieldMatch]| list.iter().all(|m| m.matches(refr));
+        all(&self.select)
+            && (self.include.is_empty() || any(&self.include))
+            && !any(&self.exclude)
+            && (self.quash.is_empty() || !all(&self.quash))
+    }
+}
+
+/// The references in a section, in the same order as `disamb_participants`. None if there is no
+/// section with that name.
+pub(crate) fn section_keys(
+    db: &dyn CiteDatabase,
+    name: SmartString,
+) -> Option<Arc<IndexSet<Atom>>> {
+    let sections = db.bibliography_sections();
+    let section = sections.iter().find(|s| s.name == name)?;
+    let candidates = match &section.clusters {
+        None => db.disamb_participants(),
+        Some(range) => {
+            let clusters = db.clusters_sorted();
+            let all = db.all_keys();
+            let mut keys = IndexSet::new();
+            if let Some((first, last)) = range.positions(&clusters) {
+                for cluster in clusters.iter().take(last + 1).skip(first) {
+                    for cite_id in cluster.cites.iter() {
+                        let ref_id = &cite_id.lookup(db).ref_id;
+                        if all.contains(ref_id) {
+                            keys.insert(ref_id.clone());
+                        }
+                    }
+                }
+            }
+            Arc::new(keys)
+        }
+    };
+    let keys = candidates
+        .iter()
+        .filter(|key| {
+            db.reference((*key).clone())
+                .map_or(false, |refr| section.filter.matches(&refr))
+        })
+        .cloned()
+        .collect();
+    Some(Arc::new(keys))
+}
+
+#[test]
+fn filters() {
+    use csl::CslType;
+    let mut refr = Reference::empty("a".into(), CslType::LegalCase);
+    refr.ordinary.insert(Variable::Keyword, "primary; cases".into());
+    let filter = |f: BibliographyFilter| f.matches(&refr);
+    let case = FieldMatch::new("type", "legal_case");
+    let book = FieldMatch::new("type", "book");
+    let primary = FieldMatch::new("keyword", "primary");
+    assert!(filter(BibliographyFilter::default()));
+    assert!(filter(BibliographyFilter {
+        select: vec![case.clone(), primary.clone()],
+        ..Default::default()
+    }));
+    assert!(!filter(BibliographyFilter {
+        select: vec![case.clone(), book.clone()],
+        ..Default::default()
+    }));
+    assert!(filter(BibliographyFilter {
+        include: vec![case.clone(), book.clone()],
+        ..Default::default()
+    }));
+    assert!(!filter(BibliographyFilter {
+        exclude: vec![book.clone(), primary.clone()],
+        ..Default::default()
+    }));
+    assert!(filter(BibliographyFilter {
+        quash: vec![case.clone(), book.clone()],
+        ..Default::default()
+    }));
+    assert!(!filter(BibliographyFilter {
+        quash: vec![case, primary],
+        ..Default::default()
+    }));
+}
@@ -0,0 +1,126 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::panic::RefUnwindSafe;
+use std::path::PathBuf;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "parallel")] {
+        /// Finds the independent parent of a dependent style. See [LocaleFetcher](crate::LocaleFetcher)
+        /// for why it must be RefUnwindSafe.
+        pub trait StyleFetcher: Send + Sync + RefUnwindSafe {
+            /// `id` is the `href` of a dependent style's `<link rel="independent-parent" />`,
+            /// e.g. `http://www.zotero.org/styles/apa`. `Ok(None)` means there is no such style.
+            fn fetch_style(&self, id: &str) -> Result<Option<String>, StyleFetchError>;
+        }
+    } else {
+        pub trait StyleFetcher: RefUnwindSafe {
+            fn fetch_style(&self, id: &str) -> Result<Option<String>, StyleFetchError>;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StyleFetchError {
+    Io(io::Error),
+    Other(String),
+}
+
+impl fmt::Display for StyleFetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StyleFetchError::Io(e) => write!(f, "{}", e),
+            StyleFetchError::Other(s) => f.write_str(s),
+        }
+    }
+}
+
+impl std::error::Error for StyleFetchError {}
+
+impl From<String> for StyleFetchError {
+    fn from(err: String) -> StyleFetchError {
+        StyleFetchError::Other(err)
+    }
+}
+
+impl From<io::Error> for StyleFetchError {
+    fn from(err: io::Error) -> StyleFetchError {
+        StyleFetchError::Io(err)
+    }
+}
+
+/// Styles keyed by id.
+pub struct PredefinedStyles(pub HashMap<String, String>);
+
+impl StyleFetcher for PredefinedStyles {
+    fn fetch_style(&self, id: &str) -> Result<Option<String>, StyleFetchError> {
+        Ok(self.0.get(id).cloned())
+    }
+}
+
+/// Reads styles from a local clone of the [CSL styles repository](https://github.com/citation-style-language/styles),
+/// where `http://www.zotero.org/styles/apa` is stored at `<root>/apa.csl`.
+pub struct StyleRepository {
+    root: PathBuf,
+}
+
+impl StyleRepository {
+    pub fn new(repo_dir: impl Into<PathBuf>) -> Self {
+        StyleRepository {
+            root: repo_dir.into(),
+        }
+    }
+
+    /// The file an id would be stored in, if it looks like a style id at all. Only the last path
+    /// segment is used, so ids from any host work, and nothing outside `root` can be read.
+    pub fn path_for(&self, id: &str) -> Option<PathBuf> {
+        let name = id.trim_end_matches('/').rsplit('/').next()?;
+        let valid = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.';
+        if name.is_empty() || name.starts_with('.') || !name.chars().all(valid) {
+            return None;
+        }
+        let mut path = self.root.clone();
+        path.push(format!("{}.csl", name));
+        Some(path)
+    }
+}
+
+impl StyleFetcher for StyleRepository {
+    fn fetch_style(&self, id: &str) -> Result<Option<String>, StyleFetchError> {
+        let path = match self.path_for(id) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        match fs::read_to_string(path) {
+            Ok(string) => Ok(Some(string)),
+            Err(e) => match e.kind() {
+                io::ErrorKind::NotFound => Ok(None),
+                _ => Err(StyleFetchError::Io(e)),
+            },
+        }
+    }
+}
+
+#[test]
+fn repository_paths() {
+    let repo = StyleRepository::new("/styles");
+    let path = |id: &str| repo.path_for(id).map(|p| p.to_string_lossy().into_owned());
+    assert_eq!(
+        path("http://www.zotero.org/styles/apa"),
+        Some("/styles/apa.csl".into())
+    );
+    assert_eq!(
+        path("https://www.zotero.org/styles/chicago-author-date/"),
+        Some("/styles/chicago-author-date.csl".into())
+    );
+    assert_eq!(path("http://www.zotero.org/styles/.."), None);
+    assert_eq!(path("http://www.zotero.org/styles/a b"), None);
+    assert_eq!(path(""), None);
+}
@@ -15,8 +15,14 @@ use csl::{
 };
 use fnv::FnvHashSet;
 
+use crate::ParseCache;
+
 pub trait HasFetcher {
     fn get_fetcher(&self) -> Arc<dyn LocaleFetcher>;
+    /// Where to get parsed locales from, instead of parsing them again.
+    fn get_parse_cache(&self) -> Option<Arc<ParseCache>> {
+        None
+    }
 }
 
 /// Salsa interface to a CSL style.
@@ -232,16 +238,18 @@ fn inline_locale(db: &dyn LocaleDatabase, key: Option<Lang>) -> Option<Arc<Local
 fn parsed_locale(db: &dyn LocaleDatabase, key: LocaleSource) -> Option<Arc<Locale>> {
     match key {
         LocaleSource::File(ref lang) => {
-            let string = db.locale_xml(lang.clone());
-            string
-                .and_then(|s| match Locale::parse(&s) {
-                    Ok(l) => Some(l),
-                    Err(e) => {
-                        error!("failed to parse locale for lang {}: {:?}", lang, e);
-                        None
-                    }
-                })
-                .map(Arc::new)
+            let string = db.locale_xml(lang.clone())?;
+            let parsed = match db.get_parse_cache() {
+                Some(cache) => cache.locale(&string),
+                None => Locale::parse(&string).map(Arc::new),
+            };
+            match parsed {
+                Ok(l) => Some(l),
+                Err(e) => {
+                    error!("failed to parse locale for lang {}: {:?}", lang, e);
+                    None
+                }
+            }
         }
         LocaleSource::Inline(ref lang) => db.inline_locale(lang.clone()),
     }
@@ -272,7 +280,9 @@ fn merged_locale(db: &dyn LocaleDatabase, key: Lang) -> Arc<Locale> {
     )
 }
 
+use std::future::Future;
 use std::panic::RefUnwindSafe;
+use std::pin::Pin;
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "parallel")] {
@@ -286,6 +296,17 @@ cfg_if::cfg_if! {
                 Some(Locale::parse(&s).ok()?)
             }
         }
+
+        /// Returned by [AsyncLocaleFetcher::fetch_string]. Must be Send so a processor can be
+        /// used from a multithreaded async runtime.
+        pub type LocaleFuture<'a> =
+            Pin<Box<dyn Future<Output = Result<Option<String>, LocaleFetchError>> + Send + 'a>>;
+
+        /// Like [LocaleFetcher], but for locales that come from somewhere slow, like the network.
+        /// Used by `Processor::fetch_locales`.
+        pub trait AsyncLocaleFetcher: Send + Sync + RefUnwindSafe {
+            fn fetch_string(&self, lang: Lang) -> LocaleFuture<'_>;
+        }
     } else {
         pub trait LocaleFetcher: RefUnwindSafe {
             fn fetch_string(&self, lang: &Lang) -> Result<Option<String>, LocaleFetchError>;
@@ -294,6 +315,13 @@ cfg_if::cfg_if! {
                 Some(Locale::parse(&s).ok()?)
             }
         }
+
+        pub type LocaleFuture<'a> =
+            Pin<Box<dyn Future<Output = Result<Option<String>, LocaleFetchError>> + 'a>>;
+
+        pub trait AsyncLocaleFetcher: RefUnwindSafe {
+            fn fetch_string(&self, lang: Lang) -> LocaleFuture<'_>;
+        }
     }
 }
 
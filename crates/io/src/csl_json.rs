@@ -10,7 +10,7 @@
 
 mod cow_str;
 
-use crate::names::Name;
+use crate::names::{Name, PersonName};
 use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
 use serde::de::{Error, IgnoredAny};
 use std::borrow::Cow;
@@ -29,6 +29,7 @@ use csl::GetAttribute;
 use csl::Lang;
 
 use super::date::{Date, DateOrRange};
+use super::raw_date::RawDateParser;
 use super::reference::Reference;
 use fnv::FnvHashMap;
 use std::marker::PhantomData;
@@ -54,7 +55,8 @@ impl<'de> Visitor<'de> for LanguageVisitor {
     }
 }
 
-pub struct MaybeDate(Option<DateOrRange>);
+/// The second field is the raw string, if it was a raw date the English parser couldn't read.
+pub struct MaybeDate(Option<DateOrRange>, Option<String>);
 
 pub struct WrapLang(Option<Lang>);
 
@@ -100,7 +102,7 @@ enum Field<'a> {
     Any(Cow<'a, str>),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Hash)]
 #[serde(untagged)]
 pub enum NumberLike {
     Str(String),
@@ -211,6 +213,7 @@ impl<'de> Deserialize<'de> for Reference {
                 let mut number = FnvHashMap::default();
                 let mut name = FnvHashMap::default();
                 let mut date = FnvHashMap::default();
+                let mut raw_dates = FnvHashMap::default();
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::Id => {
@@ -241,8 +244,11 @@ impl<'de> Deserialize<'de> for Reference {
                                     name.insert(v, names);
                                 }
                                 Ok(AnyVariable::Date(v)) => {
-                                    if let MaybeDate(Some(d)) = map.next_value()? {
+                                    if let MaybeDate(Some(d), raw) = map.next_value()? {
                                         date.insert(v, d);
+                                        if let Some(raw) = raw {
+                                            raw_dates.insert(v, raw);
+                                        }
                                     }
                                 }
                             }
@@ -259,6 +265,7 @@ impl<'de> Deserialize<'de> for Reference {
                     number,
                     name,
                     date,
+                    raw_dates,
                 })
             }
         }
@@ -446,6 +453,22 @@ impl<'de> Deserialize<'de> for DateParts {
     }
 }
 
+/// Reads a raw date as English. If that fails, falls back to whatever the ISO parser can make of
+/// a prefix, or a literal, and hands back the raw string so it can be parsed again later with the
+/// reference's own locale.
+fn parse_raw(raw: &str) -> (DateOrRange, Option<String>) {
+    match RawDateParser::english().parse(raw) {
+        Ok(parsed) => (parsed, None),
+        Err(_) => {
+            let fallback = DateOrRange::from_str(raw).unwrap_or_else(|_| DateOrRange::Literal {
+                literal: raw.into(),
+                circa: false,
+            });
+            (fallback, Some(raw.into()))
+        }
+    }
+}
+
 impl<'de> Deserialize<'de> for MaybeDate {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -478,28 +501,8 @@ impl<'de> Deserialize<'de> for MaybeDate {
             where
                 E: de::Error,
             {
-                FromStr::from_str(value)
-                    .or_else(|_| {
-                        Ok(DateOrRange::Literal {
-                            literal: value.into(),
-                            circa: false,
-                        })
-                    })
-                    .map(|x| MaybeDate(Some(x)))
-            }
-
-            fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
-            where
-                E: de::Error,
-            {
-                FromStr::from_str(&value)
-                    .or_else(|_| {
-                        Ok(DateOrRange::Literal {
-                            literal: value.into(),
-                            circa: false,
-                        })
-                    })
-                    .map(|x| MaybeDate(Some(x)))
+                let (date, raw) = parse_raw(value);
+                Ok(MaybeDate(Some(date), raw))
             }
 
             fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
@@ -507,6 +510,7 @@ impl<'de> Deserialize<'de> for MaybeDate {
                 V: MapAccess<'de>,
             {
                 let mut found = None;
+                let mut found_raw = None;
                 let mut found_season: Option<NumberLike> = None;
                 let mut found_circa: Option<bool> = None;
                 while let Some(key) = map.next_key()? {
@@ -514,19 +518,17 @@ impl<'de> Deserialize<'de> for MaybeDate {
                         DateType::Raw => {
                             let v: Cow<'de, str> = map.next_value()?;
                             if found.is_none() {
-                                found = Some(DateOrRange::from_str(&v).unwrap_or_else(|_| {
-                                    DateOrRange::Literal {
-                                        literal: v.as_ref().into(),
-                                        circa: false,
-                                    }
-                                }))
+                                let (date, raw) = parse_raw(&v);
+                                found = Some(date);
+                                found_raw = raw;
                             }
                         }
                         DateType::Literal => {
                             found = Some(DateOrRange::Literal {
                                 literal: map.next_value()?,
                                 circa: false,
-                            })
+                            });
+                            found_raw = None;
                         }
                         DateType::DateParts => {
                             let dp: DateParts = match map.next_value() {
@@ -538,6 +540,7 @@ impl<'de> Deserialize<'de> for MaybeDate {
                             };
                             if dp.0.is_some() {
                                 found = dp.0;
+                                found_raw = None;
                             }
                         }
                         DateType::Edtf => {
@@ -562,6 +565,7 @@ impl<'de> Deserialize<'de> for MaybeDate {
                                     circa: false,
                                 };
                                 found = Some(DateOrRange::Single(date));
+                                found_raw = None;
                             }
                         }
                         DateType::Unknown(k) => {
@@ -608,10 +612,10 @@ impl<'de> Deserialize<'de> for MaybeDate {
                         if let Some(circa) = found_circa {
                             found.set_circa(circa)
                         }
-                        Ok(MaybeDate(Some(found)))
+                        Ok(MaybeDate(Some(found), found_raw))
                     })
                     .ok()
-                    .unwrap_or(MaybeDate(None)))
+                    .unwrap_or(MaybeDate(None, None)))
             }
         }
 
@@ -619,3 +623,166 @@ impl<'de> Deserialize<'de> for MaybeDate {
         deserializer.deserialize_struct("DateOrRange", DATE_TYPES, DateVisitor)
     }
 }
+
+// Serialization writes CSL-JSON that the Deserialize impls above read back into an identical
+// Reference. Names and dates have their own derived Serialize impls, which are not CSL-JSON, so
+// they are wrapped here.
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+impl Serialize for Reference {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("id", &self.id)?;
+        map.serialize_entry("type", self.csl_type.as_ref())?;
+        if let Some(language) = &self.language {
+            map.serialize_entry("language", language)?;
+        }
+        for (var, value) in &self.ordinary {
+            map.serialize_entry(var.as_ref(), value)?;
+        }
+        for (var, value) in &self.number {
+            map.serialize_entry(var.as_ref(), value)?;
+        }
+        for (var, names) in &self.name {
+            let names: Vec<_> = names.iter().map(CslJsonName).collect();
+            map.serialize_entry(var.as_ref(), &names)?;
+        }
+        for (var, date) in &self.date {
+            let raw = self.raw_dates.get(var).map(String::as_str);
+            map.serialize_entry(var.as_ref(), &CslJsonDate(date, raw))?;
+        }
+        map.end()
+    }
+}
+
+struct CslJsonName<'a>(&'a Name);
+
+impl Serialize for CslJsonName<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            Name::Literal { literal, .. }
+            | Name::Person(PersonName {
+                family: Some(literal),
+                given: None,
+                non_dropping_particle: None,
+                dropping_particle: None,
+                suffix: None,
+                is_literal: true,
+                ..
+            }) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("literal", literal)?;
+                map.end()
+            }
+            Name::Institution(institution) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("institution", &institution.units)?;
+                map.end()
+            }
+            Name::Person(person) => person.serialize(serializer),
+        }
+    }
+}
+
+/// The second field is the raw string it was parsed from, if it needs parsing again with the
+/// reference's locale.
+struct CslJsonDate<'a>(&'a DateOrRange, Option<&'a str>);
+
+impl Serialize for CslJsonDate<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        fn parts(date: &Date) -> Vec<i32> {
+            let mut parts = vec![date.year];
+            if date.month != 0 || date.day != 0 {
+                parts.push(date.month as i32);
+            }
+            if date.day != 0 {
+                parts.push(date.day as i32);
+            }
+            parts
+        }
+        let CslJsonDate(date, raw) = *self;
+        let mut map = serializer.serialize_map(None)?;
+        match (raw, date) {
+            (Some(raw), _) => map.serialize_entry("raw", raw)?,
+            (None, DateOrRange::Literal { literal, .. }) => {
+                map.serialize_entry("literal", literal)?
+            }
+            (None, DateOrRange::Single(d)) => map.serialize_entry("date-parts", &[parts(d)])?,
+            (None, DateOrRange::Range(d1, d2)) => {
+                map.serialize_entry("date-parts", &[parts(d1), parts(d2)])?
+            }
+        }
+        if date.is_uncertain_date() {
+            map.serialize_entry("circa", &true)?;
+        }
+        map.end()
+    }
+}
+
+#[test]
+fn reference_round_trip() {
+    let json = r#"{
+        "id": "smith",
+        "type": "book",
+        "language": "en-GB",
+        "title": "Title",
+        "volume": 2,
+        "page": "1-5",
+        "author": [
+            { "family": "Smith", "given": "John", "non-dropping-particle": "van" },
+            { "literal": "Literal Name" },
+            { "institution": ["Department", { "long": "University", "short": "Uni" }] }
+        ],
+        "issued": { "date-parts": [[1999, 3], [2001]], "circa": true },
+        "accessed": { "date-parts": [[2000]], "season": 2 },
+        "original-date": { "literal": "Whenever" },
+        "event-date": { "raw": "29 février 2004" }
+    }"#;
+    let reference: Reference = serde_json::from_str(json).unwrap();
+    let written = serde_json::to_string(&reference).unwrap();
+    let again: Reference = serde_json::from_str(&written).unwrap();
+    assert_eq!(reference, again);
+}
+
+#[test]
+fn parse_literal_names_only_splits_literals() {
+    let json = r#"{
+        "id": "who",
+        "type": "report",
+        "author": [
+            { "literal": "John Smith" },
+            { "family": "World Health Organization" }
+        ]
+    }"#;
+    let mut reference: Reference = serde_json::from_str(json).unwrap();
+    reference.parse_literal_names();
+    let authors = &reference.name[&csl::NameVariable::Author];
+    assert_eq!(
+        authors[0],
+        Name::Person(PersonName {
+            family: Some("Smith".into()),
+            given: Some("John".into()),
+            is_latin_cyrillic: true,
+            is_literal: true,
+            ..Default::default()
+        })
+    );
+    assert_eq!(
+        authors[1],
+        Name::Person(PersonName {
+            family: Some("World Health Organization".into()),
+            is_latin_cyrillic: true,
+            ..Default::default()
+        })
+    );
+}
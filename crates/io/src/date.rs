@@ -57,6 +57,109 @@ impl PartialOrd for DateOrRange {
     }
 }
 
+/// A key for sorting dates by the rules in the CSL 1.0.2 spec, for `<key variable="issued" />`.
+///
+/// > Date variables called via the variable attribute are returned in the YYYYMMDD format, with
+/// > zeros substituted for any missing date-parts. [...] Negative years are sorted inversely, e.g.
+/// > “100BC, 50BC, 50AD, 100AD”. Seasons are ignored for sorting [...]. In the case of date
+/// > ranges, the start date is used for the primary sort, and the end date is used for a
+/// > secondary sort [...]. Date ranges are placed after single dates when they share the same
+/// > (start) date.
+///
+/// The spec says nothing about the rest, so:
+///
+/// * Open ranges (`2000–`, where the end year is 0) come after closed ranges with the same start.
+/// * Uncertain dates come after otherwise identical certain ones.
+/// * Literal dates come after all parsed ones, and are compared as strings.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DateSortKey {
+    Date {
+        start: (i32, u32, u32),
+        end: RangeEndSortKey,
+        circa: bool,
+    },
+    Literal(String),
+}
+
+/// Variant order matters: single dates, then closed ranges, then open ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RangeEndSortKey {
+    Single,
+    Closed(i32, u32, u32),
+    Open,
+}
+
+impl Date {
+    /// (year, month, day), with seasons treated as an absent month.
+    fn sort_tuple(&self) -> (i32, u32, u32) {
+        let month = if self.month <= 12 { self.month } else { 0 };
+        (self.year, month, self.day)
+    }
+}
+
+impl DateOrRange {
+    pub fn sort_key(&self) -> DateSortKey {
+        match self {
+            DateOrRange::Single(d) => DateSortKey::Date {
+                start: d.sort_tuple(),
+                end: RangeEndSortKey::Single,
+                circa: d.circa,
+            },
+            DateOrRange::Range(d1, d2) => DateSortKey::Date {
+                start: d1.sort_tuple(),
+                end: if d2.year == 0 {
+                    RangeEndSortKey::Open
+                } else {
+                    let (y, m, d) = d2.sort_tuple();
+                    RangeEndSortKey::Closed(y, m, d)
+                },
+                circa: d1.circa || d2.circa,
+            },
+            DateOrRange::Literal { literal, .. } => DateSortKey::Literal(literal.clone()),
+        }
+    }
+}
+
+#[test]
+fn test_date_sort_key() {
+    fn key(d: impl Into<DateOrRange>) -> DateSortKey {
+        d.into().sort_key()
+    }
+    let open = |y| key((Date::new(y, 0, 0), Date::new(0, 0, 0)));
+    let range = |a, b| key((Date::new(a, 0, 0), Date::new(b, 0, 0)));
+    let single = |y, m, d| key(Date::new(y, m, d));
+
+    // sort_DateVariable: 2000, May 2000, May 1st 2000
+    assert!(single(2000, 0, 0) < single(2000, 5, 0));
+    assert!(single(2000, 5, 0) < single(2000, 5, 1));
+    // 100BC, 50BC, 50AD, 100AD
+    assert!(single(-100, 0, 0) < single(-50, 0, 0));
+    assert!(single(-50, 0, 0) < single(50, 0, 0));
+    assert!(single(50, 0, 0) < single(100, 0, 0));
+    // seasons are ignored
+    assert_eq!(single(2000, 13, 0), single(2000, 0, 0));
+    assert!(single(2000, 16, 0) < single(2000, 1, 0));
+    // sort_DateVariableRange: 2000–2001, 2000–2005, 2002–2003, 2002–2009
+    assert!(range(2000, 2001) < range(2000, 2005));
+    assert!(range(2000, 2005) < range(2002, 2003));
+    assert!(range(2002, 2003) < range(2002, 2009));
+    // 2000, 2000–2002
+    assert!(single(2000, 0, 0) < range(2000, 2002));
+    assert!(range(1999, 2005) < single(2000, 0, 0));
+    // open ranges go after closed ones with the same start
+    assert!(range(2000, 2002) < open(2000));
+    assert!(open(2000) < single(2001, 0, 0));
+    // circa is only a tie-breaker
+    assert!(single(2000, 0, 0) < key(DateOrRange::new(2000, 0, 0).with_circa(true)));
+    assert!(key(DateOrRange::new(2000, 0, 0).with_circa(true)) < single(2001, 0, 0));
+    // literals last
+    let lit = key(DateOrRange::Literal {
+        literal: "n.d.".into(),
+        circa: false,
+    });
+    assert!(single(9999, 12, 31) < lit);
+}
+
 #[test]
 fn test_date_ord() {
     // years only
@@ -474,7 +577,7 @@ fn and_ymd(inp: &[u8]) -> IResult<&[u8], Date> {
     Ok(ymd_date(rem1)?)
 }
 
-fn range(inp: &[u8]) -> IResult<&[u8], DateOrRange> {
+pub(crate) fn range(inp: &[u8]) -> IResult<&[u8], DateOrRange> {
     let (rem1, d1) = ymd_date(inp)?;
     let (rem2, d2o) = opt(and_ymd)(rem1)?;
     Ok((
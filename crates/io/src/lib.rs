@@ -29,6 +29,7 @@ mod names;
 pub use names::TrimInPlace;
 mod numeric;
 pub mod output;
+mod raw_date;
 mod reference;
 pub mod unicode;
 pub mod utils;
@@ -47,10 +48,12 @@ pub use self::names::*;
 #[doc(inline)]
 pub use self::numeric::*;
 #[doc(inline)]
+pub use self::raw_date::*;
+#[doc(inline)]
 pub use self::reference::*;
 
 use self::output::LocalizedQuotes;
-use csl::TextCase;
+use csl::{TextCase, WordList};
 
 // Export these, because proc is going to need them
 // type Sixteen = smallstr::SmallString<[u8; 16]>;
@@ -66,7 +69,9 @@ pub struct IngestOptions {
     pub text_case: TextCase,
     pub quotes: LocalizedQuotes,
     pub strip_periods: bool,
-    pub is_english: bool,
+    pub language: CaseLanguage,
+    /// CSL-M `skip-words` from the locale, which replace the built-in title case stopwords.
+    pub skip_words: Option<WordList>,
 
     /// For `flipflop_LeadingMarkupWithApostrophe.txt`
     ///
@@ -92,3 +97,4 @@ impl IngestOptions {
 
 pub mod lazy;
 mod text_case;
+pub use text_case::CaseLanguage;
@@ -6,6 +6,10 @@
 
 use crate::csl_json::RelaxedBool;
 use crate::{lazy, String};
+use serde::de::{Deserialize, Deserializer};
+
+mod literal;
+pub use literal::{lang_puts_family_first, looks_institutional, parse_literal_name};
 
 #[derive(Default, Debug, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
@@ -23,7 +27,7 @@ struct PersonNameInput {
 
 // kebab-case here is the same as Strum's "kebab_case",
 // but with a more accurate name
-#[derive(Default, Debug, Eq, PartialEq, Hash, Deserialize, Serialize, Clone)]
+#[derive(Default, Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 #[serde(from = "PersonNameInput")]
 pub struct PersonName {
@@ -38,6 +42,43 @@ pub struct PersonName {
     pub comma_suffix: bool,
     #[serde(default, skip_serializing)]
     pub is_latin_cyrillic: bool,
+    /// Input as `{ "literal": "..." }`, and stored as a lone family name until
+    /// [crate::Reference::parse_literal_names] splits it. Kept after splitting, because the order
+    /// of a name written out as one string follows the reference's language.
+    ///
+    /// Not part of equality or hashing: `{ "literal": "Plato" }` and `{ "family": "Plato" }` are
+    /// the same name, e.g. for subsequent-author-substitute and disambiguation.
+    #[serde(default, skip_serializing)]
+    pub is_literal: bool,
+}
+
+impl PartialEq for PersonName {
+    fn eq(&self, other: &Self) -> bool {
+        self.family == other.family
+            && self.given == other.given
+            && self.non_dropping_particle == other.non_dropping_particle
+            && self.dropping_particle == other.dropping_particle
+            && self.suffix == other.suffix
+            && self.static_particles == other.static_particles
+            && self.comma_suffix == other.comma_suffix
+            && self.is_latin_cyrillic == other.is_latin_cyrillic
+    }
+}
+
+impl Eq for PersonName {}
+
+use std::hash::{Hash, Hasher};
+impl Hash for PersonName {
+    fn hash<H: Hasher>(&self, h: &mut H) {
+        self.family.hash(h);
+        self.given.hash(h);
+        self.non_dropping_particle.hash(h);
+        self.dropping_particle.hash(h);
+        self.suffix.hash(h);
+        self.static_particles.hash(h);
+        self.comma_suffix.hash(h);
+        self.is_latin_cyrillic.hash(h);
+    }
 }
 
 #[derive(Deserialize)]
@@ -49,8 +90,10 @@ enum NameInput {
         // the untagged macro uses the field names on Literal { literal } instead of the discriminant, so don't change that
         literal: String,
     },
+    Institution {
+        institution: Vec<InstitutionUnit>,
+    },
     Person(PersonNameInput),
-    // TODO: represent an institution in CSL-M?
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Deserialize, Serialize, Clone)]
@@ -64,8 +107,74 @@ pub enum Name {
         #[serde(skip_serializing)]
         is_latin_cyrillic: bool,
     },
+    /// CSL-M: institutions
+    Institution(Institution),
     Person(PersonName),
-    // TODO: represent an institution in CSL-M?
+}
+
+/// A CSL-M institutional name, input as `{ "institution": [...] }` with the subunits listed from
+/// the smallest to the largest, e.g. `["Department of Physics", "University of Oxford"]`. Each
+/// subunit can also be given as `{ "long": "...", "short": "..." }`.
+#[derive(Default, Debug, Eq, PartialEq, Hash, Deserialize, Serialize, Clone)]
+pub struct Institution {
+    pub units: Vec<InstitutionUnit>,
+    #[serde(default, skip_serializing)]
+    pub is_latin_cyrillic: bool,
+}
+
+impl Institution {
+    pub fn new(units: Vec<InstitutionUnit>) -> Self {
+        let is_latin_cyrillic = units.iter().all(|u| {
+            is_latin_cyrillic(&u.long) && u.short.as_ref().map_or(true, |s| is_latin_cyrillic(s))
+        });
+        Institution {
+            units,
+            is_latin_cyrillic,
+        }
+    }
+}
+
+#[derive(Default, Debug, Eq, PartialEq, Hash, Serialize, Clone)]
+pub struct InstitutionUnit {
+    pub long: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short: Option<String>,
+}
+
+impl InstitutionUnit {
+    pub fn new(long: impl Into<String>) -> Self {
+        InstitutionUnit {
+            long: long.into(),
+            short: None,
+        }
+    }
+
+    /// The short form, if there is one, otherwise the long form.
+    pub fn short_or_long(&self) -> &str {
+        self.short.as_ref().unwrap_or(&self.long)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InstitutionUnitInput {
+    Long(String),
+    Both { long: String, short: Option<String> },
+}
+
+impl<'de> Deserialize<'de> for InstitutionUnit {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(match InstitutionUnitInput::deserialize(deserializer)? {
+            InstitutionUnitInput::Long(long) => InstitutionUnit::new(long),
+            InstitutionUnitInput::Both { long, short } => InstitutionUnit {
+                long,
+                short: short.filter(|s| !s.is_empty()),
+            },
+        })
+    }
 }
 
 impl From<NameInput> for Name {
@@ -78,11 +187,10 @@ impl From<NameInput> for Name {
             // uniformly. They can still be created by using
             // the Rust API directly, so this has to be
             // removed at some point.
-            NameInput::Literal { literal } => Name::Person(PersonName {
-                is_latin_cyrillic: is_latin_cyrillic(&literal),
-                family: Some(literal),
-                ..Default::default()
-            }),
+            NameInput::Literal { literal } => literal::literal_person(literal),
+            NameInput::Institution { institution } => {
+                Name::Institution(Institution::new(institution))
+            }
             NameInput::Person(pn) => Name::Person(pn.into()),
         }
     }
@@ -198,6 +306,7 @@ impl From<PersonNameInput> for PersonName {
             static_particles,
             comma_suffix,
             is_latin_cyrillic,
+            is_literal: false,
         };
 
         let PersonName {
@@ -209,6 +318,7 @@ impl From<PersonNameInput> for PersonName {
             static_particles,
             comma_suffix,
             is_latin_cyrillic: _,
+            is_literal: _,
         } = &mut pn;
 
         // Don't parse if these are supplied
@@ -499,3 +609,48 @@ fn test_is_latin() {
     };
     assert!(pn_is_latin_cyrillic(&pn));
 }
+
+#[test]
+fn test_institution_input() {
+    let names: Vec<Name> = serde_json::from_str(
+        r#"[
+            { "institution": ["Department of Physics", { "long": "University of Oxford", "short": "Oxford" }] },
+            { "literal": "Plato" }
+        ]"#,
+    )
+    .unwrap();
+    assert_eq!(
+        names[0],
+        Name::Institution(Institution {
+            units: vec![
+                InstitutionUnit::new("Department of Physics"),
+                InstitutionUnit {
+                    long: "University of Oxford".into(),
+                    short: Some("Oxford".into()),
+                },
+            ],
+            is_latin_cyrillic: true,
+        })
+    );
+    assert!(matches!(names[1], Name::Person(_)));
+}
+
+#[test]
+fn test_literal_equals_family() {
+    use std::collections::hash_map::DefaultHasher;
+    let names: Vec<Name> =
+        serde_json::from_str(r#"[{ "literal": "Plato" }, { "family": "Plato" }]"#).unwrap();
+    let (literal, family) = match (&names[0], &names[1]) {
+        (Name::Person(literal), Name::Person(family)) => (literal, family),
+        _ => panic!("expected two person names, got {:?}", names),
+    };
+    assert!(literal.is_literal);
+    assert!(!family.is_literal);
+    assert_eq!(literal, family);
+    let hash = |pn: &PersonName| {
+        let mut h = DefaultHasher::new();
+        pn.hash(&mut h);
+        h.finish()
+    };
+    assert_eq!(hash(literal), hash(family));
+}
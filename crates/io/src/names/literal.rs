@@ -0,0 +1,405 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! Opt-in splitting of single-string names (`{ "literal": "Smith, John A." }`) into
+//! [PersonName] parts, so they get initials, sort keys and disambiguation like any other person.
+//!
+//! This is guesswork, which is why it is off by default. Anything that looks like an institution
+//! is left alone.
+
+use super::{is_latin_cyrillic, Name, PersonName, PersonNameInput};
+use crate::String;
+use csl::{IsoLang, Lang};
+
+/// Lowercase, period-free. Matched against whole tokens at the end of a name.
+const SUFFIXES: &[&str] = &["jr", "sr", "ii", "iii", "iv", "esq"];
+
+/// Lowercase whole words that mark a name as an organisation rather than a person.
+const INSTITUTION_WORDS: &[&str] = &[
+    "&",
+    "and",
+    "for",
+    "of",
+    "agency",
+    "association",
+    "board",
+    "bureau",
+    "center",
+    "centre",
+    "college",
+    "commission",
+    "committee",
+    "company",
+    "consortium",
+    "corporation",
+    "council",
+    "department",
+    "dept",
+    "foundation",
+    "gmbh",
+    "government",
+    "group",
+    "inc",
+    "institut",
+    "institute",
+    "instituto",
+    "laboratory",
+    "library",
+    "llc",
+    "ltd",
+    "ministry",
+    "museum",
+    "office",
+    "organisation",
+    "organization",
+    "press",
+    "project",
+    "school",
+    "society",
+    "team",
+    "universidad",
+    "universität",
+    "université",
+    "university",
+];
+
+/// Two-character Chinese and Korean family names. Everything else is assumed to be one character.
+const COMPOUND_FAMILY_NAMES: &[&str] = &[
+    "欧阳", "歐陽", "司马", "司馬", "诸葛", "諸葛", "上官", "东方", "東方", "皇甫", "令狐", "南宫",
+    "南宮", "独孤", "獨孤", "慕容", "司徒", "夏侯", "남궁", "선우", "제갈", "황보", "독고", "사공",
+];
+
+fn normalise_token(token: &str) -> String {
+    token
+        .chars()
+        .filter(|&c| c != '.' && c != ',')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn is_suffix(token: &str) -> bool {
+    SUFFIXES.contains(&normalise_token(token).as_str())
+}
+
+/// Guesses whether a single-string name belongs to an organisation.
+pub fn looks_institutional(literal: &str) -> bool {
+    let tokens: Vec<&str> = literal.split_whitespace().collect();
+    if tokens
+        .iter()
+        .any(|t| INSTITUTION_WORDS.contains(&normalise_token(t).as_str()))
+    {
+        return true;
+    }
+    // NASA, WHO, but not "A." or "J"
+    if let [only] = tokens.as_slice() {
+        let letters = only.chars().filter(|c| c.is_alphabetic()).count();
+        if letters >= 2 && only.chars().all(|c| c.is_uppercase() || !c.is_alphabetic()) {
+            return true;
+        }
+    }
+    // People rarely have more than four names without a comma in there somewhere
+    tokens.len() > 4 && !literal.contains(',')
+}
+
+/// The way the deserializer stores a literal name: a lone family name.
+pub(super) fn literal_person(literal: String) -> Name {
+    Name::Person(PersonName {
+        is_latin_cyrillic: is_latin_cyrillic(&literal),
+        family: Some(literal),
+        is_literal: true,
+        ..Default::default()
+    })
+}
+
+/// Whether names written out in this language put the family name first, as in Hungarian and
+/// Vietnamese.
+pub fn lang_puts_family_first(lang: &Lang) -> bool {
+    matches!(lang, Lang::Iso(IsoLang::Other(code), _) if code == "hu" || code == "vi")
+}
+
+/// Splits a single-string name into [PersonName] parts, or leaves it as a lone family name if it
+/// looks like an institution or can't be split.
+///
+/// `lang` is the reference's language, if any. For names that are not in Latin or Cyrillic
+/// script, it decides whether a name with no spaces can be split. For Latin-script names without
+/// a comma, it decides whether the family name comes first ([lang_puts_family_first]).
+pub fn parse_literal_name(literal: &str, lang: Option<&Lang>) -> Name {
+    let trimmed = literal.trim();
+    if trimmed.is_empty() || looks_institutional(trimmed) {
+        return literal_person(trimmed.into());
+    }
+    if !is_latin_cyrillic(trimmed) {
+        return parse_non_latin(trimmed, lang);
+    }
+    let input = if trimmed.contains(',') {
+        split_comma_order(trimmed)
+    } else if lang.map_or(false, lang_puts_family_first) {
+        split_family_first(trimmed)
+    } else {
+        split_space_order(trimmed, None)
+    };
+    match input {
+        Some(input) => Name::Person(input.into()),
+        None => literal_person(trimmed.into()),
+    }
+}
+
+/// "Smith, John A.", "van der Vlist, Eric", "Smith, John, Jr.", and "John Smith, Jr."
+fn split_comma_order(s: &str) -> Option<PersonNameInput> {
+    let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+    match parts.as_slice() {
+        // "John Smith, Jr." is space order with a suffix
+        [name, suffix] if is_suffix(suffix) => split_space_order(name, Some((*suffix, true))),
+        [family, given] if !family.is_empty() => Some(PersonNameInput {
+            family: Some((*family).into()),
+            given: Some((*given).into()).filter(|g: &String| !g.is_empty()),
+            ..Default::default()
+        }),
+        [family, given, suffix] if !family.is_empty() && is_suffix(suffix) => {
+            Some(PersonNameInput {
+                family: Some((*family).into()),
+                given: Some(given_with_suffix(given, Some((*suffix, false)))),
+                ..Default::default()
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Suffixes go in the given name the way CSL-JSON input writes them (`"John, Jr."`, or
+/// `"John,! Jr."` to force the comma), because [PersonName]'s own parsing skips particles when
+/// the suffix field is already filled in.
+fn given_with_suffix(given: &str, suffix: Option<(&str, bool)>) -> String {
+    let mut out = String::from(given);
+    if let Some((suffix, comma)) = suffix {
+        out.push_str(if comma { ",! " } else { ", " });
+        out.push_str(suffix);
+    }
+    out
+}
+
+/// "Kovács János", "Nguyễn Văn An"
+fn split_family_first(s: &str) -> Option<PersonNameInput> {
+    let (family, given) = match s.split_once(char::is_whitespace) {
+        Some((family, given)) => (family, Some(given.trim().into())),
+        None => (s, None),
+    };
+    Some(PersonNameInput {
+        family: Some(family.into()),
+        given,
+        ..Default::default()
+    })
+}
+
+/// "John A. Smith", "Vincent van Gogh", "Jean de la Fontaine", "Martin Luther King Jr."
+fn split_space_order(s: &str, mut suffix: Option<(&str, bool)>) -> Option<PersonNameInput> {
+    let mut tokens: Vec<&str> = s.split_whitespace().collect();
+    if suffix.is_none() && tokens.len() > 2 && is_suffix(tokens[tokens.len() - 1]) {
+        suffix = tokens.pop().map(|suffix| (suffix, false));
+    }
+    match tokens.as_slice() {
+        [] => None,
+        [_] if suffix.is_some() => None,
+        [family] => Some(PersonNameInput {
+            family: Some((*family).into()),
+            ..Default::default()
+        }),
+        [given @ .., family] => {
+            // The first lowercase word after the given names starts the family name, so the
+            // particles go with it and get split off again by PersonName's own particle parsing.
+            let split = given
+                .iter()
+                .skip(1)
+                .position(|t| t.chars().next().map_or(false, char::is_lowercase))
+                .map_or(given.len(), |p| p + 1);
+            let (given, particles) = given.split_at(split);
+            let mut family_str = particles.join(" ");
+            if !family_str.is_empty() {
+                family_str.push(' ');
+            }
+            family_str.push_str(family);
+            Some(PersonNameInput {
+                family: Some(family_str.as_str().into()),
+                given: Some(given_with_suffix(&given.join(" "), suffix)),
+                ..Default::default()
+            })
+        }
+    }
+}
+
+fn is_han(c: char) -> bool {
+    matches!(c, '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(c, '\u{AC00}'..='\u{D7A3}')
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}')
+}
+
+/// CJK names are written family name first. With a separator that's easy; without one, Chinese
+/// and Korean family names are nearly always one character (or a known two-character one), but
+/// Japanese family names vary too much to guess.
+fn parse_non_latin(s: &str, lang: Option<&Lang>) -> Name {
+    let person = |family: &str, given: &str| {
+        Name::Person(PersonName {
+            family: Some(family.trim().into()),
+            given: Some(given.trim().into()).filter(|g: &String| !g.is_empty()),
+            is_latin_cyrillic: false,
+            ..Default::default()
+        })
+    };
+    if let Some((family, given)) = s.split_once(',') {
+        return person(family, given);
+    }
+    if let Some(ix) = s.find(|c: char| c.is_whitespace() || c == '・' || c == '·' || c == '･') {
+        let sep_len = s[ix..].chars().next().map_or(1, char::len_utf8);
+        return person(&s[..ix], &s[ix + sep_len..]);
+    }
+    let is_japanese = matches!(lang, Some(Lang::Iso(IsoLang::Japanese, _)));
+    let chars = s.chars().count();
+    let splittable = s.chars().all(|c| is_han(c) || is_hangul(c))
+        && !s.chars().any(is_kana)
+        && !is_japanese
+        && chars >= 2
+        && chars <= 4;
+    if !splittable {
+        return literal_person(s.into());
+    }
+    let family_len = COMPOUND_FAMILY_NAMES
+        .iter()
+        .find(|compound| s.starts_with(*compound) && chars > 2)
+        .map_or_else(|| s.chars().next().map_or(0, char::len_utf8), |c| c.len());
+    person(&s[..family_len], &s[family_len..])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn person(family: &str, given: &str) -> PersonName {
+        PersonName {
+            family: Some(family.into()),
+            given: Some(given.into()),
+            is_latin_cyrillic: is_latin_cyrillic(family),
+            ..Default::default()
+        }
+    }
+
+    fn parse(s: &str) -> Name {
+        parse_literal_name(s, None)
+    }
+
+    #[test]
+    fn latin() {
+        assert_eq!(
+            parse("Smith, John A."),
+            Name::Person(person("Smith", "John A."))
+        );
+        assert_eq!(
+            parse("John A. Smith"),
+            Name::Person(person("Smith", "John A."))
+        );
+        assert_eq!(parse("Plato"), literal_person("Plato".into()));
+        assert_eq!(
+            parse("Vincent van Gogh"),
+            Name::Person(PersonName {
+                non_dropping_particle: Some("van".into()),
+                ..person("Gogh", "Vincent")
+            })
+        );
+        assert_eq!(
+            parse("van der Vlist, Eric"),
+            Name::Person(PersonName {
+                non_dropping_particle: Some("van der".into()),
+                ..person("Vlist", "Eric")
+            })
+        );
+        assert_eq!(
+            parse("Jean de la Fontaine"),
+            Name::Person(PersonName {
+                non_dropping_particle: Some("de la".into()),
+                ..person("Fontaine", "Jean")
+            })
+        );
+    }
+
+    #[test]
+    fn suffixes() {
+        let king = Name::Person(PersonName {
+            suffix: Some("Jr.".into()),
+            ..person("King", "Martin Luther")
+        });
+        assert_eq!(parse("Martin Luther King Jr."), king);
+        assert_eq!(parse("King, Martin Luther, Jr."), king);
+        assert_eq!(
+            parse("Martin Luther King, Jr."),
+            Name::Person(PersonName {
+                suffix: Some("Jr.".into()),
+                comma_suffix: true,
+                ..person("King", "Martin Luther")
+            })
+        );
+        assert_eq!(
+            parse("John Smith III"),
+            Name::Person(PersonName {
+                suffix: Some("III".into()),
+                ..person("Smith", "John")
+            })
+        );
+    }
+
+    #[test]
+    fn institutions() {
+        for inst in &[
+            "World Health Organization",
+            "WHO",
+            "University of Chicago",
+            "Smith & Sons",
+            "Acme Ltd.",
+        ] {
+            assert_eq!(parse(inst), literal_person((*inst).into()), "{}", inst);
+        }
+    }
+
+    #[test]
+    fn family_first() {
+        let hu = Lang::Iso(IsoLang::Other("hu".into()), None);
+        assert_eq!(
+            parse_literal_name("Kovács János", Some(&hu)),
+            Name::Person(person("Kovács", "János"))
+        );
+        let vi = Lang::Iso(IsoLang::Other("vi".into()), None);
+        assert_eq!(
+            parse_literal_name("Nguyễn Văn An", Some(&vi)),
+            Name::Person(person("Nguyễn", "Văn An"))
+        );
+        assert_eq!(
+            parse_literal_name("Kovács, János", Some(&hu)),
+            Name::Person(person("Kovács", "János"))
+        );
+    }
+
+    #[test]
+    fn non_latin() {
+        assert_eq!(parse("毛泽东"), Name::Person(person("毛", "泽东")));
+        assert_eq!(parse("欧阳修"), Name::Person(person("欧阳", "修")));
+        assert_eq!(parse("강소라"), Name::Person(person("강", "소라")));
+        assert_eq!(parse("山田 太郎"), Name::Person(person("山田", "太郎")));
+        assert_eq!(
+            parse("やまだ・たろう"),
+            Name::Person(person("やまだ", "たろう"))
+        );
+        // no way to know where a Japanese family name ends
+        let ja = Lang::Iso(IsoLang::Japanese, None);
+        assert_eq!(
+            parse_literal_name("山田太郎", Some(&ja)),
+            literal_person("山田太郎".into())
+        );
+    }
+}
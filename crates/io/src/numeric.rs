@@ -77,6 +77,7 @@ impl NumericToken {
 /// "L2"           => Tokens([Affixed("L2")])
 /// "L2tp"         => Tokens([Affixed("L2tp")])
 /// "2nd-4th"      => Tokens([Affixed("2nd"), Hyphen, Affixed("4th")])
+/// "A-3-A-7"      => Tokens([Affixed("A-3"), Hyphen, Affixed("A-7")])
 /// ```
 ///
 /// We don't parse:
@@ -96,14 +97,31 @@ impl<'a> NumericValue<'a> {
     pub fn num(i: u32) -> Self {
         NumericValue::Tokens(format!("{}", i).into(), vec![Num(i)], true)
     }
+    /// The first number of a page range, keeping any prefix or suffix, e.g. `S12` from
+    /// `S12-S15`.
     pub fn page_first(&self) -> Option<Self> {
-        self.first_num().map(NumericValue::num)
-    }
-    fn first_num(&self) -> Option<u32> {
-        match *self {
-            NumericValue::Tokens(_, ref ts, _) => ts.get(0).and_then(|token| token.get_num()),
-            NumericValue::Str(_) => None,
-        }
+        let first = match self {
+            NumericValue::Tokens(_, ts, _) => ts.get(0)?,
+            NumericValue::Str(_) => return None,
+        };
+        let verbatim = match first {
+            Num(i) => return Some(NumericValue::num(*i)),
+            Roman(i, upper) => {
+                let roman = roman::to(*i)?;
+                if *upper {
+                    roman.to_ascii_uppercase()
+                } else {
+                    roman.to_string()
+                }
+            }
+            Affixed(pre, i, suf) => format!("{}{}{}", pre, i, suf),
+            _ => return None,
+        };
+        Some(NumericValue::Tokens(
+            verbatim.into(),
+            vec![first.clone()],
+            true,
+        ))
     }
     pub fn is_numeric(&self) -> bool {
         match *self {
@@ -189,10 +207,10 @@ impl<'a> NumericValue<'a> {
 use nom::{
     branch::alt,
     bytes::complete::{escaped, is_not, tag},
-    character::complete::{char, digit1, one_of},
+    character::complete::{alpha1, char, digit1, one_of},
     combinator::{map, map_parser, opt},
     multi::{fold_many1, many0, many0_count},
-    sequence::{delimited, tuple},
+    sequence::{delimited, terminated, tuple},
     IResult,
 };
 
@@ -386,6 +404,40 @@ fn test_affixed() {
     assert_eq!(num_alpha_num("123n110"), Ok(("", afxd("123n", 110, ""))));
 }
 
+/// Page numbers like `A-3`, where letters and a hyphen come before the number. Without this, the
+/// hyphen would be read as a range. Roman numerals are left alone, so `iv-8` is still a range.
+fn hyphenated_prefix_num(inp: &str) -> IResult<&str, NumericToken> {
+    let (rem, (pre, token)) = tuple((terminated(alpha1, char('-')), num_alpha_num))(inp)?;
+    if roman::from(pre).is_some() {
+        return Err(nom::Err::Error(NomError::new(
+            inp,
+            nom::error::ErrorKind::Alpha,
+        )));
+    }
+    let pre_len = pre.len() + 1;
+    let token = match token {
+        Num(num) => Affixed(inp[..pre_len].into(), num, String::new()),
+        Affixed(inner, num, suf) => {
+            let mut pre = String::from(&inp[..pre_len]);
+            pre.push_str(&inner);
+            Affixed(pre, num, suf)
+        }
+        _ => unreachable!("num_alpha_num only produces Num and Affixed"),
+    };
+    Ok((rem, token))
+}
+
+#[test]
+fn test_hyphenated_prefix() {
+    assert_eq!(hyphenated_prefix_num("A-3"), Ok(("", afxd("A-", 3, ""))));
+    assert_eq!(
+        hyphenated_prefix_num("A-3b-A-7"),
+        Ok(("-A-7", afxd("A-", 3, "b")))
+    );
+    assert!(hyphenated_prefix_num("xiv-xix").is_err());
+    assert!(hyphenated_prefix_num("iv-8").is_err());
+}
+
 fn roman_numeral(inp: &str) -> IResult<&str, NumericToken> {
     let (rest, potential) = non_sep(inp)?;
     if let Some(rom) = roman::from(potential) {
@@ -404,7 +456,7 @@ fn roman_numeral(inp: &str) -> IResult<&str, NumericToken> {
 }
 
 fn num_ish(inp: &str) -> IResult<&str, NumericToken> {
-    alt((roman_numeral, num_alpha_num, int))(inp)
+    alt((hyphenated_prefix_num, roman_numeral, num_alpha_num, int))(inp)
 }
 
 fn num_tokens<'a>(
@@ -493,6 +545,16 @@ fn test_numeric_value() {
         ]
     );
     test_parse!("1998-VIII", [nn(1998), Hyphen, Roman(8, true)]);
+    test_parse!(
+        "S12\u{2013}S15",
+        [afxd("S", 12, ""), Hyphen, afxd("S", 15, "")]
+    );
+    test_parse!(
+        "A-3\u{2013}A-7",
+        [afxd("A-", 3, ""), Hyphen, afxd("A-", 7, "")]
+    );
+    test_parse!("A-3-A-7", [afxd("A-", 3, ""), Hyphen, afxd("A-", 7, "")]);
+    test_parse!("xiv-xix", [Roman(14, false), Hyphen, Roman(19, false)]);
     // Random extra text is parsed into units
     test_parse!(
         "2 - 5, 9, edition, iv",
@@ -535,4 +597,13 @@ fn test_page_first() {
         NumericValue::parse("2-5, 9").page_first().unwrap(),
         NumericValue::num(2)
     );
+    assert_eq!(
+        NumericValue::parse("S12-S15").page_first().unwrap(),
+        NumericValue::Tokens("S12".into(), vec![afxd("S", 12, "")], true)
+    );
+    assert_eq!(
+        NumericValue::parse("xiv-xix").page_first().unwrap(),
+        NumericValue::Tokens("xiv".into(), vec![Roman(14, false)], true)
+    );
+    assert_eq!(NumericValue::parse("Preface").page_first(), None);
 }
@@ -46,11 +46,18 @@ pub enum Markup {
 pub struct FormatOptions {
     /// See CSL 1.1, Appendix VI -- enable or disable making urls clickable. Default is enabled.
     pub link_anchors: bool,
+    /// In plain text, pads `display="left-margin"` fields with spaces to this many characters, so
+    /// the rest of each bibliography entry lines up. `BibliographyMeta::max_offset` plus one is a
+    /// good choice. Default is no padding.
+    pub left_margin_width: Option<u32>,
 }
 
 impl Default for FormatOptions {
     fn default() -> Self {
-        FormatOptions { link_anchors: true }
+        FormatOptions {
+            link_anchors: true,
+            left_margin_width: None,
+        }
     }
 }
 
@@ -58,6 +65,7 @@ impl FormatOptions {
     pub fn test_suite() -> Self {
         FormatOptions {
             link_anchors: false,
+            left_margin_width: None,
         }
     }
 }
@@ -96,6 +104,55 @@ impl Markup {
     pub fn plain() -> Self {
         Markup::Plain(FormatOptions::default())
     }
+
+    /// Like [OutputFormat::meta], but RTF also gets the paragraph settings for the bibliography's
+    /// `hanging-indent`, `second-field-align`, `line-spacing` and `entry-spacing`, calculated the
+    /// way Zotero does it. `max_offset` is the width of the widest left-margin field.
+    pub fn bibliography_meta(&self, bib: &csl::Bibliography, max_offset: u32) -> MarkupBibMeta {
+        use csl::SecondFieldAlign;
+        use std::fmt::Write;
+        if let Markup::Rtf(_) = self {
+            // Twips; works well enough for first fields like "[1]" and "1."
+            let align_at = 24 + i64::from(max_offset) * 120;
+            let (tab_stop, indent, first_line_indent) = match bib.second_field_align {
+                Some(SecondFieldAlign::Flush) => (Some(align_at), align_at, -align_at),
+                Some(SecondFieldAlign::Margin) => (Some(0), 0, -align_at),
+                None if bib.hanging_indent => (None, 720, -720),
+                None => (None, 0, 0),
+            };
+            let mut pre = String::from("{\\pard ");
+            if let Some(tab_stop) = tab_stop {
+                write!(pre, "\\tx{} ", tab_stop).unwrap();
+            }
+            write!(
+                pre,
+                "\\li{} \\fi{} \\sl{} \\slmult1 \\sa{} ",
+                indent,
+                first_line_indent,
+                240 * bib.line_spacing,
+                240 * bib.entry_spacing
+            )
+            .unwrap();
+            return MarkupBibMeta {
+                markup_pre: pre,
+                markup_post: "}".into(),
+            };
+        }
+        self.meta()
+    }
+
+    /// How many characters wide the first `display="left-margin"` field in `build` is, in plain
+    /// text.
+    pub fn left_margin_width(&self, build: &[InlineElement]) -> Option<u32> {
+        build.iter().find_map(|inline| match inline {
+            Div(DisplayMode::LeftMargin, inlines) => {
+                let plain = Markup::plain().output(inlines.clone(), false);
+                Some(plain.chars().count() as u32)
+            }
+            Div(_, inlines) | Formatted(inlines, _) => self.left_margin_width(inlines),
+            _ => None,
+        })
+    }
 }
 
 impl Default for Markup {
@@ -137,6 +194,9 @@ impl OutputFormat for Markup {
         if nodes.is_empty() {
             return Vec::new();
         }
+        if options.text_case == TextCase::Normal {
+            nodes = vec![MicroNode::NoCase(nodes)];
+        }
         vec![InlineElement::Micro(nodes)]
     }
 
@@ -273,11 +333,34 @@ impl OutputFormat for Markup {
 
     #[inline]
     fn apply_text_case(&self, build: &mut Self::Build, options: &IngestOptions) {
-        if options.text_case == TextCase::None {
-            return;
+        match options.text_case {
+            TextCase::None => {}
+            // Shield it from the text-case of an enclosing group or macro
+            TextCase::Normal => protect_case(build),
+            _ => {
+                let is_uppercase = options.is_uppercase(build);
+                options.apply_text_case_inner(build, false, is_uppercase);
+            }
+        }
+    }
+}
+
+/// Puts all the text in `<span class="nocase">`, which text-case leaves alone.
+fn protect_case(inlines: &mut [InlineElement]) {
+    for inline in inlines {
+        match inline {
+            Text(text) => {
+                let text = std::mem::take(text);
+                *inline = Micro(vec![MicroNode::NoCase(vec![MicroNode::Text(text)])]);
+            }
+            Micro(micros) => {
+                *micros = vec![MicroNode::NoCase(std::mem::take(micros))];
+            }
+            Formatted(inlines, _) | Quoted { inlines, .. } | Div(_, inlines) => {
+                protect_case(inlines)
+            }
+            Linked(_) => {}
         }
-        let is_uppercase = options.is_uppercase(build);
-        options.apply_text_case_inner(build, false, is_uppercase);
     }
 }
 
@@ -9,12 +9,11 @@ use crate::output::markup::Link;
 use crate::output::micro_html::MicroNode;
 use crate::output::FormatCmd;
 use crate::String;
-use csl::Formatting;
+use csl::{DisplayMode, Formatting};
 
 #[derive(Debug)]
 pub struct PlainWriter<'a> {
     dest: &'a mut String,
-    #[allow(unused)]
     options: FormatOptions,
 }
 
@@ -84,7 +83,17 @@ impl<'a> MarkupWriter for PlainWriter<'a> {
                 self.write_escaped(text.trim_start_if(trim_start));
             }
             Div(display, inlines) => {
+                let start = self.dest.len();
                 self.stack_formats(inlines, Formatting::default(), Some(*display));
+                if let (DisplayMode::LeftMargin, Some(width)) =
+                    (display, self.options.left_margin_width)
+                {
+                    let written = self.dest[start..].chars().count();
+                    let padding = (width as usize).saturating_sub(written).max(1);
+                    for _ in 0..padding {
+                        self.dest.push(' ');
+                    }
+                }
             }
             Micro(micros) => {
                 self.write_micros(micros, trim_start);
@@ -53,11 +53,12 @@ impl<'a> MarkupWriter for RtfWriter<'a> {
     }
 
     fn stack_postorder(&mut self, stack: &[FormatCmd]) {
-        for cmd in stack.iter() {
+        for cmd in stack.iter().rev() {
             if *cmd == FormatCmd::DisplayRightInline {
                 let tlen = self.dest.trim_end_matches(' ').len();
                 self.dest.truncate(tlen);
             }
+            self.dest.push_str(cmd.rtf_close_tag());
             self.dest.push('}');
         }
     }
@@ -135,9 +136,12 @@ impl FormatCmd {
     fn rtf_tag(self) -> &'static str {
         use super::FormatCmd::*;
         match self {
-            // TODO: RTF display commands
-            DisplayBlock => "",
-            DisplayIndent => "",
+            // Blocks and indented blocks go on lines of their own, and a left-margin field is
+            // followed by a tab to the second field (see rtf_close_tag). A right-inline field is
+            // the rest of the entry, so it needs neither. The tab stops and indentation are set
+            // up in Markup::bibliography_meta.
+            DisplayBlock => "\\line ",
+            DisplayIndent => "\\tab ",
             DisplayLeftMargin => "",
             DisplayRightInline => "",
 
@@ -162,6 +166,16 @@ impl FormatCmd {
             VerticalAlignmentBaseline => "\\nosupersub ",
         }
     }
+
+    /// Written just before the closing brace.
+    fn rtf_close_tag(self) -> &'static str {
+        use super::FormatCmd::*;
+        match self {
+            DisplayBlock | DisplayIndent => "\\line ",
+            DisplayLeftMargin => "\\tab ",
+            _ => "",
+        }
+    }
 }
 
 use nom::{bytes::complete as nbc, character::complete::anychar, IResult, Parser};
@@ -0,0 +1,533 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! A parser for the free-form `{ "raw": "..." }` date field in CSL-JSON.
+//!
+//! The ISO-ish nom parser in `date.rs` only handles `1998-09-21` and `1998-09/2001`. Imported
+//! libraries are full of things like `March 3, 2001`, `3 mars 2001`, `Spring 1998`, `c. 1850` and
+//! `1990-92`. [RawDateParser][] reads month and season names out of one or more [csl::Locale][]s
+//! and uses them to recognise those forms.
+
+use crate::date::{Date, DateOrRange};
+use crate::String;
+use csl::{GenderedTermSelector, Locale, SeasonTerm};
+use std::fmt;
+use std::str::FromStr;
+
+/// Why a raw date could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RawDateErrorKind {
+    /// Nothing but whitespace and circa markers
+    Empty,
+    /// A word that is not a month, season, era or filler word in any of the locales
+    UnknownWord(String),
+    /// Neither side of the date had a year
+    NoYear,
+    /// There were more numbers than a date has room for, e.g. `1 2 3 2004`
+    TooManyNumbers,
+    /// A day of the month was given without a month
+    DayWithoutMonth,
+    /// More than two dates joined by range separators
+    TooManyDates,
+    /// A number too large to be a year, e.g. `99999999999`
+    NumberTooLarge,
+}
+
+/// A raw date that [RawDateParser][] could not make sense of. The original input is kept so it
+/// can be reported to the user, rather than silently rendered as something else.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawDateError {
+    pub input: String,
+    pub kind: RawDateErrorKind,
+}
+
+impl fmt::Display for RawDateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not parse raw date {:?}: ", self.input)?;
+        match &self.kind {
+            RawDateErrorKind::Empty => write!(f, "no date found"),
+            RawDateErrorKind::UnknownWord(w) => write!(f, "unknown word {:?}", w),
+            RawDateErrorKind::NoYear => write!(f, "no year found"),
+            RawDateErrorKind::TooManyNumbers => write!(f, "too many numbers"),
+            RawDateErrorKind::DayWithoutMonth => write!(f, "day given without a month"),
+            RawDateErrorKind::TooManyDates => write!(f, "more than two dates in a range"),
+            RawDateErrorKind::NumberTooLarge => write!(f, "number too large to be a year"),
+        }
+    }
+}
+
+impl std::error::Error for RawDateError {}
+
+/// Parses raw date strings using month and season terms from a set of locales.
+///
+/// Build one with [RawDateParser::english][] (the bundled en-US terms) or
+/// [RawDateParser::from_locales][], typically with English plus the language of the reference.
+#[derive(Debug, Clone, Default)]
+pub struct RawDateParser {
+    /// Normalised (lowercase, no periods) month and season names, with the `Date::month` value
+    /// they map to. Seasons are 13 to 16.
+    months: Vec<(String, u32)>,
+}
+
+// Words that can sit between the parts of a date without meaning anything, e.g. "3 de marzo de
+// 2001" or "the 3rd of March".
+const FILLER_WORDS: &[&str] = &[
+    "of", "the", "de", "del", "den", "der", "le", "la", "am", "im",
+];
+const CIRCA_WORDS: &[&str] = &[
+    "c",
+    "ca",
+    "circa",
+    "approx",
+    "approximately",
+    "about",
+    "around",
+];
+const BCE_WORDS: &[&str] = &["bc", "bce"];
+const CE_WORDS: &[&str] = &["ad", "ce"];
+const ORDINAL_SUFFIXES: &[&str] = &["st", "nd", "rd", "th", "er", "e", "o", "a"];
+
+fn normalise_word(word: &str) -> String {
+    word.chars()
+        .filter(|&c| c != '.')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl RawDateParser {
+    /// A parser using the month and season names from the bundled en-US locale.
+    pub fn english() -> &'static Self {
+        static EN: once_cell::sync::OnceCell<RawDateParser> = once_cell::sync::OnceCell::new();
+        EN.get_or_init(|| {
+            let en_us = Locale::from_str(csl::EN_US).expect("bundled en-US locale is valid");
+            let mut parser = RawDateParser::from_locales(std::iter::once(&en_us));
+            // Not a term in en-US, but far too common in American data to ignore.
+            parser.push_name("fall", 15);
+            parser
+        })
+    }
+
+    /// Collects every form of every month and season term defined in `locales`.
+    pub fn from_locales<'a>(locales: impl IntoIterator<Item = &'a Locale>) -> Self {
+        let mut parser = RawDateParser::default();
+        for locale in locales {
+            parser.add_locale(locale);
+        }
+        parser
+    }
+
+    pub fn add_locale(&mut self, locale: &Locale) {
+        for (sel, term) in locale.gendered_terms.iter() {
+            let month = match *sel {
+                // MonthTerm is declared in order, Month01 first
+                GenderedTermSelector::Month(mt, _) => mt as u32 + 1,
+                GenderedTermSelector::Season(st, _) => {
+                    12 + match st {
+                        SeasonTerm::Season01 => 1,
+                        SeasonTerm::Season02 => 2,
+                        SeasonTerm::Season03 => 3,
+                        SeasonTerm::Season04 => 4,
+                    }
+                }
+                _ => continue,
+            };
+            self.push_name(term.0.singular(), month);
+            self.push_name(term.0.plural(), month);
+        }
+    }
+
+    fn push_name(&mut self, name: &str, month: u32) {
+        let name = normalise_word(name);
+        if name.is_empty() || self.months.iter().any(|(n, m)| *n == name && *m == month) {
+            return;
+        }
+        self.months.push((name, month));
+    }
+
+    fn lookup_month(&self, word: &str) -> Option<u32> {
+        self.months
+            .iter()
+            .find(|(name, _)| name == word)
+            .map(|(_, m)| *m)
+    }
+
+    /// Parses a raw date. ISO-style input (`1998-09-21/2001`) is accepted exactly as
+    /// [DateOrRange::from_str][] would, as long as the whole string is consumed.
+    pub fn parse(&self, raw: &str) -> Result<DateOrRange, RawDateError> {
+        let err = |kind| RawDateError {
+            input: raw.into(),
+            kind,
+        };
+        let (input, circa) = strip_circa(raw.trim());
+        if input.is_empty() {
+            return Err(err(RawDateErrorKind::Empty));
+        }
+        if let Some(iso) = iso_complete(input) {
+            return Ok(iso.with_circa(circa));
+        }
+        let tokens = tokenize(input).map_err(err)?;
+        let mut halves = tokens.split(|t| *t == Token::RangeSep);
+        let first = halves.next().unwrap_or(&[]);
+        let second = halves.next();
+        if halves.next().is_some() {
+            return Err(err(RawDateErrorKind::TooManyDates));
+        }
+        let mut start = self.parse_half(first).map_err(err)?;
+        let mut circa = circa || start.circa;
+        let parsed = match second {
+            None => {
+                let year = start.year.ok_or_else(|| err(RawDateErrorKind::NoYear))?;
+                DateOrRange::Single(start.into_date(year).map_err(err)?)
+            }
+            Some(second) => {
+                let end = self.parse_half(second).map_err(err)?;
+                circa = circa || end.circa;
+                // "3–5 March 2001": a lone small number on the left is a day, not a year
+                if let (None, None, Some(n), Some(_)) =
+                    (start.month, start.day, start.year, end.day)
+                {
+                    if start.year_digits <= 2 && n >= 1 && n <= 31 {
+                        start.day = Some(n as u32);
+                        start.year = None;
+                    }
+                }
+                // Fill in what the left side leaves out: "March–April 2001", "3–5 March 2001"
+                if start.month.is_none() && start.day.is_some() {
+                    start.month = end.month;
+                }
+                if start.year.is_none() {
+                    start.year = end.year;
+                }
+                let start_year = start.year.ok_or_else(|| err(RawDateErrorKind::NoYear))?;
+                let end_year = match end.year {
+                    // "1990-92", "1998-9"
+                    Some(y) if end.year_digits < 3 && start_year >= 100 && y >= 0 => {
+                        let modulus = 10i32.pow(end.year_digits as u32);
+                        (start_year - start_year % modulus)
+                            .checked_add(y)
+                            .and_then(|expanded| {
+                                if expanded < start_year {
+                                    expanded.checked_add(modulus)
+                                } else {
+                                    Some(expanded)
+                                }
+                            })
+                            .ok_or_else(|| err(RawDateErrorKind::NumberTooLarge))?
+                    }
+                    Some(y) => y,
+                    None => start_year,
+                };
+                DateOrRange::Range(
+                    start.into_date(start_year).map_err(err)?,
+                    end.into_date(end_year).map_err(err)?,
+                )
+            }
+        };
+        Ok(parsed.with_circa(circa))
+    }
+
+    fn parse_half(&self, tokens: &[Token]) -> Result<PartialDate, RawDateErrorKind> {
+        let mut half = PartialDate::default();
+        let mut numbers: Vec<(i32, usize)> = Vec::new();
+        let mut bce = false;
+        for token in tokens {
+            match token {
+                Token::Number(n, digits) => numbers.push((*n, *digits)),
+                Token::Word(w) => {
+                    if let Some(m) = self.lookup_month(w) {
+                        if half.month.is_some() {
+                            return Err(RawDateErrorKind::UnknownWord(w.clone()));
+                        }
+                        half.month = Some(m);
+                    } else if BCE_WORDS.contains(&w.as_str()) {
+                        bce = true;
+                    } else if CIRCA_WORDS.contains(&w.as_str()) {
+                        half.circa = true;
+                    } else if !CE_WORDS.contains(&w.as_str()) && !FILLER_WORDS.contains(&w.as_str())
+                    {
+                        return Err(RawDateErrorKind::UnknownWord(w.clone()));
+                    }
+                }
+                Token::RangeSep => unreachable!("halves are split on RangeSep"),
+            }
+        }
+        match numbers.as_slice() {
+            [] => {}
+            [(n, digits)] => {
+                // With a month name, a number that could be a day is a day ("March 3"), unless
+                // the other half will supply the year. Without one it's a year ("1998", "92").
+                if half.month.map_or(false, |m| m <= 12) && *n >= 1 && *n <= 31 && *digits <= 2 {
+                    half.day = Some(*n as u32);
+                } else {
+                    half.year = Some(*n);
+                    half.year_digits = *digits;
+                }
+            }
+            [a, b] => {
+                // Year is whichever can't be a day; prefer the last one.
+                let (day, year) = if a.1 <= 2 && a.0 >= 1 && a.0 <= 31 {
+                    (a, b)
+                } else if b.1 <= 2 && b.0 >= 1 && b.0 <= 31 {
+                    (b, a)
+                } else {
+                    return Err(RawDateErrorKind::TooManyNumbers);
+                };
+                if half.month.is_none() {
+                    return Err(RawDateErrorKind::DayWithoutMonth);
+                }
+                half.day = Some(day.0 as u32);
+                half.year = Some(year.0);
+                half.year_digits = year.1;
+            }
+            _ => return Err(RawDateErrorKind::TooManyNumbers),
+        }
+        if bce {
+            half.year = half.year.map(|y| -y);
+            // a BCE year is never abbreviated in a range
+            half.year_digits = 4;
+        }
+        Ok(half)
+    }
+}
+
+#[derive(Debug, Default)]
+struct PartialDate {
+    year: Option<i32>,
+    /// How many digits the year was written with, for expanding "1990-92"
+    year_digits: usize,
+    month: Option<u32>,
+    day: Option<u32>,
+    circa: bool,
+}
+
+impl PartialDate {
+    fn into_date(self, year: i32) -> Result<Date, RawDateErrorKind> {
+        let month = self.month.unwrap_or(0);
+        let day = match self.day {
+            Some(_) if month == 0 || month > 12 => return Err(RawDateErrorKind::DayWithoutMonth),
+            Some(d) => d,
+            None => 0,
+        };
+        Ok(Date::new(year, month, day))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// The value and the number of digits it was written with
+    Number(i32, usize),
+    /// Normalised with [normalise_word]
+    Word(String),
+    RangeSep,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, RawDateErrorKind> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(ix, c)) = chars.peek() {
+        if c.is_ascii_digit() {
+            let mut end = ix;
+            while let Some(&(j, d)) = chars.peek() {
+                if !d.is_ascii_digit() {
+                    break;
+                }
+                end = j + d.len_utf8();
+                chars.next();
+            }
+            let digits = &input[ix..end];
+            let n = digits
+                .parse()
+                .map_err(|_| RawDateErrorKind::NumberTooLarge)?;
+            tokens.push(Token::Number(n, digits.len()));
+            // "3rd", "1er", but not "44BC"
+            let suffix_start = end;
+            let mut suffix_end = end;
+            while let Some(&(j, d)) = chars.peek() {
+                if !d.is_alphabetic() && d != '.' {
+                    break;
+                }
+                suffix_end = j + d.len_utf8();
+                chars.next();
+            }
+            if suffix_end > suffix_start {
+                let suffix = normalise_word(&input[suffix_start..suffix_end]);
+                if !ORDINAL_SUFFIXES.contains(&suffix.as_str()) && !suffix.is_empty() {
+                    tokens.push(Token::Word(suffix));
+                }
+            }
+        } else if c.is_alphabetic() {
+            let mut end = ix;
+            while let Some(&(j, d)) = chars.peek() {
+                if !d.is_alphabetic() && d != '.' && d != '\'' {
+                    break;
+                }
+                end = j + d.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Word(normalise_word(&input[ix..end])));
+        } else if c == '-' || c == '–' || c == '—' || c == '/' {
+            tokens.push(Token::RangeSep);
+            chars.next();
+        } else {
+            // whitespace, commas, brackets, question marks
+            chars.next();
+        }
+    }
+    Ok(tokens)
+}
+
+/// Strips `c.`, `ca.`, `circa`, `~` and `[1850?]`-style uncertainty markers from either end.
+fn strip_circa(input: &str) -> (&str, bool) {
+    let mut circa = false;
+    let mut s = input;
+    loop {
+        let before = s;
+        for prefix in &["circa", "approx.", "ca.", "ca ", "c.", "c ", "~"] {
+            if s.len() >= prefix.len()
+                && s.is_char_boundary(prefix.len())
+                && s[..prefix.len()].eq_ignore_ascii_case(prefix)
+            {
+                s = s[prefix.len()..].trim_start();
+                circa = true;
+            }
+        }
+        if s.starts_with('[') && s.ends_with(']') {
+            s = s[1..s.len() - 1].trim();
+        }
+        if s.ends_with('?') {
+            s = s.trim_end_matches('?').trim_end();
+            circa = true;
+        }
+        if s == before {
+            break;
+        }
+    }
+    (s, circa)
+}
+
+/// Only accepts the ISO parse if nothing was left over, so `1990-92` is not read as `1990`.
+fn iso_complete(input: &str) -> Option<DateOrRange> {
+    match crate::date::range(input.as_bytes()) {
+        Ok((b"", parsed)) => Some(parsed),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn en(s: &str) -> Result<DateOrRange, RawDateErrorKind> {
+        RawDateParser::english().parse(s).map_err(|e| e.kind)
+    }
+
+    #[test]
+    fn natural_language() {
+        assert_eq!(en("March 3, 2001"), Ok(DateOrRange::new(2001, 3, 3)));
+        assert_eq!(en("3 March 2001"), Ok(DateOrRange::new(2001, 3, 3)));
+        assert_eq!(en("3rd of Mar. 2001"), Ok(DateOrRange::new(2001, 3, 3)));
+        assert_eq!(en("Jan 2001"), Ok(DateOrRange::new(2001, 1, 0)));
+        assert_eq!(en("Jan. 2001"), Ok(DateOrRange::new(2001, 1, 0)));
+        assert_eq!(en("Spring 1998"), Ok(DateOrRange::new(1998, 13, 0)));
+        assert_eq!(en("fall 1998"), Ok(DateOrRange::new(1998, 15, 0)));
+        assert_eq!(en("44 BC"), Ok(DateOrRange::new(-44, 0, 0)));
+        assert_eq!(en("1998-09-21"), Ok(DateOrRange::new(1998, 9, 21)));
+    }
+
+    #[test]
+    fn circa() {
+        assert_eq!(
+            en("c. 1850"),
+            Ok(DateOrRange::new(1850, 0, 0).with_circa(true))
+        );
+        assert_eq!(
+            en("ca 1850"),
+            Ok(DateOrRange::new(1850, 0, 0).with_circa(true))
+        );
+        assert_eq!(
+            en("[1850?]"),
+            Ok(DateOrRange::new(1850, 0, 0).with_circa(true))
+        );
+        assert_eq!(
+            en("circa May 1850"),
+            Ok(DateOrRange::new(1850, 5, 0).with_circa(true))
+        );
+    }
+
+    #[test]
+    fn ranges() {
+        let range = |a, b| Ok(DateOrRange::Range(a, b));
+        assert_eq!(
+            en("1990-92"),
+            range(Date::new(1990, 0, 0), Date::new(1992, 0, 0))
+        );
+        assert_eq!(
+            en("1998–9"),
+            range(Date::new(1998, 0, 0), Date::new(1999, 0, 0))
+        );
+        assert_eq!(
+            en("1999–02"),
+            range(Date::new(1999, 0, 0), Date::new(2002, 0, 0))
+        );
+        assert_eq!(
+            en("March–April 2001"),
+            range(Date::new(2001, 3, 0), Date::new(2001, 4, 0))
+        );
+        assert_eq!(
+            en("3–5 March 2001"),
+            range(Date::new(2001, 3, 3), Date::new(2001, 3, 5))
+        );
+        assert_eq!(
+            en("December 1999 - January 2000"),
+            range(Date::new(1999, 12, 0), Date::new(2000, 1, 0))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(en(""), Err(RawDateErrorKind::Empty));
+        assert_eq!(en("c."), Err(RawDateErrorKind::Empty));
+        assert_eq!(en("March"), Err(RawDateErrorKind::NoYear));
+        assert_eq!(
+            en("sometime 1998"),
+            Err(RawDateErrorKind::UnknownWord("sometime".into()))
+        );
+        assert_eq!(en("1 2 3 2004"), Err(RawDateErrorKind::TooManyNumbers));
+        assert_eq!(en("1990-1991-1992"), Err(RawDateErrorKind::TooManyDates));
+        assert_eq!(en("99999999999"), Err(RawDateErrorKind::NumberTooLarge));
+        assert_eq!(en("99999999999-05"), Err(RawDateErrorKind::NumberTooLarge));
+        // the year fits, but expanding the end of the range would not
+        assert_eq!(en("2147483647-99"), Err(RawDateErrorKind::NumberTooLarge));
+        assert_eq!(en("2147483600-99"), Err(RawDateErrorKind::NumberTooLarge));
+    }
+
+    #[test]
+    fn other_locale() {
+        let fr = Locale::from_str(
+            r#"<locale xml:lang="fr-FR">
+                <terms>
+                    <term name="month-03">mars</term>
+                    <term name="month-01" form="short">janv.</term>
+                    <term name="season-04">hiver</term>
+                </terms>
+            </locale>"#,
+        )
+        .unwrap();
+        let parser = RawDateParser::from_locales(std::iter::once(&fr));
+        assert_eq!(
+            parser.parse("3 mars 2001"),
+            Ok(DateOrRange::new(2001, 3, 3))
+        );
+        assert_eq!(
+            parser.parse("1er janv. 2001"),
+            Ok(DateOrRange::new(2001, 1, 1))
+        );
+        assert_eq!(
+            parser.parse("Hiver 1998"),
+            Ok(DateOrRange::new(1998, 16, 0))
+        );
+    }
+}
@@ -11,7 +11,8 @@ extern crate fnv;
 use fnv::FnvHashMap;
 
 use super::date::DateOrRange;
-use super::names::Name;
+use super::names::{parse_literal_name, Name, PersonName};
+use super::raw_date::{RawDateError, RawDateParser};
 use crate::NumberLike;
 use csl::{Atom, CslType, DateVariable, Lang, NameVariable, NumberVariable, Variable};
 
@@ -30,6 +31,10 @@ pub struct Reference {
     pub number: FnvHashMap<NumberVariable, NumberLike>,
     pub name: FnvHashMap<NameVariable, Vec<Name>>,
     pub date: FnvHashMap<DateVariable, DateOrRange>,
+    /// `{ "raw": "..." }` strings that could not be parsed as English dates during
+    /// deserialization, kept so they can be tried again once the reference's language is known.
+    /// See [Reference::reparse_raw_dates].
+    pub raw_dates: FnvHashMap<DateVariable, String>,
 }
 
 impl Reference {
@@ -42,6 +47,55 @@ impl Reference {
             number: FnvHashMap::default(),
             name: FnvHashMap::default(),
             date: FnvHashMap::default(),
+            raw_dates: FnvHashMap::default(),
+        }
+    }
+
+    /// Parses the unparsed raw dates again with `parser`, replacing the fallback values for the
+    /// ones that succeed. The ones that still fail are returned so they can be reported.
+    pub fn reparse_raw_dates(&mut self, parser: &RawDateParser) -> Vec<RawDateError> {
+        let mut errors = Vec::new();
+        let date = &mut self.date;
+        self.raw_dates.retain(|var, raw| match parser.parse(raw) {
+            Ok(mut parsed) => {
+                // a separate "circa": true key applies whichever way the raw string was read
+                if date.get(var).map_or(false, DateOrRange::is_uncertain_date) {
+                    parsed.set_circa(true);
+                }
+                date.insert(*var, parsed);
+                false
+            }
+            Err(e) => {
+                errors.push(e);
+                true
+            }
+        });
+        errors
+    }
+
+    /// Splits `{ "literal": "..." }` names into given/family/particle/suffix parts with
+    /// [parse_literal_name]. Names given as a structured family name, e.g.
+    /// `{ "family": "World Health Organization" }`, are left alone.
+    pub fn parse_literal_names(&mut self) {
+        let lang = self.language.as_ref();
+        for name in self.name.values_mut().flat_map(|names| names.iter_mut()) {
+            let literal = match name {
+                Name::Literal { literal, .. } => literal.clone(),
+                Name::Person(PersonName {
+                    family: Some(family),
+                    given: None,
+                    non_dropping_particle: None,
+                    dropping_particle: None,
+                    suffix: None,
+                    is_literal: true,
+                    ..
+                }) => family.clone(),
+                _ => continue,
+            };
+            *name = parse_literal_name(&literal, lang);
+            if let Name::Person(pn) = name {
+                pn.is_literal = true;
+            }
         }
     }
 }
@@ -2,9 +2,51 @@ use crate::output::markup::InlineElement;
 use crate::output::micro_html::MicroNode;
 use crate::output::LocalizedQuotes;
 use crate::{lazy, IngestOptions, SmartCow, String};
-use csl::{FontVariant, TextCase, VerticalAlignment};
+use csl::{FontVariant, IsoLang, Lang, TextCase, VerticalAlignment};
+use std::cell::Cell;
 use unic_segment::{GraphemeIndices, WordBoundIndices, Words};
 
+/// Whose capitalisation rules `text-case="title"` and `text-case="sentence"` follow, from the
+/// reference's `language`, or else the style's `default-locale`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CaseLanguage {
+    English,
+    German,
+    French,
+    Spanish,
+    /// Title case is not applied at all, per the CSL spec's rule for non-English items.
+    Other,
+}
+
+impl Default for CaseLanguage {
+    fn default() -> Self {
+        CaseLanguage::Other
+    }
+}
+
+impl CaseLanguage {
+    pub fn from_lang(lang: &Lang) -> Self {
+        match lang {
+            Lang::Iso(IsoLang::English, _) => CaseLanguage::English,
+            Lang::Iso(IsoLang::Deutsch, _) => CaseLanguage::German,
+            Lang::Iso(IsoLang::French, _) => CaseLanguage::French,
+            Lang::Iso(IsoLang::Spanish, _) => CaseLanguage::Spanish,
+            _ => CaseLanguage::Other,
+        }
+    }
+
+    /// Words left in lowercase by title case. English uses [stopword_regex] instead, and CSL-M
+    /// `skip-words` replace both.
+    fn stopwords(self) -> &'static [&'static str] {
+        match self {
+            CaseLanguage::German => GERMAN_STOPWORDS,
+            CaseLanguage::French => FRENCH_STOPWORDS,
+            CaseLanguage::Spanish => SPANISH_STOPWORDS,
+            CaseLanguage::English | CaseLanguage::Other => &[],
+        }
+    }
+}
+
 // from the unic_segment example code
 fn has_alphanumeric(s: &&str) -> bool {
     is_word(*s)
@@ -215,6 +257,162 @@ fn stopwords() {
     assert!(!is_stopword("this word followed by l’Égypte "));
 }
 
+// Articles, prepositions and conjunctions.
+static GERMAN_STOPWORDS: &[&str] = &[
+    "aber", "als", "am", "an", "auf", "aus", "bei", "beim", "bis", "das", "dem", "den", "der",
+    "des", "die", "durch", "ein", "eine", "einem", "einen", "einer", "eines", "für", "gegen", "im",
+    "in", "ins", "mit", "nach", "neben", "noch", "ob", "oder", "ohne", "sowie", "über", "um",
+    "und", "unter", "vom", "von", "vor", "während", "wegen", "weder", "wie", "zu", "zum", "zur",
+    "zwischen",
+];
+static FRENCH_STOPWORDS: &[&str] = &[
+    "à", "au", "aux", "avec", "chez", "contre", "dans", "de", "des", "du", "en", "entre", "et",
+    "la", "le", "les", "mais", "ni", "ou", "par", "pour", "que", "sans", "selon", "sous", "sur",
+    "un", "une", "vers",
+];
+static SPANISH_STOPWORDS: &[&str] = &[
+    "a", "al", "ante", "bajo", "como", "con", "contra", "de", "del", "desde", "durante", "e", "el",
+    "en", "entre", "hacia", "hasta", "la", "las", "lo", "los", "mediante", "ni", "o", "para",
+    "pero", "por", "que", "según", "sin", "sobre", "tras", "u", "un", "una", "unas", "unos", "y",
+];
+/// Leading articles that French capitalises the following word after, as in _Le Monde
+/// diplomatique_.
+static FRENCH_ARTICLES: &[&str] = &["l", "la", "le", "les", "un", "une"];
+/// Elided words that are part of the next word as far as Unicode word boundaries are concerned.
+static FRENCH_ELISIONS: &[&str] = &[
+    "c", "d", "j", "jusqu", "l", "lorsqu", "m", "n", "puisqu", "qu", "s", "t",
+];
+
+fn is_listed<S: AsRef<str>>(word: &str, list: &[S]) -> bool {
+    let lower = lazy::lazy_lowercase(word);
+    list.iter().any(|listed| listed.as_ref() == lower.as_ref())
+}
+
+/// Splits `l'histoire` into `("l'", "histoire")`. `aujourd'hui` stays whole.
+fn split_elision(word: &str, lang: CaseLanguage) -> (&str, &str) {
+    if lang != CaseLanguage::French {
+        return ("", word);
+    }
+    if let Some((ix, apos)) = word
+        .char_indices()
+        .find(|&(_, c)| c == '\'' || c == '\u{2019}')
+    {
+        let rest_ix = ix + apos.len_utf8();
+        if is_listed(&word[..ix], FRENCH_ELISIONS) && rest_ix < word.len() {
+            return (&word[..rest_ix], &word[rest_ix..]);
+        }
+    }
+    ("", word)
+}
+
+/// Two or more letters, all capitals, like `NATO` or `UNESCO`.
+fn is_acronym(word: &str) -> bool {
+    word.chars().filter(|c| c.is_alphabetic()).nth(1).is_some() && !any_lowercase(word)
+}
+
+fn title_case_word_lang<'a, S: AsRef<str>>(
+    word: &'a str,
+    entire_is_uppercase: bool,
+    no_stopword: bool,
+    lang: CaseLanguage,
+    stopwords: &[S],
+) -> SmartCow<'a> {
+    // Nothing to learn from the capitals in an all-caps string
+    let word = if entire_is_uppercase {
+        lazy::lazy_lowercase(word)
+    } else if is_acronym(word) {
+        return SmartCow::Borrowed(word);
+    } else {
+        SmartCow::Borrowed(word)
+    };
+    let (elided, rest) = split_elision(&word, lang);
+    let mut ret = String::new();
+    if !elided.is_empty() {
+        // qu'Elle, l'Histoire
+        if no_stopword {
+            ret.push_str(&transform_uppercase_first(elided));
+        } else {
+            ret.push_str(&lazy::lazy_lowercase(elided));
+        }
+        ret.push_str(&transform_uppercase_first(rest));
+    } else if !no_stopword && is_listed(rest, stopwords) {
+        ret.push_str(&lazy::lazy_lowercase(rest));
+    } else {
+        ret.push_str(&transform_uppercase_first(rest));
+    }
+    SmartCow::Owned(ret)
+}
+
+/// Title case with a plain list of stopwords, for languages other than English, or any language
+/// when a locale defines CSL-M `skip-words`.
+fn transform_title_case_lang<S: AsRef<str>>(
+    s: &str,
+    seen_one: bool,
+    is_last: bool,
+    entire_is_uppercase: bool,
+    lang: CaseLanguage,
+    stopwords: &[S],
+) -> String {
+    // transform_each_word's is_first is also false after leading punctuation like ¿ or «.
+    let first = Cell::new(!seen_one);
+    transform_each_word(s, seen_one, is_last, |word, _, _, no_stop| {
+        let no_stop = first.replace(false) || no_stop;
+        (
+            title_case_word_lang(word, entire_is_uppercase, no_stop, lang, stopwords),
+            None,
+        )
+    })
+}
+
+/// Strings that are not entirely uppercase only have their first word capitalised (and in French,
+/// the word after a leading article). Everything else is left as it was written, which keeps
+/// German nouns and acronyms intact.
+fn transform_sentence_case_lang(
+    s: &str,
+    seen_one: bool,
+    is_last: bool,
+    entire_is_uppercase: bool,
+    lang: CaseLanguage,
+) -> String {
+    let first = Cell::new(!seen_one);
+    let after_article = Cell::new(false);
+    transform_each_word(s, seen_one, is_last, |word, _, _, _| {
+        let word = if entire_is_uppercase {
+            lazy::lazy_lowercase(word)
+        } else {
+            SmartCow::Borrowed(word)
+        };
+        if first.replace(false) {
+            let (elided, rest) = split_elision(&word, lang);
+            let stem = elided.trim_end_matches(|c| c == '\'' || c == '\u{2019}');
+            let mut ret = String::new();
+            if lang == CaseLanguage::French && is_listed(stem, FRENCH_ARTICLES) {
+                // L'Histoire de France
+                ret.push_str(&transform_uppercase_first(elided));
+                ret.push_str(&transform_uppercase_first(rest));
+            } else {
+                after_article.set(lang == CaseLanguage::French && is_listed(rest, FRENCH_ARTICLES));
+                ret.push_str(&transform_uppercase_first(&word));
+            }
+            return (SmartCow::Owned(ret), None);
+        }
+        let capitalise = after_article.replace(false)
+            || (entire_is_uppercase
+                && lang == CaseLanguage::German
+                // Most of what is left once the function words are gone are nouns, which German
+                // always capitalises.
+                && !is_listed(&word, GERMAN_STOPWORDS));
+        if capitalise {
+            (
+                SmartCow::Owned(transform_uppercase_first(&word).into_owned()),
+                None,
+            )
+        } else {
+            (word, None)
+        }
+    })
+}
+
 /// Returns the length of the matched word
 fn is_stopword(word_and_rest: &str) -> Option<usize> {
     stopword_regex().find(word_and_rest).map(|mat| mat.end())
@@ -440,7 +638,7 @@ impl IngestOptions {
         seen_one
     }
     pub fn apply_text_case_micro(&self, micros: &mut [MicroNode]) {
-        if self.text_case == TextCase::None {
+        if self.text_case == TextCase::None || self.text_case == TextCase::Normal {
             return;
         }
         let is_uppercase = self.is_uppercase_micro(micros);
@@ -505,17 +703,49 @@ impl IngestOptions {
             TextCase::Lowercase => lazy::lazy_lowercase_owned(s),
             TextCase::Uppercase => lazy::lazy_uppercase_owned(s),
             TextCase::CapitalizeFirst => transform_first_word(s, transform_uppercase_first),
-            TextCase::Sentence if !seen_one => {
-                transform_sentence_case(s, seen_one, is_last, entire_is_uppercase)
-            }
+            TextCase::Sentence if !seen_one => match self.language {
+                CaseLanguage::German | CaseLanguage::French | CaseLanguage::Spanish => {
+                    transform_sentence_case_lang(
+                        &s,
+                        seen_one,
+                        is_last,
+                        entire_is_uppercase,
+                        self.language,
+                    )
+                }
+                CaseLanguage::English | CaseLanguage::Other => {
+                    transform_sentence_case(s, seen_one, is_last, entire_is_uppercase)
+                }
+            },
             // Fallback is nothing
-            TextCase::Title if self.is_english => transform_title_case(&s, seen_one, is_last),
+            TextCase::Title => match (self.language, &self.skip_words) {
+                (CaseLanguage::Other, _) => s,
+                (lang, Some(skip_words)) => transform_title_case_lang(
+                    &s,
+                    seen_one,
+                    is_last,
+                    entire_is_uppercase,
+                    lang,
+                    &skip_words[..],
+                ),
+                (CaseLanguage::English, None) => transform_title_case(&s, seen_one, is_last),
+                (lang, None) => transform_title_case_lang(
+                    &s,
+                    seen_one,
+                    is_last,
+                    entire_is_uppercase,
+                    lang,
+                    lang.stopwords(),
+                ),
+            },
             TextCase::CapitalizeAll => {
                 transform_each_word(&s, seen_one, is_last, |word, _, _, _| {
                     (transform_uppercase_first(word), None)
                 })
             }
-            TextCase::None | _ => s,
+            // Normal is only different from None inside something else with a text-case. See
+            // Markup::apply_text_case.
+            TextCase::None | TextCase::Normal | _ => s,
         }
     }
     pub fn default_with_quotes(quotes: LocalizedQuotes) -> Self {
@@ -563,3 +793,73 @@ fn test_any_micros() {
     assert_eq!(upper("HELLOSUPERSCRIPT"), true);
     assert_eq!(upper("HELLO, <sup>SUPERSCRIPT</sup>"), true);
 }
+
+#[test]
+fn language_text_case() {
+    use CaseLanguage::*;
+    use TextCase::{Sentence, Title};
+    #[rustfmt::skip]
+    let cases: &[(CaseLanguage, TextCase, &str, &str)] = &[
+        // English keeps the CSL rules
+        (English, Title, "the lord of the rings", "The Lord of the Rings"),
+        (Other, Title, "the lord of the rings", "the lord of the rings"),
+        (Other, Sentence, "the lord of the rings", "The lord of the rings"),
+        // German
+        (German, Title, "der untergang des abendlandes", "Der Untergang des Abendlandes"),
+        (German, Title, "krieg und frieden in der NATO", "Krieg und Frieden in der NATO"),
+        (German, Title, "der ost-west-konflikt", "Der Ost-West-Konflikt"),
+        (German, Sentence, "die Geschichte der Stadt Wien", "Die Geschichte der Stadt Wien"),
+        (German, Sentence, "DIE GESCHICHTE DER STADT WIEN", "Die Geschichte der Stadt Wien"),
+        // French
+        (French, Title, "histoire de l'art en europe", "Histoire de l'Art en Europe"),
+        (French, Title, "l’histoire et les historiens", "L’Histoire et les Historiens"),
+        (French, Title, "aujourd'hui et demain", "Aujourd'hui et Demain"),
+        (French, Sentence, "le monde diplomatique", "Le Monde diplomatique"),
+        (French, Sentence, "LE MONDE DIPLOMATIQUE", "Le Monde diplomatique"),
+        (French, Sentence, "L'HISTOIRE DU CINÉMA", "L'Histoire du cinéma"),
+        (French, Sentence, "histoire de la France", "Histoire de la France"),
+        // Spanish
+        (Spanish, Title, "cien años de soledad", "Cien Años de Soledad"),
+        (Spanish, Title, "el amor en los tiempos del cólera", "El Amor en los Tiempos del Cólera"),
+        (Spanish, Sentence, "¿QUÉ ES LA FILOSOFÍA?", "¿Qué es la filosofía?"),
+        (Spanish, Title, "¿y tú qué sabes?", "¿Y Tú Qué Sabes?"),
+    ];
+    for &(language, text_case, input, expected) in cases {
+        let options = IngestOptions {
+            text_case,
+            language,
+            ..Default::default()
+        };
+        let is_uppercase = !any_lowercase(input);
+        let output = options.transform_case(String::from(input), false, true, is_uppercase);
+        assert_eq!(
+            output.as_str(),
+            expected,
+            "{:?} {:?} of {:?}",
+            language,
+            text_case,
+            input
+        );
+    }
+}
+
+#[test]
+fn language_text_case_nocase() {
+    let options = IngestOptions {
+        text_case: TextCase::Title,
+        language: CaseLanguage::German,
+        ..Default::default()
+    };
+    let mut micros = MicroNode::parse(
+        r#"leben in <span class="nocase">iPhone</span>-zeiten"#,
+        &options,
+    );
+    options.apply_text_case_micro(&mut micros);
+    assert_eq!(
+        micros,
+        MicroNode::parse(
+            r#"Leben in <span class="nocase">iPhone</span>-Zeiten"#,
+            &IngestOptions::default()
+        )
+    );
+}
@@ -26,6 +26,37 @@ pub fn is_latin_cyrillic(s: &str) -> bool {
     s.chars().all(|c| char_is_latin_cyrillic(c))
 }
 
+/// Han, Hiragana, Katakana, Hangul and Bopomofo, including their compatibility and extension
+/// blocks.
+pub fn char_is_cjk(c: char) -> bool {
+    match c {
+        '\u{1100}'..='\u{11FF}'
+        | '\u{2E80}'..='\u{2FDF}'
+        | '\u{3005}'..='\u{3007}'
+        | '\u{3021}'..='\u{3029}'
+        | '\u{3031}'..='\u{3035}'
+        | '\u{3038}'..='\u{303B}'
+        | '\u{3041}'..='\u{30FF}'
+        | '\u{3105}'..='\u{318F}'
+        | '\u{31A0}'..='\u{31BF}'
+        | '\u{31F0}'..='\u{31FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{A960}'..='\u{A97F}'
+        | '\u{AC00}'..='\u{D7FF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FF66}'..='\u{FFDC}'
+        | '\u{1B000}'..='\u{1B16F}'
+        | '\u{20000}'..='\u{3134F}' => true,
+        _ => false,
+    }
+}
+
+/// Whether `s` contains any Chinese, Japanese or Korean characters.
+pub fn is_cjk(s: &str) -> bool {
+    s.chars().any(char_is_cjk)
+}
+
 #[test]
 fn test_is_latin_cyrillic() {
     assert!(is_latin_cyrillic(" @")); // Common only
@@ -43,3 +74,17 @@ fn test_is_latin_cyrillic() {
     assert!(!is_latin_cyrillic("⺙.⺛⻳")); // Han with common
     assert!(!is_latin_cyrillic("휴전 상태를 유지해야 한다")); // Hangeul with common
 }
+
+#[test]
+fn test_is_cjk() {
+    assert!(is_cjk("毛泽东")); // Han
+    assert!(is_cjk("山田たろう")); // Han and Hiragana
+    assert!(is_cjk("カタカナ")); // Katakana
+    assert!(is_cjk("김철수")); // Hangul
+    assert!(is_cjk("ㄅㄆㄇ")); // Bopomofo
+
+    assert!(!is_cjk("Smith"));
+    assert!(!is_cjk("דוד כהן")); // Hebrew
+    assert!(!is_cjk("สมชาย")); // Thai
+    assert!(!is_cjk("देवनागरी")); // Devanagari
+}
@@ -484,6 +484,32 @@ fn test_date_combos() {
     }
 }
 
+#[test]
+fn test_raw_date_languages() {
+    setup();
+    let doc = json!({ "id": 1, "issued": { "raw": "March 3, 2001" }, "accessed": { "raw": "3 mars 2001", "circa": true } });
+    let mut refr: Reference = serde_json::from_value(doc).unwrap();
+    assert_eq!(refr.date.get(&Issued), Some(&DateOrRange::new(2001, 3, 3)));
+    // not English, so kept for later
+    assert_eq!(
+        refr.raw_dates
+            .get(&DateVariable::Accessed)
+            .map(String::as_str),
+        Some("3 mars 2001")
+    );
+    let fr = csl::Locale::from_str(
+        r#"<locale xml:lang="fr-FR"><terms><term name="month-03">mars</term></terms></locale>"#,
+    )
+    .unwrap();
+    let errors = refr.reparse_raw_dates(&RawDateParser::from_locales(std::iter::once(&fr)));
+    assert!(errors.is_empty());
+    assert!(refr.raw_dates.is_empty());
+    assert_eq!(
+        refr.date.get(&DateVariable::Accessed),
+        Some(&DateOrRange::new(2001, 3, 3).with_circa(true))
+    );
+}
+
 #[test]
 #[ignore = "EDTF dates not implemented yet"]
 fn test_edtf_date() {
@@ -270,11 +270,15 @@ where
             Cond::HasMonthOrSeason(dvar) => checker.has_month_or_season(*dvar),
             Cond::HasDay(dvar) => checker.has_day(*dvar),
 
+            Cond::Context(context) => checker.context() == *context,
+            Cond::IsPlural(var) => checker.is_plural(*var),
+            Cond::Page(typ) => checker.page_label() == Some(*typ),
+            Cond::Genre(genre) => checker.genre().map_or(false, |g| {
+                g.to_lowercase().replace('-', " ") == genre.as_str()
+            }),
+
             // Not implemented
-            Cond::Context(_)
-            | Cond::IsPlural(_)
-            | Cond::Jurisdiction(_)
-            | Cond::SubJurisdiction(_) => {
+            Cond::Jurisdiction(_) | Cond::SubJurisdiction(_) => {
                 log::warn!("unimplemented choose condition: {:?}", cond);
                 return None;
             }
@@ -284,8 +288,41 @@ where
     run_matcher(&mut iter_all, &cond_set.match_type)
 }
 
-use csl::Features;
 use csl::LocatorType;
+use csl::{Context, Features, GenderedTermSelector, Locale, TermForm, TextTermSelector};
+
+/// The label at the start of a `page` variable, like the `chap.` in `chap. 5`, for
+/// `page="chapter"`. A page with no label is `page`, like citeproc-js.
+pub(crate) fn page_label(locale: &Locale, page: &str) -> LocatorType {
+    use strum::IntoEnumIterator;
+    let page = page.trim_start();
+    let mut found: Option<(usize, LocatorType)> = None;
+    for loc_type in LocatorType::iter() {
+        for &form in &[TermForm::Long, TermForm::Short, TermForm::Symbol] {
+            for &plural in &[false, true] {
+                let sel = TextTermSelector::Gendered(GenderedTermSelector::Locator(loc_type, form));
+                let term = match locale.get_text_term(sel, plural) {
+                    Some(term) if !term.is_empty() => term,
+                    _ => continue,
+                };
+                // The term has to be followed by the number, not just begin a longer word
+                let rest = match page.get(term.len()..) {
+                    Some(rest) if page.starts_with(term) => rest,
+                    _ => continue,
+                };
+                let followed_by_number = rest
+                    .chars()
+                    .next()
+                    .map_or(false, |c| c.is_whitespace() || c.is_ascii_digit());
+                // Prefer the longest term that matches
+                if followed_by_number && found.map_or(true, |(len, _)| term.len() > len) {
+                    found = Some((term.len(), loc_type));
+                }
+            }
+        }
+    }
+    found.map_or(LocatorType::Page, |(_, loc_type)| loc_type)
+}
 
 pub struct UselessCondChecker;
 impl CondChecker for UselessCondChecker {
@@ -310,6 +347,18 @@ impl CondChecker for UselessCondChecker {
     fn position(&self) -> Option<Position> {
         None
     }
+    fn context(&self) -> Context {
+        Context::Citation
+    }
+    fn is_plural(&self, _var: AnyVariable) -> bool {
+        false
+    }
+    fn page_label(&self) -> Option<LocatorType> {
+        None
+    }
+    fn genre(&self) -> Option<&str> {
+        None
+    }
     fn features(&self) -> &csl::version::Features {
         lazy_static::lazy_static! {
             static ref NO_FEATURES: Features = {
@@ -332,6 +381,12 @@ pub trait CondChecker {
     fn locator_type(&self) -> Option<LocatorType>;
     fn get_date(&self, dvar: DateVariable) -> Option<&DateOrRange>;
     fn position(&self) -> Option<Position>;
+    fn context(&self) -> Context;
+    /// More than one name, or more than one number
+    fn is_plural(&self, var: AnyVariable) -> bool;
+    /// The label at the start of the `page` variable, if there is one. See `page_label`.
+    fn page_label(&self) -> Option<LocatorType>;
+    fn genre(&self) -> Option<&str>;
     fn features(&self) -> &Features;
     fn has_year_only(&self, dvar: DateVariable) -> bool {
         self.get_date(dvar)
@@ -374,3 +429,21 @@ pub trait CondChecker {
             .map_or(false, |dor| dor.is_uncertain_date())
     }
 }
+
+#[test]
+fn test_page_label() {
+    use citeproc_db::LocaleFetcher;
+    use csl::Lang;
+    let locale = citeproc_db::PredefinedLocales::bundled_en_us()
+        .fetch_locale(&Lang::en_us())
+        .unwrap();
+    assert_eq!(page_label(&locale, "chap. 5"), LocatorType::Chapter);
+    assert_eq!(page_label(&locale, "chapter 5"), LocatorType::Chapter);
+    assert_eq!(page_label(&locale, "sections 5-7"), LocatorType::Section);
+    assert_eq!(page_label(&locale, "§§5-7"), LocatorType::Section);
+    assert_eq!(page_label(&locale, "s.v. 12"), LocatorType::SubVerbo);
+    assert_eq!(page_label(&locale, "pp. 5-7"), LocatorType::Page);
+    assert_eq!(page_label(&locale, "5-7"), LocatorType::Page);
+    // a word that only starts with a term
+    assert_eq!(page_label(&locale, "change 5"), LocatorType::Page);
+}
@@ -36,6 +36,14 @@ impl Trigraph {
                 }) {
                     let name_to_write = match &authors[prog] {
                         Name::Literal { literal, .. } => literal,
+                        // the largest subunit, i.e. the "University" in "Department, University"
+                        Name::Institution(institution) => match institution.units.last() {
+                            Some(unit) => &unit.long,
+                            None => {
+                                prog += 1;
+                                continue;
+                            }
+                        },
                         Name::Person(PersonName {
                             family: Some(family),
                             ..
@@ -129,7 +129,8 @@ impl<'a, O: OutputFormat, I: OutputFormat> RenderContext for CiteContext<'a, O,
                 .map(NumericValue::from_localized(and_term)),
             NumberVariable::FirstReferenceNoteNumber => self.position.1.map(NumericValue::num),
             NumberVariable::CitationNumber => self.bib_number.map(NumericValue::num),
-            NumberVariable::PageFirst => get(NumberVariable::Page).and_then(|pp| pp.page_first()),
+            NumberVariable::PageFirst => get(NumberVariable::PageFirst)
+                .or_else(|| get(NumberVariable::Page).and_then(|pp| pp.page_first())),
             _ => get(var),
         }
     }
@@ -177,6 +178,16 @@ pub trait RenderContext {
     }
 }
 
+/// Shared with RefContext, as the `page` variable is always on the reference.
+pub(crate) fn ref_page_label(refr: &Reference, locale: &Locale) -> Option<LocatorType> {
+    refr.number
+        .get(&NumberVariable::Page)
+        .map(|page| match page {
+            NumberLike::Str(s) => crate::choose::page_label(locale, s),
+            NumberLike::Num(_) => LocatorType::Page,
+        })
+}
+
 // Implemented here privately so we don't use it by mistake.
 // It's meant to be used only by CiteContext::has_variable, which wraps it and prevents
 // testing variables that only exist on the Cite.
@@ -254,6 +265,29 @@ where
         // ignore count as that's for references
         self.disamb_pass == Some(DisambPass::Conditionals)
     }
+    fn context(&self) -> Context {
+        if self.in_bibliography {
+            Context::Bibliography
+        } else {
+            Context::Citation
+        }
+    }
+    fn is_plural(&self, var: AnyVariable) -> bool {
+        match var {
+            AnyVariable::Name(nv) => self.get_name(nv).map_or(false, |names| names.len() > 1),
+            AnyVariable::Number(nv) => self.get_number(nv).map_or(false, |n| n.is_multiple(nv)),
+            _ => false,
+        }
+    }
+    fn page_label(&self) -> Option<LocatorType> {
+        ref_page_label(self.reference, self.locale)
+    }
+    fn genre(&self) -> Option<&str> {
+        self.reference
+            .ordinary
+            .get(&Variable::Genre)
+            .map(|s| s.as_str())
+    }
     fn features(&self) -> &Features {
         &self.style.features
     }
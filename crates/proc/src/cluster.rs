@@ -18,13 +18,35 @@ use crate::ir::transforms;
 use crate::prelude::*;
 
 mod layout;
+mod spans;
 use layout::DelimKind;
 pub(crate) use layout::WhichStream;
+pub use spans::{built_cluster_spans, CiteSpan};
 
 pub fn built_cluster_before_output(
     db: &dyn IrDatabase,
     cluster_id: ClusterId,
     fmt: &Markup,
+) -> MarkupBuild {
+    build_cluster(db, cluster_id, fmt, None)
+}
+
+/// Which cites each pair of [layout::CITE_START_MARK] / [layout::CITE_END_MARK] in a marked
+/// cluster belongs to, in the order they appear in the output. A pair in the intext stream
+/// covers every cite in a run with the same names.
+#[derive(Debug, Default)]
+pub(crate) struct MarkedCites {
+    pub marks: Vec<Vec<CiteId>>,
+    /// Cites that were collapsed into a range (e.g. the 2 in `1–3`), with the marks at either end
+    /// of the range.
+    pub collapsed: Vec<(CiteId, usize, usize)>,
+}
+
+pub(crate) fn build_cluster(
+    db: &dyn IrDatabase,
+    cluster_id: ClusterId,
+    fmt: &Markup,
+    mut marked: Option<&mut MarkedCites>,
 ) -> MarkupBuild {
     let cite_ids = if let Some(x) = db.cluster_cites_sorted(cluster_id) {
         x
@@ -32,15 +54,13 @@ pub fn built_cluster_before_output(
         return fmt.plain("");
     };
     let style = db.style();
-    let sorted_refs_arc = db.sorted_refs();
     let mut irs: Vec<_> = cite_ids
         .iter()
         .map(|&id| {
             let gen4 = db.ir_fully_disambiguated(id);
             let position = db.cite_position(id).0;
             let cite = id.lookup(db);
-            let (_keys, citation_numbers_by_id) = &*sorted_refs_arc;
-            let cnum = citation_numbers_by_id.get(&cite.ref_id).cloned();
+            let cnum = db.bib_number(id);
             CiteInCluster::new(id, cite, position, cnum.map(|x| x.get()), gen4, &fmt)
         })
         .collect();
@@ -97,6 +117,10 @@ pub fn built_cluster_before_output(
 
     let mut citation_stream = layout::LayoutStream::new(irs.len() * 2, citation_delims, fmt);
     let mut intext_stream = layout::LayoutStream::new(0, intext_delimiters, fmt);
+    if marked.is_some() {
+        citation_stream.mark_cites();
+        intext_stream.mark_cites();
+    }
 
     // render the intext stream
     let intext_authors = group_by(&irs, |a, b| a.by_name() == b.by_name())
@@ -110,7 +134,21 @@ pub fn built_cluster_before_output(
         //
         //
         //
-        .map(|run| &run[0])
+        .map(|run| {
+            if let Some(marked) = marked.as_mut() {
+                let in_intext = run.iter().filter(|c| match c.destination {
+                    WhichStream::MainToIntext { .. } | WhichStream::MainToCitationPlusIntext(_) => {
+                        true
+                    }
+                    _ => false,
+                });
+                let ids: Vec<CiteId> = in_intext.map(|c| c.cite_id).collect();
+                if !ids.is_empty() {
+                    marked.marks.push(ids);
+                }
+            }
+            &run[0]
+        })
         .filter_map(|cite| match cite.destination {
             WhichStream::MainToIntext { success } => {
                 Some((cite, Some(cite.gen4.tree_ref().node).filter(|_| success)))
@@ -132,13 +170,29 @@ pub fn built_cluster_before_output(
 
     intext_stream.write_interspersed(intext_authors, DelimKind::Layout);
 
+    let intext_marks = marked.as_ref().map_or(0, |m| m.marks.len());
+    let mut in_range = Vec::new();
     for cite in &irs {
         match cite.destination {
-            WhichStream::Nowhere | WhichStream::MainToIntext { .. } => {
+            WhichStream::Nowhere => {
+                in_range.push(cite.cite_id);
+                continue;
+            }
+            WhichStream::MainToIntext { .. } => {
                 continue;
             }
             _ => {
                 citation_stream.write_flat(cite, None);
+                if let Some(marked) = marked.as_mut() {
+                    let ix = marked.marks.len();
+                    for id in in_range.drain(..) {
+                        // the head of a range is always written, so ix > intext_marks here
+                        marked
+                            .collapsed
+                            .push((id, ix.saturating_sub(1).max(intext_marks), ix));
+                    }
+                    marked.marks.push(vec![cite.cite_id]);
+                }
             }
         }
     }
@@ -13,8 +13,15 @@ pub(crate) struct LayoutStream<'a> {
     chunks: Vec<Chunk>,
     delimiters: LayoutDelimiters<'a>,
     fmt: &'a Markup,
+    mark_cites: bool,
 }
 
+/// Wrapped around each cite (and its affixes) when the stream is marked, so the cites can be found
+/// in the output afterwards. These are private use characters, so nothing else in the pipeline
+/// will produce them.
+pub(crate) const CITE_START_MARK: char = '\u{E000}';
+pub(crate) const CITE_END_MARK: char = '\u{E001}';
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum Chunk {
     Cite { built: MarkupBuild },
@@ -50,8 +57,15 @@ impl<'a> LayoutStream<'a> {
             chunks: Vec::with_capacity(cap),
             delimiters,
             fmt,
+            mark_cites: false,
         }
     }
+
+    /// Wrap each cite in [CITE_START_MARK] and [CITE_END_MARK] when finishing.
+    pub(crate) fn mark_cites(&mut self) {
+        self.mark_cites = true;
+    }
+
     pub(crate) fn write_interspersed(
         &mut self,
         iter: impl IntoIterator<Item = MarkupBuild>,
@@ -164,15 +178,44 @@ impl<'a> LayoutStream<'a> {
             is_external: true,
             ..Default::default()
         };
-        let seq = self.chunks.into_iter().filter_map(|x| match x {
-            Chunk::Cite { built, .. } => Some(built),
-            Chunk::Prefix(s) if !s.is_empty() => Some(fmt.ingest(&s, &external)),
-            Chunk::Suffix(s) if !s.is_empty() => Some(fmt.ingest(&s, &external)),
-            Chunk::Delim(d) => delimiters.delim(d).map(|x| fmt.plain(x)),
-            _ => None,
-        });
+        let mark_cites = self.mark_cites;
+        let mark = |c: char| {
+            let mut s = SmartString::new();
+            s.push(c);
+            fmt.plain(&s)
+        };
+        let mut seq = Vec::with_capacity(self.chunks.len());
+        let mut chunks = self.chunks.into_iter().peekable();
+        let mut after_prefix = false;
+        while let Some(chunk) = chunks.next() {
+            // A cite's span runs from the start of its prefix to the end of its suffix.
+            let starts_cite = match chunk {
+                Chunk::Prefix(_) => true,
+                Chunk::Cite { .. } => !after_prefix,
+                _ => false,
+            };
+            let ends_cite = match (&chunk, chunks.peek()) {
+                (Chunk::Suffix(_), _) => true,
+                (Chunk::Cite { .. }, next) => !matches!(next, Some(Chunk::Suffix(_))),
+                _ => false,
+            };
+            after_prefix = matches!(chunk, Chunk::Prefix(_));
+            if mark_cites && starts_cite {
+                seq.push(mark(CITE_START_MARK));
+            }
+            seq.extend(match chunk {
+                Chunk::Cite { built, .. } => Some(built),
+                Chunk::Prefix(s) if !s.is_empty() => Some(fmt.ingest(&s, &external)),
+                Chunk::Suffix(s) if !s.is_empty() => Some(fmt.ingest(&s, &external)),
+                Chunk::Delim(d) => delimiters.delim(d).map(|x| fmt.plain(x)),
+                _ => None,
+            });
+            if mark_cites && ends_cite {
+                seq.push(mark(CITE_END_MARK));
+            }
+        }
         Some(fmt.with_format(
-            fmt.affixed(fmt.seq(seq), delimiters.affixes),
+            fmt.affixed(fmt.seq(seq.into_iter()), delimiters.affixes),
             delimiters.formatting,
         ))
         .filter(|x| !x.is_empty())
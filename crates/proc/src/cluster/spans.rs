@@ -0,0 +1,254 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! Finding which part of a rendered cluster belongs to which cite.
+//!
+//! The cluster is rendered a second time with each cite wrapped in marker characters, and the
+//! markers are located in the output and removed. Punctuation is moved around across cite
+//! boundaries during output (e.g. a comma going inside a closing quote), and the markers can get in
+//! the way of that, so the marked output is aligned with the real output rather than assumed to be
+//! identical to it.
+
+use super::layout::{CITE_END_MARK, CITE_START_MARK};
+use super::MarkedCites;
+use crate::prelude::*;
+use citeproc_db::{CiteData, ClusterId};
+use csl::Atom;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// The part of a rendered cluster that a cite produced, including its prefix and suffix.
+///
+/// A cite can have more than one span, e.g. an author-only cite in a composite cluster. Cites
+/// collapsed into a range (the 2 in `1–3`) get the span of the whole range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CiteSpan {
+    /// The index of the cite in the cluster, as it was inserted (not as it was sorted).
+    pub cite_index: u32,
+    pub ref_id: Atom,
+    /// Byte offsets into the rendered cluster.
+    pub range: Range<usize>,
+}
+
+/// Returns exactly the same output as `db.built_cluster(cluster_id)`, along with the cite spans,
+/// in the order they appear.
+pub fn built_cluster_spans(
+    db: &dyn IrDatabase,
+    cluster_id: ClusterId,
+) -> (Arc<<Markup as OutputFormat>::Output>, Vec<CiteSpan>) {
+    let fmt = db.get_formatter();
+    let output = db.built_cluster(cluster_id);
+    let mut marked = MarkedCites::default();
+    let build = super::build_cluster(db, cluster_id, &fmt, Some(&mut marked));
+    let marked_output = fmt.output(build, crate::db::get_piq(db));
+    let (stripped, ranges) = strip_marks(&fmt, &marked_output);
+    let align = Alignment::new(&stripped, &output);
+    let ranges: Vec<Range<usize>> = ranges
+        .into_iter()
+        .map(|r| align.start(r.start)..align.end(r.end))
+        .collect();
+
+    let mut spans = Vec::new();
+    let mut push = |cite_id: CiteId, range: Range<usize>| {
+        if let CiteData::RealCite { index, cite, .. } = db.lookup_cite(cite_id) {
+            spans.push(CiteSpan {
+                cite_index: index,
+                ref_id: cite.ref_id.clone(),
+                range,
+            });
+        }
+    };
+    for (ids, range) in marked.marks.iter().zip(ranges.iter()) {
+        for &id in ids {
+            push(id, range.clone());
+        }
+    }
+    for &(id, first, last) in &marked.collapsed {
+        if let (Some(first), Some(last)) = (ranges.get(first), ranges.get(last)) {
+            push(id, first.start..last.end);
+        }
+    }
+    spans.sort_by_key(|span| (span.range.start, span.cite_index));
+    (output, spans)
+}
+
+/// Removes the markers, returning the ranges they enclosed in the stripped string.
+fn strip_marks(fmt: &Markup, marked: &str) -> (String, Vec<Range<usize>>) {
+    // Output formats escape the markers (RTF writes `\uN `), so look for them as they were written.
+    let written = |c: char| fmt.output(fmt.plain(c.encode_utf8(&mut [0; 4])), false);
+    let start_mark = written(CITE_START_MARK);
+    let end_mark = written(CITE_END_MARK);
+    let mut stripped = String::with_capacity(marked.len());
+    let mut ranges = Vec::new();
+    let mut open = Vec::new();
+    let mut rest = marked;
+    while !rest.is_empty() {
+        if rest.starts_with(start_mark.as_str()) {
+            open.push(stripped.len());
+            rest = &rest[start_mark.len()..];
+        } else if rest.starts_with(end_mark.as_str()) {
+            if let Some(start) = open.pop() {
+                ranges.push(start..stripped.len());
+            }
+            rest = &rest[end_mark.len()..];
+        } else {
+            let c = rest.chars().next().unwrap();
+            stripped.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    ranges.sort_by_key(|r| r.start);
+    (stripped, ranges)
+}
+
+/// Maps byte offsets in one string to byte offsets in a slightly different one, by matching up
+/// their characters (longest common subsequence).
+struct Alignment {
+    /// Byte offset of each character in `from`, paired with the byte offset of the character it
+    /// matched in `to`. Only matched characters are present.
+    pairs: Vec<(usize, usize, usize)>,
+    to_len: usize,
+}
+
+/// Clusters are short, but don't go quadratic on something huge. Beyond this, unmatched regions
+/// are assumed not to have changed length.
+const MAX_ALIGNMENT_CELLS: usize = 4_000_000;
+
+impl Alignment {
+    fn new(from: &str, to: &str) -> Self {
+        let a: Vec<(usize, char)> = from.char_indices().collect();
+        let b: Vec<(usize, char)> = to.char_indices().collect();
+        let pairs = if from == to || a.len() * b.len() > MAX_ALIGNMENT_CELLS {
+            a.iter()
+                .zip(b.iter())
+                .map(|(&(ai, c), &(bi, _))| (ai, bi, c.len_utf8()))
+                .collect()
+        } else {
+            let (n, m) = (a.len(), b.len());
+            // lcs[i][j] is the LCS length of a[i..] and b[j..]
+            let mut lcs = vec![0u32; (n + 1) * (m + 1)];
+            let ix = |i: usize, j: usize| i * (m + 1) + j;
+            for i in (0..n).rev() {
+                for j in (0..m).rev() {
+                    lcs[ix(i, j)] = if a[i].1 == b[j].1 {
+                        lcs[ix(i + 1, j + 1)] + 1
+                    } else {
+                        lcs[ix(i + 1, j)].max(lcs[ix(i, j + 1)])
+                    };
+                }
+            }
+            let mut pairs = Vec::new();
+            let (mut i, mut j) = (0, 0);
+            while i < n && j < m {
+                if a[i].1 == b[j].1 {
+                    pairs.push((a[i].0, b[j].0, a[i].1.len_utf8()));
+                    i += 1;
+                    j += 1;
+                } else if lcs[ix(i + 1, j)] >= lcs[ix(i, j + 1)] {
+                    i += 1;
+                } else {
+                    j += 1;
+                }
+            }
+            pairs
+        };
+        Alignment {
+            pairs,
+            to_len: to.len(),
+        }
+    }
+
+    /// Where a span starting at `offset` starts: at the first matched character at or after it.
+    fn start(&self, offset: usize) -> usize {
+        let p = self.pairs.partition_point(|&(a, _, _)| a < offset);
+        self.pairs.get(p).map_or(self.to_len, |&(_, b, _)| b)
+    }
+
+    /// Where a span ending at `offset` ends: after the last matched character before it.
+    fn end(&self, offset: usize) -> usize {
+        let p = self.pairs.partition_point(|&(a, _, _)| a < offset);
+        p.checked_sub(1)
+            .and_then(|p| self.pairs.get(p))
+            .map_or(0, |&(_, b, len)| b + len)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strip() {
+        let fmt = Markup::plain();
+        let (stripped, ranges) =
+            strip_marks(&fmt, "(\u{E000}Smith 1990a\u{E001}, \u{E000}b\u{E001})");
+        assert_eq!(stripped, "(Smith 1990a, b)");
+        assert_eq!(ranges, vec![1..12, 14..15]);
+        assert_eq!(&stripped[1..12], "Smith 1990a");
+
+        let rtf = Markup::rtf();
+        let (stripped, ranges) = strip_marks(&rtf, "\\uc0\\u57344 Smith\\uc0\\u57345 ");
+        assert_eq!(stripped, "Smith");
+        assert_eq!(ranges, vec![0..5]);
+    }
+
+    #[test]
+    fn align_moved_punctuation() {
+        // The end marker stopped the comma moving inside the quotes
+        let from = "“Title”, and “Other,”";
+        let to = "“Title,” and “Other,”";
+        let align = Alignment::new(from, to);
+        let first = align.start(0)..align.end(from.find(',').unwrap());
+        assert_eq!(&to[first], "“Title");
+        let second_start = from.find(" and").unwrap() + " and ".len();
+        let second = align.start(second_start)..align.end(from.len());
+        assert_eq!(&to[second], "“Other,”");
+    }
+
+    #[test]
+    fn collapsed_citation_numbers() {
+        use crate::test::MockProcessor;
+        use citeproc_db::{ClusterNumber, IntraNote};
+        use citeproc_io::{Cite, Reference};
+        use csl::CslType;
+
+        let mut proc = MockProcessor::new();
+        proc.set_style_text(
+            r#"<style class="in-text" version="1.0.1">
+                <citation collapse="citation-number">
+                    <layout prefix="[" suffix="]" delimiter=",">
+                        <text variable="citation-number" />
+                    </layout>
+                </citation>
+                <bibliography><layout><text variable="title" /></layout></bibliography>
+            </style>"#,
+        );
+        proc.insert_references(vec![
+            Reference::empty("a".into(), CslType::Book),
+            Reference::empty("b".into(), CslType::Book),
+            Reference::empty("c".into(), CslType::Book),
+        ]);
+        let mut interner = string_interner::StringInterner::<ClusterId>::new();
+        let cluster = interner.get_or_intern("cluster");
+        proc.init_clusters(vec![(
+            cluster,
+            ClusterNumber::Note(IntraNote::Single(1)),
+            vec![Cite::basic("a"), Cite::basic("b"), Cite::basic("c")],
+        )]);
+
+        let (output, spans) = built_cluster_spans(&proc, cluster);
+        assert_eq!(output, proc.built_cluster(cluster));
+        assert_eq!(output.as_str(), "[1–3]");
+        let text = |index: u32| {
+            let span = spans.iter().find(|s| s.cite_index == index).unwrap();
+            &output[span.range.clone()]
+        };
+        assert_eq!(text(0), "1");
+        assert_eq!(text(1), "1–3");
+        assert_eq!(text(2), "3");
+        assert_eq!(spans[1].ref_id, "b");
+    }
+}
@@ -7,14 +7,15 @@
 use crate::prelude::*;
 
 use crate::number::render_ordinal;
+use crate::page_range::truncate_prf;
 use citeproc_io::{lazy, Date, DateOrRange};
 use csl::terms::*;
 use csl::LocaleDate;
 #[cfg(test)]
 use csl::RangeDelimiter;
 use csl::{
-    BodyDate, DatePart, DatePartForm, DateParts, DateVariable, DayForm, IndependentDate, Locale,
-    LocalizedDate, MonthForm, NumberVariable, SortKey, YearForm,
+    BodyDate, DateForm, DatePart, DatePartForm, DateParts, DateVariable, DayForm, IndependentDate,
+    Locale, LocalizedDate, MonthForm, NumberVariable, SortKey, YearForm,
 };
 #[cfg(test)]
 use pretty_assertions::assert_eq;
@@ -342,8 +343,16 @@ where
     I: OutputFormat,
 {
     let locale = ctx.locale();
-    // TODO: handle missing
-    let locale_date: &LocaleDate = locale.dates.get(&local.form).unwrap();
+    let fallback;
+    let locale_date: &LocaleDate = match locale.dates.get(&local.form) {
+        Some(locale_date) => locale_date,
+        None if local.form == DateForm::Imperial => {
+            fallback = imperial_locale_date();
+            &fallback
+        }
+        // TODO: handle missing
+        None => return None,
+    };
     let gen_date = if ctx.sort_key().is_some() {
         GenericDateBits::sorting(locale)
     } else {
@@ -395,6 +404,29 @@ where
     )
 }
 
+/// For `<date form="imperial">` when the locale does not have one, e.g. 平成15年3月2日
+fn imperial_locale_date() -> LocaleDate {
+    let part = |form: DatePartForm, suffix: &str| DatePart {
+        form,
+        affixes: Some(Affixes {
+            prefix: "".into(),
+            suffix: suffix.into(),
+        }),
+        ..Default::default()
+    };
+    LocaleDate {
+        form: DateForm::Imperial,
+        date_parts: vec![
+            part(DatePartForm::Year(YearForm::Imperial), "年"),
+            part(DatePartForm::Month(MonthForm::Numeric, false), "月"),
+            part(DatePartForm::Day(DayForm::Numeric), "日"),
+        ],
+        delimiter: None,
+        text_case: TextCase::None,
+        formatting: None,
+    }
+}
+
 fn intermediate_generic_indep<'c, O, I>(
     indep: &IndependentDate,
     ctx: GenericContext<'c, O, I>,
@@ -463,7 +495,16 @@ fn build_parts<'c, O: OutputFormat, I: OutputFormat>(
                     if sorting || matches {
                         let is_filtered =
                             !matches && ctx.sort_key().map_or(false, |k| k.is_macro());
-                        dp_render_either(var, dp, ctx.clone(), arena, single, false, is_filtered)
+                        dp_render_either(
+                            var,
+                            dp,
+                            ctx.clone(),
+                            arena,
+                            single,
+                            None,
+                            false,
+                            is_filtered,
+                        )
                     } else {
                         None
                     }
@@ -511,12 +552,19 @@ fn build_parts<'c, O: OutputFormat, I: OutputFormat>(
                             builder.push_either(arena, Either::Build(Some(fmt.plain(&delim))))
                         }
                         last_rdel = false;
+                        // The end of a range across years, for year-range-format
+                        let range_start = if date.year == second.year && first.year != second.year {
+                            Some(first)
+                        } else {
+                            None
+                        };
                         if let Some((_form, either)) = dp_render_either(
                             var,
                             part,
                             ctx.clone(),
                             arena,
                             date,
+                            range_start,
                             is_max_diff,
                             false,
                         ) {
@@ -714,6 +762,7 @@ fn dp_render_either<'c, O: OutputFormat, I: OutputFormat>(
     ctx: GenericContext<'c, O, I>,
     arena: &mut IrArena<O>,
     date: &Date,
+    range_start: Option<&Date>,
     is_max_diff: bool,
     is_filtered: bool,
 ) -> Option<(DatePartForm, Either<O>)> {
@@ -722,7 +771,7 @@ fn dp_render_either<'c, O: OutputFormat, I: OutputFormat>(
         let string = dp_render_sort_string(part, date, key, is_filtered);
         return string.map(|s| (part.form, Either::Build(Some(fmt.text_node(s, None)))));
     }
-    let string = dp_render_string(part, &ctx, date);
+    let string = dp_render_string(part, &ctx, date, range_start);
     string
         .map(|s| {
             let mut affixes = part.affixes.clone();
@@ -817,7 +866,9 @@ fn render_year(year: i32, form: YearForm, locale: &Locale) -> SmartString {
     // Only do short form ('07) for four-digit years
     match (form, year > 1000) {
         (YearForm::Short, true) => write!(s, "{:02}", year.abs() % 100).unwrap(),
-        (YearForm::Long, _) | (YearForm::Short, false) => write!(s, "{}", year.abs()).unwrap(),
+        (YearForm::Long, _) | (YearForm::Short, false) | (YearForm::Imperial, _) => {
+            write!(s, "{}", year.abs()).unwrap()
+        }
     }
     if year < 0 {
         let sel = SimpleTermSelector::Misc(MiscTerm::Bc, TermFormExtended::Long);
@@ -839,14 +890,65 @@ fn render_year(year: i32, form: YearForm, locale: &Locale) -> SmartString {
     s
 }
 
+/// Japanese eras, by the day each began (YYYYMMDD) and the year before its first year.
+const IMPERIAL_ERAS: &[(u32, &str, i32)] = &[
+    (2019_05_01, "令和", 2018),
+    (1989_01_08, "平成", 1988),
+    (1926_12_25, "昭和", 1925),
+    (1912_07_30, "大正", 1911),
+    (1868_09_08, "明治", 1867),
+];
+
+/// None for dates before the Meiji era, which are rendered as ordinary years.
+fn render_imperial_year(date: &Date) -> Option<SmartString> {
+    if date.year <= 0 {
+        return None;
+    }
+    // Like citeproc-js, a missing month or day (or a season) counts as the first.
+    let month = if date.month >= 1 && date.month <= 12 {
+        date.month
+    } else {
+        1
+    };
+    let ymd = date.year as u32 * 10000 + month * 100 + date.day.max(1);
+    let (_, era, before_first) = IMPERIAL_ERAS.iter().find(|(start, ..)| ymd >= *start)?;
+    Some(smart_format!("{}{}", era, date.year - before_first))
+}
+
+#[test]
+fn test_imperial_year() {
+    let go = |y, m, d| render_imperial_year(&Date::new(y, m, d));
+    assert_eq!(go(2003, 3, 2).as_deref(), Some("平成15"));
+    assert_eq!(go(1989, 1, 7).as_deref(), Some("昭和64"));
+    assert_eq!(go(1989, 1, 8).as_deref(), Some("平成1"));
+    assert_eq!(go(2019, 5, 1).as_deref(), Some("令和1"));
+    assert_eq!(go(2019, 0, 0).as_deref(), Some("平成31"));
+    assert_eq!(go(1900, 0, 0).as_deref(), Some("明治33"));
+    assert_eq!(go(1850, 0, 0), None);
+}
+
 fn dp_render_string<'c, O: OutputFormat, I: OutputFormat>(
     part: &DatePart,
     ctx: &GenericContext<'c, O, I>,
     date: &Date,
+    range_start: Option<&Date>,
 ) -> Option<SmartString> {
     let locale = ctx.locale();
     match part.form {
-        DatePartForm::Year(form) => Some(render_year(date.year, form, ctx.locale())),
+        DatePartForm::Year(YearForm::Imperial) => render_imperial_year(date)
+            .or_else(|| Some(render_year(date.year, YearForm::Long, locale))),
+        DatePartForm::Year(form) => {
+            let year_range_format = ctx.style().year_range_format;
+            if let (Some(start), Some(yrf), YearForm::Long) = (range_start, year_range_format, form)
+            {
+                // Only four-digit years, so there is no AD or BC to worry about
+                if start.year > 1000 && date.year > start.year {
+                    let cropped = truncate_prf(yrf, start.year as u32, date.year as u32);
+                    return Some(smart_format!("{}", cropped));
+                }
+            }
+            Some(render_year(date.year, form, locale))
+        }
         DatePartForm::Month(form, strip_periods) => match form {
             MonthForm::Numeric => {
                 if date.month == 0 || date.month > 12 {
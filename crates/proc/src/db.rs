@@ -64,6 +64,16 @@ pub trait IrDatabase:
     fn bib_item_gen0(&self, ref_id: Atom) -> Option<Arc<IrGen>>;
     fn bib_item(&self, ref_id: Atom) -> Arc<MarkupOutput>;
     fn get_bibliography_map(&self) -> Arc<FnvHashMap<Atom, Arc<MarkupOutput>>>;
+    /// The width of the widest `display="left-margin"` field in the bibliography, in characters.
+    /// citeproc-js calls this `maxoffset`.
+    fn bibliography_max_offset(&self) -> u32;
+    /// [IrDatabase::bibliography_max_offset] for one bibliography section, as numbered within
+    /// it. None if there is no section with that name.
+    fn section_bibliography_max_offset(&self, name: SmartString) -> Option<u32>;
+    fn section_bibliography_map(
+        &self,
+        name: SmartString,
+    ) -> Option<Arc<FnvHashMap<Atom, Arc<MarkupOutput>>>>;
 
     fn branch_runs(&self) -> Arc<FreeCondSets>;
 
@@ -112,6 +122,12 @@ pub trait IrDatabase:
     #[salsa::input]
     fn bibliography_no_sort(&self) -> bool;
 
+    #[salsa::invoke(crate::sort::section_sorted_refs)]
+    fn section_sorted_refs(
+        &self,
+        name: SmartString,
+    ) -> Option<Arc<(Vec<Atom>, FnvHashMap<Atom, BibNumber>)>>;
+
     #[salsa::invoke(crate::sort::bib_number)]
     fn bib_number(&self, id: CiteId) -> Option<BibNumber>;
 }
@@ -480,7 +496,7 @@ fn edge_stream_matches_ref(db: &dyn IrDatabase, edges: Vec<EdgeData>, ref_id: At
 }
 
 /// Returns the set of Reference IDs that could have produced a cite's IR
-fn refs_accepting_cite(
+pub(crate) fn refs_accepting_cite(
     db: &dyn IrDatabase,
     tree: IrTreeRef,
     cite_id: Option<CiteId>,
@@ -581,7 +597,7 @@ fn make_identical_name_formatter<'a>(
     find_name_block(&ref_ir, &mut nth).cloned()
 }
 
-fn list_all_name_blocks(tree: IrTreeRef) -> Vec<NodeId> {
+pub(crate) fn list_all_name_blocks(tree: IrTreeRef) -> Vec<NodeId> {
     fn list_all_name_blocks_inner(tree: IrTreeRef, vec: &mut Vec<NodeId>) {
         let me = match tree.get_node() {
             Some(x) => x.get(),
@@ -1091,8 +1107,33 @@ impl IrGenCow {
     }
 }
 
+/// The IR after each disambiguation pass the style enables, recorded by `add_given_name_passes`
+/// and `final_disamb_passes` for the explain API.
+pub(crate) type DisambStages = Vec<(Option<DisambPass>, Arc<IrGen>)>;
+
+fn record_stage(stages: &mut Option<&mut DisambStages>, pass: DisambPass, irgen: &IrGenCow) {
+    if let Some(stages) = stages {
+        stages.push((Some(pass), irgen.arc.clone()));
+    }
+}
+
 /// Starts with ir_gen0, and disambiguates through add_names and add_givenname
 fn ir_gen2_add_given_name(db: &dyn IrDatabase, id: CiteId) -> Arc<IrGen> {
+    add_given_name_passes(db, id, db.ir_gen0(id), None)
+}
+
+fn ir_fully_disambiguated(db: &dyn IrDatabase, id: CiteId) -> Arc<IrGen> {
+    // Start with the given names done.
+    final_disamb_passes(db, id, db.ir_gen2_add_given_name(id), None)
+}
+
+/// The add_names and add_givenname passes, starting from `gen0`.
+fn add_given_name_passes(
+    db: &dyn IrDatabase,
+    id: CiteId,
+    gen0: Arc<IrGen>,
+    mut stages: Option<&mut DisambStages>,
+) -> Arc<IrGen> {
     let style;
     let locale;
     let cite;
@@ -1100,15 +1141,28 @@ fn ir_gen2_add_given_name(db: &dyn IrDatabase, id: CiteId) -> Arc<IrGen> {
     let mut ctx;
     preamble!(style, locale, cite, refr, ctx, db, id, None);
 
-    let mut irgen = IrGenCow::new(db.ir_gen0(id));
+    let mut irgen = IrGenCow::new(gen0);
     irgen.update_is_ambiguous(db, &ctx);
-    irgen.disambiguate_add_names(db, &mut ctx);
-    irgen.disambiguate_add_given_name(db, &mut ctx);
+    if ctx.style.citation.disambiguate_add_names {
+        irgen.disambiguate_add_names(db, &mut ctx);
+        record_stage(&mut stages, DisambPass::AddNames, &irgen);
+    }
+    if ctx.style.citation.disambiguate_add_givenname {
+        irgen.disambiguate_add_given_name(db, &mut ctx);
+        let rule = ctx.style.citation.givenname_disambiguation_rule;
+        record_stage(&mut stages, DisambPass::AddGivenName(rule), &irgen);
+    }
     log::debug!("ir_gen2_add_given_name: {}", irgen.deref().tree);
     irgen.into_arc()
 }
 
-fn ir_fully_disambiguated(db: &dyn IrDatabase, id: CiteId) -> Arc<IrGen> {
+/// The add_year_suffix and disambiguate="true" passes, starting from `gen2`.
+fn final_disamb_passes(
+    db: &dyn IrDatabase,
+    id: CiteId,
+    gen2: Arc<IrGen>,
+    mut stages: Option<&mut DisambStages>,
+) -> Arc<IrGen> {
     let style;
     let locale;
     let cite;
@@ -1116,16 +1170,39 @@ fn ir_fully_disambiguated(db: &dyn IrDatabase, id: CiteId) -> Arc<IrGen> {
     let mut ctx;
     preamble!(style, locale, cite, refr, ctx, db, id, None);
 
-    // Start with the given names done.
-    let mut irgen = IrGenCow::new(db.ir_gen2_add_given_name(id));
+    let mut irgen = IrGenCow::new(gen2);
     irgen.disambiguate_add_year_suffix(db, &mut ctx);
+    if let Some(DisambPass::AddYearSuffix(suffix)) = ctx.disamb_pass {
+        record_stage(&mut stages, DisambPass::AddYearSuffix(suffix), &irgen);
+    }
     log::debug!("ir_add_year_suffix: {}", irgen.deref().tree);
-    irgen.disambiguate_conditionals(db, &mut ctx);
+    if !irgen.disambiguation_finished {
+        irgen.disambiguate_conditionals(db, &mut ctx);
+        record_stage(&mut stages, DisambPass::Conditionals, &irgen);
+    }
     log::debug!("ir_fully_disambiguated: {}", irgen.deref().tree);
     irgen.into_arc()
 }
 
-fn get_piq(db: &dyn IrDatabase) -> bool {
+/// Runs the same passes as `ir_fully_disambiguated`, keeping the IR after each one the style
+/// enables. The first entry is `ir_gen0`, and the last is the same as `ir_fully_disambiguated`.
+///
+/// None if the reference is missing, or the cite is a bibliography ghost.
+pub(crate) fn disamb_passes(db: &dyn IrDatabase, id: CiteId) -> Option<DisambStages> {
+    match db.lookup_cite(id) {
+        CiteData::BibliographyGhost { .. } => return None,
+        CiteData::RealCite { cite, .. } => {
+            db.reference(cite.ref_id.clone())?;
+        }
+    }
+    let gen0 = db.ir_gen0(id);
+    let mut stages = vec![(None, gen0.clone())];
+    let gen2 = add_given_name_passes(db, id, gen0, Some(&mut stages));
+    final_disamb_passes(db, id, gen2, Some(&mut stages));
+    Some(stages)
+}
+
+pub(crate) fn get_piq(db: &dyn IrDatabase) -> bool {
     // We pant PIQ to be global in a document, not change within a cluster because one cite
     // decided to use a different language. Use the default locale to get it.
     let default_locale = db.default_locale();
@@ -1466,15 +1543,86 @@ fn first_cite_used_disambiguate_true(db: &dyn IrDatabase, ref_id: Atom) -> bool
 }
 
 fn get_bibliography_map(db: &dyn IrDatabase) -> Arc<FnvHashMap<Atom, Arc<MarkupOutput>>> {
+    let sorted_refs = db.sorted_refs();
+    Arc::new(bibliography_map(db, &sorted_refs.0, |key| {
+        db.bib_item_gen0(key.clone())
+    }))
+}
+
+fn bibliography_max_offset(db: &dyn IrDatabase) -> u32 {
+    let sorted_refs = db.sorted_refs();
+    max_left_margin_width(db, &sorted_refs.0, |key| db.bib_item_gen0(key.clone()))
+}
+
+fn section_bibliography_max_offset(db: &dyn IrDatabase, name: SmartString) -> Option<u32> {
+    let section_refs = db.section_sorted_refs(name)?;
+    let (keys, section_numbers) = &*section_refs;
+    let sorted_refs = db.sorted_refs();
+    Some(max_left_margin_width(db, keys, |key| {
+        section_bib_item_gen0(db, key, section_numbers, &sorted_refs.1)
+    }))
+}
+
+fn max_left_margin_width(
+    db: &dyn IrDatabase,
+    keys: &[Atom],
+    gen0_for: impl Fn(&Atom) -> Option<Arc<IrGen>>,
+) -> u32 {
     let fmt = db.get_formatter();
-    let style = db.style();
+    keys.iter()
+        .filter_map(|key| {
+            let gen0 = gen0_for(key)?;
+            let flat = gen0.tree_ref().flatten(&fmt, None)?;
+            fmt.left_margin_width(&flat)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+fn section_bibliography_map(
+    db: &dyn IrDatabase,
+    name: SmartString,
+) -> Option<Arc<FnvHashMap<Atom, Arc<MarkupOutput>>>> {
+    let section_refs = db.section_sorted_refs(name)?;
+    let (keys, section_numbers) = &*section_refs;
     let sorted_refs = db.sorted_refs();
-    let mut m =
-        FnvHashMap::with_capacity_and_hasher(sorted_refs.0.len(), fnv::FnvBuildHasher::default());
+    let map = bibliography_map(db, keys, |key| {
+        section_bib_item_gen0(db, key, section_numbers, &sorted_refs.1)
+    });
+    Some(Arc::new(map))
+}
+
+/// A bibliography entry as numbered within its section.
+fn section_bib_item_gen0(
+    db: &dyn IrDatabase,
+    key: &Atom,
+    section_numbers: &FnvHashMap<Atom, BibNumber>,
+    global_numbers: &FnvHashMap<Atom, BibNumber>,
+) -> Option<Arc<IrGen>> {
+    let number = section_numbers.get(key).map(BibNumber::get);
+    if number == global_numbers.get(key).map(BibNumber::get) {
+        // Reuse the memoized render when the numbering doesn't differ
+        db.bib_item_gen0(key.clone())
+    } else {
+        let refr_arc = db.reference(key.clone());
+        bib_item_gen0_acontextual(db, key.clone(), refr_arc.as_deref(), number)
+    }
+}
+
+/// Renders the bibliography entries for `keys`, in order, applying subsequent-author-substitute
+/// between consecutive entries.
+fn bibliography_map(
+    db: &dyn IrDatabase,
+    keys: &[Atom],
+    gen0_for: impl Fn(&Atom) -> Option<Arc<IrGen>>,
+) -> FnvHashMap<Atom, Arc<MarkupOutput>> {
+    let fmt = db.get_formatter();
+    let style = db.style();
+    let mut m = FnvHashMap::with_capacity_and_hasher(keys.len(), fnv::FnvBuildHasher::default());
     let mut prev: Option<(NodeId, Arc<IrGen>)> = None;
-    for key in sorted_refs.0.iter() {
+    for key in keys.iter() {
         // TODO: put Nones in there so they can be updated
-        if let Some(mut gen0) = db.bib_item_gen0(key.clone()) {
+        if let Some(mut gen0) = gen0_for(key) {
             // in a bibliography, we do the affixes etc inside Layout, so they're not here
             let current = gen0.tree_ref().first_names_block();
             let sas = style.bibliography.as_ref().and_then(|bib| {
@@ -1514,7 +1662,7 @@ fn get_bibliography_map(db: &dyn IrDatabase) -> Arc<FnvHashMap<Atom, Arc<MarkupO
             prev = current.map(|cur| (cur, gen0));
         }
     }
-    Arc::new(m)
+    m
 }
 
 // See https://github.com/jgm/pandoc-citeproc/blob/e36c73ac45c54dec381920e92b199787601713d1/src/Text/CSL/Reference.hs#L910
@@ -0,0 +1,213 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! Problems with a style that rendering works around rather than panicking on. The renderer
+//! itself has nowhere to put them (salsa queries have to be pure), so they are found by walking
+//! the style separately.
+
+use crate::prelude::*;
+use crate::walker::{StyleWalker, WalkerFoldType};
+use csl::*;
+use fnv::FnvHashSet;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StyleProblemKind {
+    /// `<text macro="...">` or `<key macro="...">` naming a macro that doesn't exist. These
+    /// render as nothing.
+    UndefinedMacro(SmartString),
+    /// A macro that calls itself, directly or indirectly. The inner call renders as nothing.
+    RecursiveMacro(SmartString),
+    /// CSL-M `match="nand"`, which disambiguation treats as possibly true and possibly false.
+    UnsupportedMatchNand,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StyleProblem {
+    pub kind: StyleProblemKind,
+    /// `citation`, `bibliography`, or `macro "name"`: the innermost of these that contains the
+    /// problem.
+    pub location: SmartString,
+}
+
+impl StyleProblem {
+    pub fn message(&self) -> String {
+        match &self.kind {
+            StyleProblemKind::UndefinedMacro(name) => format!("macro `{}` is not defined", name),
+            StyleProblemKind::RecursiveMacro(name) => {
+                format!("macro `{}` is called from within itself", name)
+            }
+            StyleProblemKind::UnsupportedMatchNand => {
+                "match=\"nand\" is not supported for disambiguation".into()
+            }
+        }
+    }
+}
+
+/// Every problem in the style, each reported once, in the order they appear.
+pub fn style_problems(style: &Style) -> Vec<StyleProblem> {
+    let mut walker = ProblemWalker {
+        style,
+        location: "citation".into(),
+        macro_stack: Vec::new(),
+        visited_macros: FnvHashSet::default(),
+        problems: Vec::new(),
+    };
+    walker.walk_citation(style);
+    walker.sort(style.citation.sort.as_ref());
+    if let Some(bib) = style.bibliography.as_ref() {
+        walker.location = "bibliography".into();
+        walker.bibliography(bib);
+        walker.sort(bib.sort.as_ref());
+    }
+    walker.problems
+}
+
+struct ProblemWalker<'a> {
+    style: &'a Style,
+    location: SmartString,
+    macro_stack: Vec<SmartString>,
+    visited_macros: FnvHashSet<SmartString>,
+    problems: Vec<StyleProblem>,
+}
+
+impl ProblemWalker<'_> {
+    fn report(&mut self, kind: StyleProblemKind) {
+        let problem = StyleProblem {
+            kind,
+            location: self.location.clone(),
+        };
+        if !self.problems.contains(&problem) {
+            self.problems.push(problem);
+        }
+    }
+
+    fn sort(&mut self, sort: Option<&Sort>) {
+        for key in sort.iter().flat_map(|s| s.keys.iter()) {
+            if let SortSource::Macro(name) = &key.sort_source {
+                self.walk_macro(name);
+            }
+        }
+    }
+
+    fn walk_macro(&mut self, name: &SmartString) {
+        if self.macro_stack.contains(name) {
+            return self.report(StyleProblemKind::RecursiveMacro(name.clone()));
+        }
+        let style = self.style;
+        let elements = match style.macros.get(name) {
+            Some(els) => els,
+            None => return self.report(StyleProblemKind::UndefinedMacro(name.clone())),
+        };
+        // Recursion is only found by walking each macro from the top, so everything reachable
+        // from a macro is walked before it is marked visited.
+        if self.visited_macros.contains(name) {
+            return;
+        }
+        let outer = std::mem::replace(&mut self.location, smart_format!("macro \"{}\"", name));
+        self.macro_stack.push(name.clone());
+        for el in elements {
+            self.element(el);
+        }
+        self.macro_stack.pop();
+        self.location = outer;
+        self.visited_macros.insert(name.clone());
+    }
+
+    fn conditions(&mut self, conditions: &Conditions) {
+        let Conditions(match_type, sets) = conditions;
+        if *match_type == Match::Nand {
+            self.report(StyleProblemKind::UnsupportedMatchNand);
+        }
+        for set in sets {
+            if set.match_type == Match::Nand {
+                self.report(StyleProblemKind::UnsupportedMatchNand);
+            }
+        }
+    }
+}
+
+impl StyleWalker for ProblemWalker<'_> {
+    type Output = ();
+    type Checker = crate::choose::UselessCondChecker;
+
+    fn default(&mut self) -> Self::Output {}
+
+    /// Every branch is walked, not just the ones that would be taken.
+    fn choose(&mut self, choose: &Choose) -> Self::Output {
+        let Choose(head, rest, last) = choose;
+        for IfThen(conditions, elements) in std::iter::once(head).chain(rest.iter()) {
+            self.conditions(conditions);
+            self.fold(elements, WalkerFoldType::IfThen);
+        }
+        self.fold(&last.0, WalkerFoldType::Else);
+    }
+
+    fn text_macro(&mut self, _text: &TextElement, name: &SmartString) -> Self::Output {
+        self.walk_macro(name);
+    }
+
+    fn names(&mut self, names: &Names) -> Self::Output {
+        if let Some(Substitute(elements)) = names.substitute.as_ref() {
+            self.fold(elements, WalkerFoldType::Substitute);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn problems(macros: &str, layout: &str) -> Vec<StyleProblem> {
+        let xml = format!(
+            r#"<style class="note" version="1.0.1">
+                <features><feature name="legal-locators" /></features>
+                {}
+                <citation><layout>{}</layout></citation>
+            </style>"#,
+            macros, layout
+        );
+        let style = Style::parse_for_test(&xml, None).unwrap();
+        style_problems(&style)
+    }
+
+    #[test]
+    fn recursive_macros() {
+        let found = problems(
+            r#"<macro name="a"><text macro="b" /></macro>
+               <macro name="b"><group><text macro="a" /></group></macro>"#,
+            r#"<text macro="a" />"#,
+        );
+        assert_eq!(
+            found,
+            vec![StyleProblem {
+                kind: StyleProblemKind::RecursiveMacro("a".into()),
+                location: "macro \"b\"".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn locator_types() {
+        let found = problems(
+            "",
+            r#"<choose>
+                 <if locator="page"><text value="p" /></if>
+                 <else-if locator="article"><text value="art" /></else-if>
+               </choose>"#,
+        );
+        // CSL-M locator types can be disambiguated like any other
+        assert_eq!(found, vec![]);
+    }
+
+    #[test]
+    fn no_problems() {
+        let found = problems(
+            r#"<macro name="a"><text variable="title" /></macro>"#,
+            r#"<text macro="a" /><text macro="a" />"#,
+        );
+        assert_eq!(found, vec![]);
+    }
+}
@@ -13,7 +13,7 @@ use fnv::FnvHashSet;
 
 bitflags::bitflags! {
     /// A convenient enum of the only conds that can actually change between cites
-    pub struct FreeCond: u64 {
+    pub struct FreeCond: u128 {
         const YEAR_SUFFIX        = 1;
         const YEAR_SUFFIX_FALSE   = 1 << 1;
 
@@ -59,44 +59,52 @@ bitflags::bitflags! {
         const LT_PART_FALSE = 1 << 39;
         const LT_SECTION     = 1 << 40;
         const LT_SECTION_FALSE = 1 << 41;
-        const LT_SUBVERBO   = 1 << 41;
+        const LT_SUBVERBO   = 1 << 42;
         const LT_SUBVERBO_FALSE = 1 << 43;
         const LT_VERSE  = 1 << 44;
         const LT_VERSE_FALSE = 1 << 45;
         const LT_VOLUME     = 1 << 46;
         const LT_VOLUME_FALSE = 1 << 47;
 
-        const DISAMBIGUATE = 1 << 48;
-        const DISAMBIGUATE_FALSE = 1 << 49;
-
-        // TODO(CSL-M): enable these
-
-        // const LT_ARTICLE    = 1 << 48;
-        // const LT_ARTICLE_FALSE1 << 49;
-        // const LT_SUBPARAGRAPH   = 1 << 50;
-        // const LT_SUBPARAGRAPH_FALSE << 51;
-        // const LT_RULE   = 1 << 52;
-        // const LT_RULE_FALSE << 53;
-        // const LT_SUBSECTION     = 1 << 54;
-        // const LT_SUBSECTION_FALSE 1 << 55;
-        // const LT_SCHEDULE   = 1 << 56;
-        // const LT_SCHEDULE_FALSE << 57;
-        // const LT_TITLE  = 1 << 58;
-        // const LT_TITLE_FALSE<< 59;
-        // const LT_SUPPLEMENT     = 1 << 60;
-        // const LT_SUPPLEMENT_FALSE 1 << 61;
+        // CSL-M locator types
+        const LT_ARTICLE    = 1 << 48;
+        const LT_ARTICLE_FALSE = 1 << 49;
+        const LT_SUBPARAGRAPH   = 1 << 50;
+        const LT_SUBPARAGRAPH_FALSE = 1 << 51;
+        const LT_RULE   = 1 << 52;
+        const LT_RULE_FALSE = 1 << 53;
+        const LT_SUBSECTION     = 1 << 54;
+        const LT_SUBSECTION_FALSE = 1 << 55;
+        const LT_SCHEDULE   = 1 << 56;
+        const LT_SCHEDULE_FALSE = 1 << 57;
+        const LT_TITLE  = 1 << 58;
+        const LT_TITLE_FALSE = 1 << 59;
+        const LT_SUPPLEMENT     = 1 << 60;
+        const LT_SUPPLEMENT_FALSE = 1 << 61;
+        const LT_UNPUBLISHED    = 1 << 62;
+        const LT_UNPUBLISHED_FALSE = 1 << 63;
+
+        const DISAMBIGUATE = 1 << 64;
+        const DISAMBIGUATE_FALSE = 1 << 65;
+
+        // CSL-M is-plural="locator"
+        const LOCATOR_PLURAL = 1 << 66;
+        const LOCATOR_PLURAL_FALSE = 1 << 67;
 
         // No disambiguate, because you can't use this to do any more disambiguation, so unhelpful.
     }
 }
 
+// The locator types are bits 16..64, after which come the other conds again.
 #[allow(dead_code)]
-const LT_MASK: FreeCond = FreeCond::from_bits_truncate(std::u64::MAX << 16);
-const LT_MASK_TRUE: FreeCond = FreeCond::from_bits_truncate(0x5555_5555_5555_5555 << 16);
-const LT_MASK_FALSE: FreeCond = FreeCond::from_bits_truncate(0xAAAA_AAAA_AAAA_AAAA << 16);
+const LT_MASK: FreeCond = FreeCond::from_bits_truncate(0xFFFF_FFFF_FFFF_0000);
+const LT_MASK_TRUE: FreeCond = FreeCond::from_bits_truncate(0x5555_5555_5555_0000);
+const LT_MASK_FALSE: FreeCond = FreeCond::from_bits_truncate(0xAAAA_AAAA_AAAA_0000);
 
-const FC_MASK_TRUE: FreeCond = FreeCond::from_bits_truncate(0x5555_5555_5555_5555);
-const FC_MASK_FALSE: FreeCond = FreeCond::from_bits_truncate(0xAAAA_AAAA_AAAA_AAAA);
+const FC_MASK_TRUE: FreeCond =
+    FreeCond::from_bits_truncate(0x5555_5555_5555_5555_5555_5555_5555_5555);
+const FC_MASK_FALSE: FreeCond =
+    FreeCond::from_bits_truncate(0xAAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA_AAAA);
 
 #[test]
 fn test_lt_mask() {
@@ -114,6 +122,12 @@ fn test_lt_mask() {
 
     assert!(FC_MASK_TRUE.contains(FreeCond::LOCATOR));
     assert!(FC_MASK_FALSE.contains(FreeCond::LOCATOR_FALSE));
+
+    assert!(LT_MASK_TRUE.contains(FreeCond::LT_UNPUBLISHED));
+    assert!(LT_MASK_FALSE.contains(FreeCond::LT_UNPUBLISHED_FALSE));
+    assert!(!LT_MASK.intersects(FreeCond::DISAMBIGUATE | FreeCond::LOCATOR_PLURAL));
+    assert!(FC_MASK_TRUE.contains(FreeCond::LOCATOR_PLURAL | FreeCond::DISAMBIGUATE));
+    assert!(FC_MASK_FALSE.contains(FreeCond::LOCATOR_PLURAL_FALSE));
 }
 
 #[test]
@@ -123,6 +137,10 @@ fn test_invert() {
         (FreeCond::LT_VOLUME | FreeCond::LT_PAGE).invert()
             == (FreeCond::LT_VOLUME_FALSE | FreeCond::LT_PAGE_FALSE)
     );
+    assert_eq!(
+        (FreeCond::LT_SUPPLEMENT | FreeCond::LOCATOR_PLURAL_FALSE).invert(),
+        FreeCond::LT_SUPPLEMENT_FALSE | FreeCond::LOCATOR_PLURAL
+    );
 }
 
 macro_rules! match_fc {
@@ -138,7 +156,7 @@ macro_rules! match_fc {
             match ($pos) {
                 LT_BOOK       => LocatorType::Book,
                 LT_CHAPTER    => LocatorType::Chapter,
-                LT_COLUMN     => LocatorType::Chapter,
+                LT_COLUMN     => LocatorType::Column,
                 LT_FIGURE     => LocatorType::Figure,
                 LT_FOLIO      => LocatorType::Folio,
                 LT_ISSUE      => LocatorType::Issue,
@@ -152,6 +170,14 @@ macro_rules! match_fc {
                 LT_SUBVERBO   => LocatorType::SubVerbo,
                 LT_VERSE      => LocatorType::Verse,
                 LT_VOLUME     => LocatorType::Volume,
+                LT_ARTICLE    => LocatorType::Article,
+                LT_SUBPARAGRAPH => LocatorType::Subparagraph,
+                LT_RULE       => LocatorType::Rule,
+                LT_SUBSECTION => LocatorType::Subsection,
+                LT_SCHEDULE   => LocatorType::Schedule,
+                LT_TITLE      => LocatorType::Title,
+                LT_SUPPLEMENT => LocatorType::Supplement,
+                LT_UNPUBLISHED => LocatorType::Unpublished,
             }
         }
     };
@@ -169,6 +195,12 @@ fn test_free_to_loc_type() {
     assert_eq!(x.to_loc_type(), Some(LocatorType::Page));
     let x = FreeCond::IBID | FreeCond::IBID_WITH_LOCATOR_FALSE;
     assert_eq!(x.to_loc_type(), None);
+    let x = FreeCond::LT_COLUMN;
+    assert_eq!(x.to_loc_type(), Some(LocatorType::Column));
+    let x = FreeCond::LOCATOR | FreeCond::LT_SUBSECTION;
+    assert_eq!(x.to_loc_type(), Some(LocatorType::Subsection));
+    let x = FreeCond::LT_SUBVERBO | FreeCond::LT_SECTION_FALSE;
+    assert_eq!(x.to_loc_type(), Some(LocatorType::SubVerbo));
 }
 
 impl FreeCond {
@@ -318,34 +350,45 @@ fn cond_to_frees(c: &Cond) -> Option<(FreeCond, FreeCond)> {
                 _ => return None,
             }
         }
-        Cond::Locator(lt) => match lt {
-            LocatorType::Book => (FreeCond::LT_BOOK, FreeCond::LT_BOOK_FALSE),
-            LocatorType::Chapter => (FreeCond::LT_CHAPTER, FreeCond::LT_CHAPTER_FALSE),
-            LocatorType::Column => (FreeCond::LT_COLUMN, FreeCond::LT_COLUMN_FALSE),
-            LocatorType::Figure => (FreeCond::LT_FIGURE, FreeCond::LT_FIGURE_FALSE),
-            LocatorType::Folio => (FreeCond::LT_FOLIO, FreeCond::LT_FOLIO_FALSE),
-            LocatorType::Issue => (FreeCond::LT_ISSUE, FreeCond::LT_ISSUE_FALSE),
-            LocatorType::Line => (FreeCond::LT_LINE, FreeCond::LT_LINE_FALSE),
-            LocatorType::Note => (FreeCond::LT_NOTE, FreeCond::LT_NOTE_FALSE),
-            LocatorType::Opus => (FreeCond::LT_OPUS, FreeCond::LT_OPUS_FALSE),
-            LocatorType::Page => (FreeCond::LT_PAGE, FreeCond::LT_PAGE_FALSE),
-            LocatorType::Paragraph => (FreeCond::LT_PARAGRAPH, FreeCond::LT_PARAGRAPH_FALSE),
-            LocatorType::Part => (FreeCond::LT_PART, FreeCond::LT_PART_FALSE),
-            LocatorType::Section => (FreeCond::LT_SECTION, FreeCond::LT_SECTION_FALSE),
-            LocatorType::SubVerbo => (FreeCond::LT_SUBVERBO, FreeCond::LT_SUBVERBO_FALSE),
-            LocatorType::Verse => (FreeCond::LT_VERSE, FreeCond::LT_VERSE_FALSE),
-            LocatorType::Volume => (FreeCond::LT_VOLUME, FreeCond::LT_VOLUME_FALSE),
-            _ => unimplemented!("CSL-M locator types")
-
-            // TODO(CSL-M) enable
-            // Article => (FreeCond::LT_LINE, FreeCond::LT_LINE_FALSE),
-            // Subparagraph => (FreeCond::LT_LINE, FreeCond::LT_LINE_FALSE),
-            // Rule => (FreeCond::LT_LINE, FreeCond::LT_LINE_FALSE),
-            // Subsection => (FreeCond::LT_LINE, FreeCond::LT_LINE_FALSE),
-            // Schedule => (FreeCond::LT_LINE, FreeCond::LT_LINE_FALSE),
-            // Title => (FreeCond::LT_LINE, FreeCond::LT_LINE_FALSE),
-            // Supplement => (FreeCond::LT_LINE, FreeCond::LT_LINE_FALSE),
-        },
+        // A plural locator is still a locator
+        Cond::IsPlural(AnyVariable::Number(NumberVariable::Locator)) => (
+            FreeCond::LOCATOR_PLURAL | FreeCond::LOCATOR,
+            FreeCond::LOCATOR_PLURAL_FALSE,
+        ),
+        Cond::Locator(lt) => return free_locator_type(*lt),
+        _ => return None,
+    };
+    Some(x)
+}
+
+fn free_locator_type(lt: LocatorType) -> Option<(FreeCond, FreeCond)> {
+    let x = match lt {
+        LocatorType::Book => (FreeCond::LT_BOOK, FreeCond::LT_BOOK_FALSE),
+        LocatorType::Chapter => (FreeCond::LT_CHAPTER, FreeCond::LT_CHAPTER_FALSE),
+        LocatorType::Column => (FreeCond::LT_COLUMN, FreeCond::LT_COLUMN_FALSE),
+        LocatorType::Figure => (FreeCond::LT_FIGURE, FreeCond::LT_FIGURE_FALSE),
+        LocatorType::Folio => (FreeCond::LT_FOLIO, FreeCond::LT_FOLIO_FALSE),
+        LocatorType::Issue => (FreeCond::LT_ISSUE, FreeCond::LT_ISSUE_FALSE),
+        LocatorType::Line => (FreeCond::LT_LINE, FreeCond::LT_LINE_FALSE),
+        LocatorType::Note => (FreeCond::LT_NOTE, FreeCond::LT_NOTE_FALSE),
+        LocatorType::Opus => (FreeCond::LT_OPUS, FreeCond::LT_OPUS_FALSE),
+        LocatorType::Page => (FreeCond::LT_PAGE, FreeCond::LT_PAGE_FALSE),
+        LocatorType::Paragraph => (FreeCond::LT_PARAGRAPH, FreeCond::LT_PARAGRAPH_FALSE),
+        LocatorType::Part => (FreeCond::LT_PART, FreeCond::LT_PART_FALSE),
+        LocatorType::Section => (FreeCond::LT_SECTION, FreeCond::LT_SECTION_FALSE),
+        LocatorType::SubVerbo => (FreeCond::LT_SUBVERBO, FreeCond::LT_SUBVERBO_FALSE),
+        LocatorType::Verse => (FreeCond::LT_VERSE, FreeCond::LT_VERSE_FALSE),
+        LocatorType::Volume => (FreeCond::LT_VOLUME, FreeCond::LT_VOLUME_FALSE),
+        LocatorType::Article => (FreeCond::LT_ARTICLE, FreeCond::LT_ARTICLE_FALSE),
+        LocatorType::Subparagraph => (FreeCond::LT_SUBPARAGRAPH, FreeCond::LT_SUBPARAGRAPH_FALSE),
+        LocatorType::Rule => (FreeCond::LT_RULE, FreeCond::LT_RULE_FALSE),
+        LocatorType::Subsection => (FreeCond::LT_SUBSECTION, FreeCond::LT_SUBSECTION_FALSE),
+        LocatorType::Schedule => (FreeCond::LT_SCHEDULE, FreeCond::LT_SCHEDULE_FALSE),
+        LocatorType::Title => (FreeCond::LT_TITLE, FreeCond::LT_TITLE_FALSE),
+        LocatorType::Supplement => (FreeCond::LT_SUPPLEMENT, FreeCond::LT_SUPPLEMENT_FALSE),
+        LocatorType::Unpublished => (FreeCond::LT_UNPUBLISHED, FreeCond::LT_UNPUBLISHED_FALSE),
+        // Any locator types added later are evaluated like any other condition until they get
+        // their own FreeConds, so disambiguation can't tell cites apart by them.
         _ => return None,
     };
     Some(x)
@@ -534,7 +577,9 @@ fn condset_to_frees(c: &CondSet, inner: FreeCondSets) -> (FreeCondSets, FreeCond
             none.insert_validated(all_false);
             (any, none.at_least_1())
         }
-        Match::Nand => unimplemented!(),
+        // Not supported yet (reported through crate::diagnostics). Assume either outcome is
+        // possible regardless of the free variables.
+        Match::Nand => (inner.clone(), inner),
         // // Completely Untested
         // Match::Nand => {
         //     // _exactly one_ of them is true
@@ -779,3 +824,22 @@ fn free_all_branches_match_any() {
     result.insert(FreeCond::LOCATOR_FALSE | FreeCond::LT_PAGE_FALSE);
     assert_eq!(all.0, result);
 }
+
+#[test]
+fn free_all_branches_locator_plural() {
+    let mut if_branch_conds = FnvHashSet::default();
+    if_branch_conds.insert(Cond::IsPlural(AnyVariable::Number(NumberVariable::Locator)));
+    let if_branch = CondSet {
+        match_type: Match::Any,
+        conds: if_branch_conds,
+    };
+    let cs = vec![(&if_branch, FreeCondSets::mult_identity())];
+    let all = FreeCondSets::all_branches(cs.into_iter(), None);
+    let mut result = FnvHashSet::default();
+    result.insert(FreeCond::LOCATOR_PLURAL | FreeCond::LOCATOR);
+    result.insert(FreeCond::LOCATOR_PLURAL_FALSE);
+    assert_eq!(all.0, result);
+    assert!(
+        (FreeCond::LOCATOR_PLURAL | FreeCond::LOCATOR | FreeCond::LOCATOR_FALSE).is_incompatible()
+    );
+}
@@ -45,6 +45,7 @@ impl Disambiguation<Markup> for Group {
             Some(&|| RefIrSeq {
                 delimiter: self.delimiter.clone(),
                 affixes: self.affixes.clone(),
+                text_case: self.text_case,
                 ..Default::default()
             }),
         )
@@ -195,12 +196,10 @@ impl Disambiguation<Markup> for Element {
                     (RefIR::Edge(content), gv)
                 }
                 TextSource::Macro(ref name) => {
-                    let macro_elements = ctx
-                        .style
-                        .macros
-                        .get(name)
-                        .expect("undefined macro should not be valid CSL");
-                    state.push_macro(name);
+                    let macro_elements = match ctx.style.macros.get(name) {
+                        Some(els) if state.push_macro(name) => els,
+                        _ => return (RefIR::Edge(None), GroupVars::Missing),
+                    };
                     let (seq, group_vars) = ref_sequence(
                         db,
                         state,
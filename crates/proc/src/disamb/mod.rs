@@ -126,14 +126,11 @@ impl<'a> StyleWalker for FreeCondWalker<'a> {
     }
 
     fn text_macro(&mut self, text: &TextElement, name: &SmartString) -> Self::Output {
-        // TODO: same todos as in Proc
         let style = self.db.style();
-        let macro_elements = style
-            .macros
-            .get(name)
-            .expect("undefined macro should not be valid CSL");
-
-        self.state.push_macro(name);
+        let macro_elements = match style.macros.get(name) {
+            Some(els) if self.state.push_macro(name) => els,
+            _ => return self.default(),
+        };
         let ret = self.fold(macro_elements, WalkerFoldType::Macro(text));
         self.state.pop_macro(name);
         ret
@@ -6,8 +6,8 @@ use crate::prelude::*;
 use citeproc_io::{Name, PersonName};
 use csl::variables::*;
 use csl::{
-    Atom, DemoteNonDroppingParticle, GivenNameDisambiguationRule as GNDR, Name as NameEl, NameForm,
-    Names, Position, Style,
+    Atom, DemoteNonDroppingParticle, GivenNameDisambiguationRule as GNDR, Lang, Name as NameEl,
+    NameForm, Names, Position, Style,
 };
 use fnv::FnvHashMap;
 use petgraph::graph::NodeIndex;
@@ -27,7 +27,6 @@ impl Disambiguation<Markup> for Names {
             .map_or(stack, |mine| stack.override_with(mine));
         let fmt = ctx.format;
         let style = ctx.style;
-        let locale = ctx.locale;
         let names_inheritance =
             state
                 .name_override
@@ -36,14 +35,13 @@ impl Disambiguation<Markup> for Names {
         // TODO: resolve which parts of name_el's Formatting are irrelevant due to 'stack'
         // and get a reduced formatting to work with
 
-        let and_term = locale.and_term(None).map(SmartString::from);
-        let etal_term = locale.et_al_term(names_inheritance.et_al.as_ref());
         let mut runner = OneNameVar {
             name_el: &names_inheritance.name,
             bump_name_count: 0,
             fmt,
             demote_non_dropping_particle: style.demote_non_dropping_particle,
             initialize_with_hyphen: style.initialize_with_hyphen,
+            lang: ctx.reference.language.as_ref(),
         };
 
         let mut seq = RefIrSeq {
@@ -80,11 +78,9 @@ impl Disambiguation<Markup> for Names {
                     ctx.position,
                     &self.et_al,
                     false,
-                    and_term.as_ref(),
-                    etal_term
-                        .as_ref()
-                        .map(|(a, b)| (a.as_str().into(), b.clone()))
-                        .as_ref(),
+                    // these come from the reference's own locale for CJK names, see NamePolicy
+                    nir.and_term.as_ref(),
+                    nir.etal_term.as_ref(),
                 );
                 counted_tokens = ntb_len;
                 if counted_tokens <= max_counted_tokens {
@@ -136,9 +132,9 @@ impl Disambiguation<Markup> for Names {
                                     }
                                 }
                                 NameTokenBuilt::Ratchet(index) => match &nir.disamb_names[index] {
-                                    DisambNameRatchet::Literal {
-                                        literal,
-                                        is_latin_cyrillic: _,
+                                    DisambNameRatchet::Literal { literal, .. }
+                                    | DisambNameRatchet::Institution {
+                                        rendered: literal, ..
                                     } => {
                                         if !fmt.is_empty(literal) {
                                             let out = fmt.output_in_context(
@@ -301,12 +297,16 @@ impl DisambNameData {
     pub(crate) fn single_name_edge(&self, db: &dyn IrDatabase, stack: Formatting) -> EdgeData {
         let fmt = &db.get_formatter();
         let style = db.style();
+        let lang = db
+            .reference(self.ref_id.clone())
+            .and_then(|refr| refr.language.clone());
         let builder = OneNameVar {
             fmt,
             name_el: &self.el,
             bump_name_count: 0,
             demote_non_dropping_particle: style.demote_non_dropping_particle,
             initialize_with_hyphen: style.initialize_with_hyphen,
+            lang: lang.as_ref(),
         };
         let built = builder.render_person_name(&self.value, !self.primary);
         let o = fmt.output_in_context(built, stack, None);
@@ -663,7 +663,16 @@ impl RefNameIR {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DisambNameRatchet<B> {
-    Literal { literal: B, is_latin_cyrillic: bool },
+    Literal {
+        literal: B,
+        is_latin_cyrillic: bool,
+    },
+    /// A CSL-M institution, already rendered according to `<institution>`. It has no given names
+    /// or initials to expand, so disambiguation leaves it alone.
+    Institution {
+        rendered: B,
+        is_latin_cyrillic: bool,
+    },
     Person(PersonDisambNameRatchet),
 }
 
@@ -719,6 +728,8 @@ pub struct NameIR<O: OutputFormat> {
     pub initialize_with_hyphen: bool,
     pub etal_term: Option<(SmartString, Option<Formatting>)>,
     pub and_term: Option<SmartString>,
+    /// The reference's language, for [NamePolicy](crate::names::NamePolicy)
+    pub lang: Option<Lang>,
 }
 
 impl<O> NameIR<O>
@@ -751,9 +762,14 @@ where
             etal_term,
             and_term,
             built_label,
+            lang: gen_ctx.reference().language.clone(),
         }
     }
 
+    pub(crate) fn variable(&self) -> NameVariable {
+        self.variable
+    }
+
     pub fn achieved_count(&mut self, count: u16) {
         let (prev_best, _at) = self.achieved_at;
         if count < prev_best {
@@ -15,6 +15,8 @@ pub struct RefContext<'a, O: OutputFormat = Markup> {
     pub locale: &'a Locale,
     pub reference: &'a Reference,
     pub locator_type: Option<LocatorType>,
+    /// Whether the locator has more than one number, for `is-plural="locator"`
+    pub locator_plural: bool,
     pub position: Position,
     pub year_suffix: bool,
     pub names_delimiter: Option<SmartString>,
@@ -58,6 +60,7 @@ where
                 // XXX
                 Locators::Multiple { .. } => None,
             }),
+            locator_plural: ctx.is_plural(AnyVariable::Number(NumberVariable::Locator)),
             position: ctx.position.0,
             // XXX: technically Cites need to know this during the Conditionals pass as well,
             // so it should be promoted beyond that single DisambPass::AddYearSuffix(ys) variant.
@@ -88,6 +91,7 @@ where
             locale,
             reference,
             locator_type: fc.to_loc_type(),
+            locator_plural: fc.contains(FreeCond::LOCATOR_PLURAL),
             position: Position::from(fc),
             year_suffix: fc.contains(FreeCond::YEAR_SUFFIX),
             names_delimiter: name_info.0,
@@ -129,7 +133,8 @@ impl<'c, O: OutputFormat> RenderContext for RefContext<'c, O> {
                 .map(NumericValue::from_localized(and_term))
         };
         match var {
-            NumberVariable::PageFirst => get(NumberVariable::Page).and_then(|pp| pp.page_first()),
+            NumberVariable::PageFirst => get(NumberVariable::PageFirst)
+                .or_else(|| get(NumberVariable::Page).and_then(|pp| pp.page_first())),
 
             // Should never be accessed, handled without using the actual NumericValue
             NumberVariable::FirstReferenceNoteNumber
@@ -149,9 +154,6 @@ where
         match var {
             AnyVariable::Number(v) => match v {
                 NumberVariable::Locator => self.locator_type.is_some(),
-                NumberVariable::PageFirst => {
-                    self.is_numeric(AnyVariable::Number(NumberVariable::Page))
-                }
                 NumberVariable::FirstReferenceNoteNumber => {
                     self.position.matches(Position::Subsequent)
                 }
@@ -191,6 +193,27 @@ where
     fn position(&self) -> Option<Position> {
         Some(self.position)
     }
+    /// RefIR is only ever generated for cites to match against
+    fn context(&self) -> Context {
+        Context::Citation
+    }
+    fn is_plural(&self, var: AnyVariable) -> bool {
+        match var {
+            AnyVariable::Number(NumberVariable::Locator) => self.locator_plural,
+            AnyVariable::Name(nv) => self.get_name(nv).map_or(false, |names| names.len() > 1),
+            AnyVariable::Number(nv) => self.get_number(nv).map_or(false, |n| n.is_multiple(nv)),
+            _ => false,
+        }
+    }
+    fn page_label(&self) -> Option<LocatorType> {
+        crate::cite_context::ref_page_label(self.reference, self.locale)
+    }
+    fn genre(&self) -> Option<&str> {
+        self.reference
+            .ordinary
+            .get(&Variable::Genre)
+            .map(|s| s.as_str())
+    }
     fn is_disambiguate(&self, current_count: u32) -> bool {
         // See docs on is_disambiguate
         // current_count is mutated as IR is rolled out;
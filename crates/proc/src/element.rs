@@ -101,17 +101,12 @@ where
             Element::Text(ref text) => {
                 match text.source {
                     TextSource::Macro(ref name) => {
-                        // TODO: be able to return errors
-                        let macro_elements = ctx
-                            .style
-                            .macros
-                            .get(name)
-                            .expect("undefined macro should not be valid CSL");
-                        // Technically, if re-running a style with a fresh IrState, you might
-                        // get an extra level of recursion before it panics. BUT, then it will
-                        // already have panicked when it was run the first time! So we're OK.
-                        // XXX: that's not quite true
-                        state.push_macro(name);
+                        // Undefined and recursive macros render as nothing. They are reported
+                        // through crate::diagnostics instead.
+                        let macro_elements = match ctx.style.macros.get(name) {
+                            Some(els) if state.push_macro(name) => els,
+                            _ => return arena.new_node((IR::Rendered(None), GroupVars::Missing)),
+                        };
                         let ir_sum = sequence(
                             db,
                             state,
@@ -245,6 +240,7 @@ where
                     formatting: g.formatting,
                     affixes: g.affixes.clone(),
                     display: g.display,
+                    text_case: g.text_case,
                     ..Default::default()
                 }),
             ),
@@ -359,9 +355,17 @@ impl<'a, O: OutputFormat, I: OutputFormat> StyleWalker for ProcWalker<'a, O, I>
                     ..Default::default()
                 }),
             ),
-            WalkerFoldType::Substitute => {
-                todo!("use fold() to implement name element substitution")
-            }
+            // Name substitution goes through Names::intermediate, not the walker, so this is
+            // only here for completeness; render the elements like any other sequence.
+            WalkerFoldType::Substitute => sequence(
+                self.db,
+                &mut self.state,
+                self.ctx,
+                self.arena,
+                elements,
+                false,
+                None,
+            ),
         }
     }
 
@@ -0,0 +1,411 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! Explaining how a cite was disambiguated.
+//!
+//! Disambiguation runs as a fixed sequence of passes (see `docs/disambiguation.md`), each of which
+//! stops early once the cite can only match its own reference. This runs the passes for one cite
+//! the same way rendering does, and records which other references it could still be mistaken
+//! for after each of them, and which names ended up rendered differently from what the style
+//! asks for.
+
+use crate::db::{disamb_passes, list_all_name_blocks, refs_accepting_cite, IrGen};
+use crate::disamb::names::{DisambNameRatchet, NameDisambPass};
+use crate::prelude::*;
+use citeproc_io::PersonName;
+use csl::variables::NameVariable;
+use csl::{Atom, GivenNameDisambiguationRule, NameEl, NameForm};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisambMethod {
+    /// `disambiguate-add-names`
+    AddNames,
+    /// `disambiguate-add-givenname`, with the style's `givenname-disambiguation-rule`
+    AddGivenName(GivenNameDisambiguationRule),
+    /// `disambiguate-add-year-suffix`, with the suffix assigned (1 is `a`)
+    AddYearSuffix(u32),
+    /// `<if disambiguate="true">`
+    Conditionals,
+}
+
+impl DisambMethod {
+    fn from_pass(pass: DisambPass) -> Self {
+        match pass {
+            DisambPass::AddNames => DisambMethod::AddNames,
+            DisambPass::AddGivenName(rule) => DisambMethod::AddGivenName(rule),
+            DisambPass::AddYearSuffix(suffix) => DisambMethod::AddYearSuffix(suffix),
+            DisambPass::Conditionals => DisambMethod::Conditionals,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisambStage {
+    /// None for the cite as first rendered, before any disambiguation.
+    pub method: Option<DisambMethod>,
+    /// Other references that could have produced the cite as it was after this stage.
+    pub ambiguous_with: Vec<Atom>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisambExplanation {
+    pub ref_id: Atom,
+    /// The initial render, then one stage for each method the style enables, in the order they are
+    /// tried. Methods that aren't needed still get a stage, but change nothing.
+    pub stages: Vec<DisambStage>,
+    /// The first method after which the cite was no longer ambiguous. None if it never was, or if
+    /// it still is.
+    pub resolved_by: Option<DisambMethod>,
+    /// Name lists that were rendered differently from what the style asks for.
+    pub names: Vec<NameListExpansion>,
+}
+
+impl DisambExplanation {
+    pub fn was_ambiguous(&self) -> bool {
+        self.stages
+            .first()
+            .map_or(false, |stage| !stage.ambiguous_with.is_empty())
+    }
+
+    /// Empty if the cite ended up unambiguous.
+    pub fn still_ambiguous_with(&self) -> &[Atom] {
+        self.stages
+            .last()
+            .map_or(&[], |stage| stage.ambiguous_with.as_slice())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameListExpansion {
+    pub variable: NameVariable,
+    /// How many more names were shown than the et-al settings allow, by `disambiguate-add-names`.
+    pub added_names: u16,
+    pub expanded: Vec<ExpandedName>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpandedName {
+    pub name: PersonName,
+    pub expansion: NameExpansion,
+    pub reason: ExpansionReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NameExpansion {
+    /// Rendered with `form="long"` instead of `form="short"`, which adds initials if the style
+    /// initializes given names, and the full given name otherwise.
+    FormLong,
+    /// Rendered with the full given name instead of initials.
+    FullGivenName,
+}
+
+impl NameExpansion {
+    fn from_pass(pass: NameDisambPass) -> Option<Self> {
+        match pass {
+            NameDisambPass::Initial => None,
+            NameDisambPass::WithFormLong => Some(NameExpansion::FormLong),
+            NameDisambPass::WithInitializeFalse => Some(NameExpansion::FullGivenName),
+        }
+    }
+
+    fn between(original: &NameEl, current: &NameEl) -> Option<Self> {
+        if current.initialize == Some(false) && original.initialize != Some(false) {
+            Some(NameExpansion::FullGivenName)
+        } else if current.form == Some(NameForm::Long) && original.form == Some(NameForm::Short) {
+            Some(NameExpansion::FormLong)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpansionReason {
+    /// Global name disambiguation: the name would otherwise look the same as a different person's
+    /// name somewhere in the document. This happens for every `givenname-disambiguation-rule`
+    /// except `by-cite`.
+    Global,
+    /// Expanded to tell this cite apart from the references it was ambiguous with.
+    ThisCite,
+}
+
+/// None if the cite's reference doesn't exist.
+pub fn explain_disambiguation(db: &dyn IrDatabase, id: CiteId) -> Option<DisambExplanation> {
+    let passes = disamb_passes(db, id)?;
+    let ref_id = id.lookup(db).ref_id.clone();
+    let stages: Vec<DisambStage> = passes
+        .iter()
+        .map(|(pass, gen)| {
+            let mut ambiguous_with =
+                refs_accepting_cite(db, gen.tree_ref(), Some(id), &ref_id, *pass);
+            ambiguous_with.retain(|other| *other != ref_id);
+            DisambStage {
+                method: pass.map(DisambMethod::from_pass),
+                ambiguous_with,
+            }
+        })
+        .collect();
+    let resolved_by = if stages
+        .first()
+        .map_or(true, |stage| stage.ambiguous_with.is_empty())
+    {
+        None
+    } else {
+        stages
+            .iter()
+            .find(|stage| stage.ambiguous_with.is_empty())
+            .and_then(|stage| stage.method)
+    };
+    let names = passes
+        .last()
+        .map(|(_, gen)| name_expansions(db, gen))
+        .unwrap_or_default();
+    Some(DisambExplanation {
+        ref_id,
+        stages,
+        resolved_by,
+        names,
+    })
+}
+
+fn name_expansions(db: &dyn IrDatabase, gen: &IrGen) -> Vec<NameListExpansion> {
+    let global = db.disambiguated_person_names();
+    let arena = &gen.tree.arena;
+    let mut lists = Vec::new();
+    for nid in list_all_name_blocks(gen.tree_ref()) {
+        let nir = match arena.get(nid) {
+            Some(node) => node.get().0.unwrap_name_ir(),
+            None => continue,
+        };
+        let mut expanded = Vec::new();
+        for dnr in &nir.disamb_names {
+            let ratchet = match dnr {
+                DisambNameRatchet::Person(ratchet) => ratchet,
+                DisambNameRatchet::Literal { .. } | DisambNameRatchet::Institution { .. } => {
+                    continue
+                }
+            };
+            // The interned data is the name as the style asks for it, before any disambiguation.
+            let original = ratchet.id.lookup(db);
+            let expansion = match NameExpansion::between(&original.el, &ratchet.data.el) {
+                Some(expansion) => expansion,
+                None => continue,
+            };
+            let global_expansion = global
+                .get(&ratchet.id)
+                .cloned()
+                .and_then(NameExpansion::from_pass);
+            let reason = if global_expansion == Some(expansion) {
+                ExpansionReason::Global
+            } else {
+                ExpansionReason::ThisCite
+            };
+            expanded.push(ExpandedName {
+                name: ratchet.data.value.clone(),
+                expansion,
+                reason,
+            });
+        }
+        let added_names = nir.name_counter.bump;
+        if added_names > 0 || !expanded.is_empty() {
+            lists.push(NameListExpansion {
+                variable: nir.variable(),
+                added_names,
+                expanded,
+            });
+        }
+    }
+    lists
+}
+
+impl fmt::Display for DisambMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisambMethod::AddNames => f.write_str("disambiguate-add-names"),
+            DisambMethod::AddGivenName(rule) => {
+                write!(f, "disambiguate-add-givenname ({})", rule.as_ref())
+            }
+            DisambMethod::AddYearSuffix(suffix) => {
+                write!(f, "disambiguate-add-year-suffix (suffix {})", suffix)
+            }
+            DisambMethod::Conditionals => f.write_str("disambiguate=\"true\" conditions"),
+        }
+    }
+}
+
+/// A summary for people, one line per fact.
+impl fmt::Display for DisambExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "cite of {}", self.ref_id)?;
+        for stage in &self.stages {
+            match &stage.method {
+                None => f.write_str("  as first rendered: ")?,
+                Some(method) => write!(f, "  after {}: ", method)?,
+            }
+            if stage.ambiguous_with.is_empty() {
+                writeln!(f, "unambiguous")?;
+            } else {
+                let others: Vec<&str> = stage.ambiguous_with.iter().map(|a| &**a).collect();
+                writeln!(f, "ambiguous with {}", others.join(", "))?;
+            }
+        }
+        match (&self.resolved_by, self.was_ambiguous()) {
+            (Some(method), _) => writeln!(f, "  resolved by {}", method)?,
+            (None, true) => writeln!(f, "  never resolved")?,
+            (None, false) => {}
+        }
+        for list in &self.names {
+            let variable = list.variable.as_ref();
+            if list.added_names > 0 {
+                writeln!(
+                    f,
+                    "  {}: showed {} more name(s)",
+                    variable, list.added_names
+                )?;
+            }
+            for name in &list.expanded {
+                let what = match name.expansion {
+                    NameExpansion::FormLong => "long form",
+                    NameExpansion::FullGivenName => "full given name",
+                };
+                let why = match name.reason {
+                    ExpansionReason::Global => "it matches another person's name in the document",
+                    ExpansionReason::ThisCite => "it helped tell this cite apart",
+                };
+                let display = [name.name.given.as_deref(), name.name.family.as_deref()]
+                    .iter()
+                    .flatten()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(
+                    f,
+                    "  {}: {} used the {}, because {}",
+                    variable, display, what, why
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::MockProcessor;
+    use citeproc_db::{ClusterId, ClusterNumber, IntraNote};
+    use citeproc_io::{Cite, Date, DateOrRange, Name, Reference};
+    use csl::{CslType, DateVariable};
+
+    fn person(given: &str, family: &str) -> Name {
+        Name::Person(PersonName {
+            given: Some(given.into()),
+            family: Some(family.into()),
+            ..Default::default()
+        })
+    }
+
+    /// John Smith, Jane Smith and another John Smith, all from 2000, cited together.
+    fn smiths(style: &str) -> (MockProcessor, Vec<CiteId>) {
+        let mut proc = MockProcessor::new();
+        proc.set_style_text(style);
+        let mut refs = Vec::new();
+        for (id, given, family) in &[
+            ("john", "John", "Smith"),
+            ("jane", "Jane", "Smith"),
+            ("john2", "John", "Smith"),
+        ] {
+            let mut refr = Reference::empty((*id).into(), CslType::Book);
+            refr.name
+                .insert(NameVariable::Author, vec![person(given, family)]);
+            refr.date.insert(
+                DateVariable::Issued,
+                DateOrRange::Single(Date::new(2000, 1, 1)),
+            );
+            refs.push(refr);
+        }
+        proc.insert_references(refs);
+        let mut interner = string_interner::StringInterner::<ClusterId>::new();
+        let cluster = interner.get_or_intern("cluster");
+        proc.init_clusters(vec![(
+            cluster,
+            ClusterNumber::Note(IntraNote::Single(1)),
+            vec![
+                Cite::basic("jane"),
+                Cite::basic("john"),
+                Cite::basic("john2"),
+            ],
+        )]);
+        let cites = proc.cluster_cites(cluster).to_vec();
+        (proc, cites)
+    }
+
+    #[test]
+    fn given_name_and_year_suffix() {
+        let (proc, cites) = smiths(
+            r#"<style class="in-text" version="1.0.1">
+                <citation disambiguate-add-givenname="true" givenname-disambiguation-rule="by-cite"
+                          disambiguate-add-year-suffix="true">
+                    <layout>
+                        <group delimiter=" ">
+                            <names variable="author"><name form="short" initialize-with=". " /></names>
+                            <date variable="issued"><date-part name="year" /></date>
+                        </group>
+                    </layout>
+                </citation>
+            </style>"#,
+        );
+
+        let jane = explain_disambiguation(&proc, cites[0]).unwrap();
+        assert_eq!(jane.stages[0].ambiguous_with.len(), 2);
+        assert_eq!(
+            jane.resolved_by,
+            Some(DisambMethod::AddGivenName(
+                GivenNameDisambiguationRule::ByCite
+            ))
+        );
+        assert_eq!(
+            jane.names[0].expanded[0].expansion,
+            NameExpansion::FullGivenName
+        );
+        assert_eq!(jane.names[0].expanded[0].reason, ExpansionReason::ThisCite);
+
+        let john = explain_disambiguation(&proc, cites[1]).unwrap();
+        assert_eq!(john.stages[1].ambiguous_with, vec![Atom::from("john2")]);
+        assert!(matches!(
+            john.resolved_by,
+            Some(DisambMethod::AddYearSuffix(_))
+        ));
+        assert!(john.still_ambiguous_with().is_empty());
+    }
+
+    #[test]
+    fn last_stage_is_fully_disambiguated() {
+        let (proc, cites) = smiths(
+            r#"<style class="in-text" version="1.0.1">
+                <citation disambiguate-add-names="true" disambiguate-add-givenname="true">
+                    <layout>
+                        <group delimiter=" ">
+                            <names variable="author"><name form="short" /></names>
+                            <choose>
+                                <if disambiguate="true"><text variable="title" /></if>
+                            </choose>
+                        </group>
+                    </layout>
+                </citation>
+            </style>"#,
+        );
+        for &id in &cites {
+            let stages = disamb_passes(&proc, id).unwrap();
+            assert_eq!(stages[0].1, proc.ir_gen0(id));
+            assert_eq!(
+                stages.last().map(|(_, gen)| gen),
+                Some(&proc.ir_fully_disambiguated(id))
+            );
+        }
+    }
+}
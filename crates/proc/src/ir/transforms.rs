@@ -595,6 +595,9 @@ impl<'a, T> ReducedNameToken<'a, T> {
             NameToken::Name(dnr_index) => match &names[*dnr_index] {
                 DisambNameRatchet::Person(p) => ReducedNameToken::Name(&p.data.value),
                 DisambNameRatchet::Literal { literal, .. } => ReducedNameToken::Literal(literal),
+                DisambNameRatchet::Institution { rendered, .. } => {
+                    ReducedNameToken::Literal(rendered)
+                }
             },
             NameToken::Ellipsis => ReducedNameToken::Ellipsis,
             NameToken::EtAl(..) => ReducedNameToken::EtAl,
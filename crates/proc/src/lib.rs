@@ -30,8 +30,10 @@ mod cite_context;
 mod cluster;
 mod date;
 pub mod db;
+pub mod diagnostics;
 pub mod disamb;
 mod element;
+pub mod explain;
 mod group;
 mod helpers;
 mod ir;
@@ -44,9 +46,10 @@ mod sort;
 mod tree;
 mod walker;
 
-pub use crate::cluster::built_cluster_before_output;
+pub use crate::cluster::{built_cluster_before_output, built_cluster_spans, CiteSpan};
 pub use crate::db::bib_item_preview;
 pub use crate::db::safe_default;
+pub use crate::names::is_east_asian_lang;
 pub use crate::sort::BibNumber;
 
 pub(crate) mod prelude {
@@ -377,14 +380,19 @@ impl IrState {
         IrState::default()
     }
 
-    pub fn push_macro(&mut self, macro_name: &SmartString) {
+    /// Returns false, and pushes nothing, if the macro is already being rendered. Recursive
+    /// macros render as empty, and are reported as diagnostics by
+    /// [crate::diagnostics::style_problems].
+    pub fn push_macro(&mut self, macro_name: &SmartString) -> bool {
         if self.macro_stack.contains(macro_name) {
-            panic!(
-                "foiled macro recursion: {} called from within itself; exiting",
+            warn!(
+                "foiled macro recursion: {} called from within itself",
                 macro_name
             );
+            return false;
         }
         self.macro_stack.insert(macro_name.clone());
+        true
     }
 
     pub fn pop_macro(&mut self, macro_name: &SmartString) {
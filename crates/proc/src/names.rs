@@ -14,11 +14,16 @@ use crate::NamesInheritance;
 use citeproc_io::utils::Intercalate;
 use citeproc_io::{Name, PersonName, Reference};
 use csl::{
-    Atom, DelimiterPrecedes, DemoteNonDroppingParticle, Name as NameEl, NameAnd, NameAsSortOrder,
-    NameEtAl, NameForm, NamePart, NameVariable, Names, Position,
+    Atom, DelimiterPrecedes, DemoteNonDroppingParticle, Lang, Name as NameEl, NameAnd,
+    NameAsSortOrder, NameEtAl, NameForm, NamePart, NameVariable, Names, Position,
 };
 
 mod initials;
+mod institution;
+mod policy;
+use self::institution::{authority_as_institution, institution_sort_string, render_institution};
+pub(crate) use self::policy::east_asian_terms_locale;
+pub use self::policy::{is_east_asian_lang, NamePolicy};
 
 impl<B> DisambNameRatchet<B> {
     fn for_person(
@@ -70,6 +75,9 @@ pub fn to_individual_name_irs<'a, O: OutputFormat, I: OutputFormat>(
     let get_name_ir = move |(var, label_var, value): (NameVariable, NameVariable, Vec<Name>)| {
         // fullstyles_APA.txt
         let all_same_family_name = disamb::all_same_family_name(&value);
+        let terms_locale = east_asian_terms_locale(db, refr, &value);
+        let terms_locale = terms_locale.as_deref().unwrap_or(locale);
+        let has_persons = value.iter().any(|name| matches!(name, Name::Person(_)));
         let ratchets = value
             .into_iter()
             .enumerate()
@@ -97,6 +105,16 @@ pub fn to_individual_name_irs<'a, O: OutputFormat, I: OutputFormat>(
                             is_latin_cyrillic,
                         }
                     }
+                    Name::Institution(institution) => DisambNameRatchet::Institution {
+                        rendered: render_institution(
+                            fmt,
+                            names.institution.as_ref(),
+                            &institution,
+                            has_persons,
+                            terms_locale.and_term(None),
+                        ),
+                        is_latin_cyrillic: institution.is_latin_cyrillic,
+                    },
                 }
             })
             .collect();
@@ -107,10 +125,10 @@ pub fn to_individual_name_irs<'a, O: OutputFormat, I: OutputFormat>(
             label_var,
             ratchets,
             style,
-            locale
+            terms_locale
                 .et_al_term(names_inheritance.et_al.as_ref())
                 .map(|(a, b)| (SmartString::from(a), b)),
-            locale.and_term(None).map(|x| x.into()),
+            terms_locale.and_term(None).map(|x| x.into()),
         )
     };
 
@@ -171,7 +189,11 @@ pub fn to_individual_name_irs<'a, O: OutputFormat, I: OutputFormat>(
         .filter(move |var| !state.is_suppressed_name(**var))
         .filter_map(move |var| {
             let ovar = var_override.as_ref().unwrap_or(var);
-            refr.name.get(var).map(|val| (*var, *ovar, val.clone()))
+            refr.name
+                .get(var)
+                .cloned()
+                .or_else(|| authority_as_institution(refr, *var, &style.features))
+                .map(|val| (*var, *ovar, val))
         })
         .map(get_name_ir)
 }
@@ -206,10 +228,12 @@ pub(crate) fn sort_strings_for_names(
         bump_name_count: 0,
         demote_non_dropping_particle: style.demote_non_dropping_particle,
         initialize_with_hyphen: style.initialize_with_hyphen,
+        lang: refr.language.as_ref(),
         fmt: &fmt,
     };
     let mut out = Vec::new();
-    if let Some(values) = refr.name.get(&var) {
+    let authority = authority_as_institution(refr, var, &style.features);
+    if let Some(values) = refr.name.get(&var).or(authority.as_ref()) {
         for value in values {
             match value {
                 Name::Person(pn) => {
@@ -220,6 +244,9 @@ pub(crate) fn sort_strings_for_names(
                         out.push(Natural::new(literal.clone()));
                     }
                 }
+                Name::Institution(institution) => {
+                    out.push(Natural::new(institution_sort_string(institution)));
+                }
             }
         }
     }
@@ -438,6 +465,7 @@ impl<'c, O: OutputFormat> NameIR<O> {
             bump_name_count: self.name_counter.bump,
             demote_non_dropping_particle: self.demote_non_dropping_particle,
             initialize_with_hyphen: self.initialize_with_hyphen,
+            lang: self.lang.as_ref(),
         }
     }
 
@@ -496,9 +524,9 @@ impl<'c, O: OutputFormat> NameIR<O> {
             let renderable = match ntb {
                 NameTokenBuilt::Built(b, _lat_cy) => Some(b),
                 NameTokenBuilt::Ratchet(index) => match self.disamb_names.get(index)? {
-                    DisambNameRatchet::Literal {
-                        literal,
-                        is_latin_cyrillic: _,
+                    DisambNameRatchet::Literal { literal, .. }
+                    | DisambNameRatchet::Institution {
+                        rendered: literal, ..
                     } => {
                         if fmt.is_empty(literal) {
                             None
@@ -682,6 +710,8 @@ pub struct OneNameVar<'a, O: OutputFormat> {
     // From Style
     pub demote_non_dropping_particle: DemoteNonDroppingParticle,
     pub initialize_with_hyphen: bool,
+    /// The reference's language, for [NamePolicy]
+    pub lang: Option<&'a Lang>,
     pub fmt: &'a O,
 }
 
@@ -795,8 +825,8 @@ impl<'a, O: OutputFormat> OneNameVar<'a, O> {
         pn: &PersonName,
         out: &mut Vec<Natural<SmartString>>,
     ) {
-        let order = get_sort_order(
-            pn.is_latin_cyrillic,
+        let policy = NamePolicy::of(pn, self.lang);
+        let order = policy.sort_order(
             self.name_el.form == Some(NameForm::Long),
             self.demote_non_dropping_particle,
         );
@@ -813,17 +843,7 @@ impl<'a, O: OutputFormat> OneNameVar<'a, O> {
                     | NamePartToken::GivenAndBoth => {
                         if let Some(ref given) = pn.given {
                             // TODO: parametrize for disambiguation
-                            let string = initialize(
-                                &given,
-                                self.name_el.initialize.unwrap_or(true),
-                                // name_OnlyGivenname.txt
-                                if pn.family.is_some() {
-                                    self.name_el.initialize_with.as_ref().map(|s| s.as_ref())
-                                } else {
-                                    None
-                                },
-                                self.initialize_with_hyphen,
-                            );
+                            let string = self.initialize_given(pn, policy, given);
                             s.push_str(&string);
                             if token != NamePartToken::Given {
                                 if let Some(dp) = pn.dropping_particle.as_ref() {
@@ -897,6 +917,28 @@ impl<'a, O: OutputFormat> OneNameVar<'a, O> {
         }
     }
 
+    fn initialize_given<'g>(
+        &self,
+        pn: &PersonName,
+        policy: NamePolicy,
+        given: &'g str,
+    ) -> std::borrow::Cow<'g, str> {
+        if !policy.initializes() {
+            return given.into();
+        }
+        initialize(
+            given,
+            self.name_el.initialize.unwrap_or(true),
+            // name_OnlyGivenname.txt
+            if pn.family.is_some() {
+                self.name_el.initialize_with.as_ref().map(|s| s.as_ref())
+            } else {
+                None
+            },
+            self.initialize_with_hyphen,
+        )
+    }
+
     fn format_with_part(&self, o_part: &Option<NamePart>, s: impl AsRef<str>) -> O::Build {
         let fmt = self.fmt;
         // We don't want quotes to be parsed in names, so don't leave MicroNodes; we just
@@ -926,8 +968,8 @@ impl<'a, O: OutputFormat> OneNameVar<'a, O> {
     pub(crate) fn render_person_name(&self, pn: &PersonName, seen_one: bool) -> O::Build {
         let fmt = self.fmt;
 
-        let order = get_display_order(
-            pn.is_latin_cyrillic,
+        let policy = NamePolicy::of(pn, self.lang);
+        let order = policy.display_order(
             self.name_el.form == Some(NameForm::Long),
             self.naso(seen_one),
             self.demote_non_dropping_particle,
@@ -946,17 +988,7 @@ impl<'a, O: OutputFormat> OneNameVar<'a, O> {
                         let family_part = &self.name_el.name_part_family;
                         let mut parts = Vec::new();
                         // TODO: parametrize for disambiguation
-                        let initialized = initialize(
-                            &given,
-                            self.name_el.initialize.unwrap_or(true),
-                            // name_OnlyGivenname.txt
-                            if pn.family.is_some() {
-                                self.name_el.initialize_with.as_ref().map(|s| s.as_ref())
-                            } else {
-                                None
-                            },
-                            self.initialize_with_hyphen,
-                        );
+                        let initialized = self.initialize_given(pn, policy, given);
                         parts.push(self.format_with_part(given_part, initialized.as_ref()));
                         if token != NamePartToken::Given {
                             if let Some(dp) = pn.dropping_particle.as_ref() {
@@ -1172,6 +1204,9 @@ impl<B> NameTokenBuilt<B> {
             NameTokenBuilt::Ratchet(index) => match &ratchets[*index] {
                 DisambNameRatchet::Literal {
                     is_latin_cyrillic, ..
+                }
+                | DisambNameRatchet::Institution {
+                    is_latin_cyrillic, ..
                 } => Some(*is_latin_cyrillic),
                 DisambNameRatchet::Person(ratchet) => Some(ratchet.data.value.is_latin_cyrillic),
             },
@@ -1179,7 +1214,7 @@ impl<B> NameTokenBuilt<B> {
     }
 }
 
-use self::ord::{get_display_order, get_sort_order, DisplayOrdering, NamePartToken};
+use self::ord::{DisplayOrdering, NamePartToken};
 
 #[allow(dead_code)]
 mod ord {
@@ -1275,6 +1310,10 @@ mod ord {
         &[Family, SortSeparator, GivenAndBoth, SortSeparator, Suffix];
     /// [La] [Fontaine]
     static LATIN_SHORT: DisplayOrdering = &[FamilyDropped];
+    /// [Kovács] [János]; Hungarian and Vietnamese names, where this is the normal order, so
+    /// name-as-sort-order doesn't add a sort separator.
+    pub static LATIN_FAMILY_FIRST_LONG: DisplayOrdering =
+        &[FamilyDropped, Space, GivenAndDropping, Space, Suffix];
 
     /// [La Fontaine] [de] [Jean] [III]
     static LATIN_SORT_NEVER: SortOrdering = &[
@@ -1292,10 +1331,7 @@ mod ord {
     ];
 
     /// 毛泽东 [Mao Zedong]
-    static NON_LATIN_LONG: DisplayOrdering = &[
-        Family, // TODO: how do we determine if spaces are required?
-        Given,
-    ];
+    static NON_LATIN_LONG: DisplayOrdering = &[Family, Given];
     /// 毛 [Mao]
     static NON_LATIN_SHORT: DisplayOrdering = &[Family];
     /// 毛泽东 [Mao Zedong]
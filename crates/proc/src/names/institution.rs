@@ -0,0 +1,275 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! CSL-M institutional names. These render as a fixed string per reference, and never take part
+//! in given-name disambiguation.
+
+use crate::prelude::*;
+use citeproc_io::unicode::is_latin_cyrillic;
+use citeproc_io::{Institution, InstitutionUnit, Name, Reference};
+use csl::{
+    Institution as InstitutionEl, InstitutionPart, InstitutionPartName, InstitutionParts,
+    InstitutionUseFirst, NameAnd, NameVariable, Variable,
+};
+
+/// Picks the subunits to render, following `use-first`, `substitute-use-first` and `use-last`.
+/// `has_persons` is whether any person names appear alongside the institution.
+fn select_units<'i>(
+    el: &InstitutionEl,
+    units: &'i [InstitutionUnit],
+    has_persons: bool,
+) -> Vec<&'i InstitutionUnit> {
+    let use_first = el.use_first.map(|uf| match uf {
+        InstitutionUseFirst::Normal(n) => n as usize,
+        InstitutionUseFirst::Substitute(n) if !has_persons => n as usize,
+        InstitutionUseFirst::Substitute(_) => 0,
+    });
+    let mut selected: Vec<_> = if use_first.is_none() && el.use_last.is_none() {
+        units.iter().collect()
+    } else {
+        let first = std::cmp::min(use_first.unwrap_or(0), units.len());
+        let last = std::cmp::min(el.use_last.unwrap_or(0) as usize, units.len() - first);
+        units[..first]
+            .iter()
+            .chain(units[units.len() - last..].iter())
+            .collect()
+    };
+    if el.reverse_order {
+        selected.reverse();
+    }
+    selected
+}
+
+fn part_for(el: &InstitutionEl, short: bool) -> Option<&InstitutionPart> {
+    el.institution_parts.iter().find(|p| match p.name {
+        InstitutionPartName::Short => short,
+        InstitutionPartName::Long(_) => !short,
+    })
+}
+
+fn render_part<O: OutputFormat>(fmt: &O, part: Option<&InstitutionPart>, text: &str) -> O::Build {
+    let mut text = SmartString::from(text);
+    if part.map_or(false, |p| p.strip_periods) {
+        text = text.chars().filter(|&c| c != '.').collect();
+    }
+    let b = fmt.text_node(text, part.and_then(|p| p.formatting));
+    fmt.affixed(b, part.and_then(|p| p.affixes.as_ref()))
+}
+
+fn render_unit<O: OutputFormat>(
+    fmt: &O,
+    el: &InstitutionEl,
+    unit: &InstitutionUnit,
+) -> Option<O::Build> {
+    let long_part = part_for(el, false);
+    let short_part = part_for(el, true);
+    let short = unit.short.as_deref();
+    // `if-short="true"` on the long part means only render it if there is a short form too.
+    let long = match long_part.map(|p| p.name) {
+        Some(InstitutionPartName::Long(true)) if short.is_none() => None,
+        _ => Some(render_part(fmt, long_part, &unit.long)),
+    };
+    let short = short.map(|s| render_part(fmt, short_part, s));
+    let pair = |a: Option<O::Build>, b: Option<O::Build>| match (a, b) {
+        (Some(a), Some(b)) => Some(fmt.join_delim(a, " ", b)),
+        (a, b) => a.or(b),
+    };
+    match el.parts_selector {
+        InstitutionParts::Long => long,
+        InstitutionParts::Short => short.or(long),
+        InstitutionParts::ShortLong => pair(short, long),
+        InstitutionParts::LongShort => pair(long, short),
+    }
+}
+
+/// Renders an institution according to `<institution>`, or with every subunit in its long form,
+/// smallest first, if the `<names>` block doesn't have one. `and_term` is the locale's `and` term;
+/// without one, `and="text"` joins the last subunit with the delimiter like the rest.
+pub(crate) fn render_institution<O: OutputFormat>(
+    fmt: &O,
+    el: Option<&InstitutionEl>,
+    institution: &Institution,
+    has_persons: bool,
+    and_term: Option<&str>,
+) -> O::Build {
+    let default_el = InstitutionEl::default();
+    let el = el.unwrap_or(&default_el);
+    let units: Vec<O::Build> = select_units(el, &institution.units, has_persons)
+        .into_iter()
+        .filter_map(|unit| render_unit(fmt, el, unit))
+        .collect();
+    let delimiter = el.delimiter.as_deref().unwrap_or(", ");
+    let and = match el.and {
+        Some(NameAnd::Symbol) => Some("&"),
+        Some(NameAnd::Text) => and_term,
+        None => None,
+    };
+    match and {
+        Some(and) if units.len() >= 2 => {
+            let mut units = units;
+            let last = units.pop().unwrap();
+            let rest = fmt.group(units, delimiter, None);
+            // As between person names, only Latin and Cyrillic terms are spaced out
+            let and = if is_latin_cyrillic(and) {
+                format!(" {} ", and)
+            } else {
+                and.to_owned()
+            };
+            fmt.join_delim(rest, &and, last)
+        }
+        _ => fmt.group(units, delimiter, None),
+    }
+}
+
+/// Institutions sort on their long forms, largest subunit first.
+pub(crate) fn institution_sort_string(institution: &Institution) -> SmartString {
+    let mut s = SmartString::new();
+    for (i, unit) in institution.units.iter().rev().enumerate() {
+        if i > 0 {
+            s.push_str(", ");
+        }
+        s.push_str(&unit.long);
+    }
+    s
+}
+
+/// CSL-M styles render the `authority` of a legal reference through `<names>`, but it is usually
+/// input as a plain string. With the institutions feature on, that string is used as a
+/// one-subunit institution.
+pub(crate) fn authority_as_institution(
+    refr: &Reference,
+    var: NameVariable,
+    features: &csl::Features,
+) -> Option<Vec<Name>> {
+    if var != NameVariable::Authority || !features.institutions {
+        return None;
+    }
+    let authority = refr.ordinary.get(&Variable::Authority)?;
+    if authority.is_empty() {
+        return None;
+    }
+    Some(vec![Name::Institution(Institution::new(vec![
+        InstitutionUnit::new(authority.as_str()),
+    ]))])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use csl::Affixes;
+
+    fn oxford() -> Institution {
+        Institution::new(vec![
+            InstitutionUnit::new("Dept. of Physics"),
+            InstitutionUnit::new("Faculty of Science"),
+            InstitutionUnit {
+                long: "University of Oxford".into(),
+                short: Some("Oxford".into()),
+            },
+        ])
+    }
+
+    fn render_with_and(
+        el: Option<&InstitutionEl>,
+        has_persons: bool,
+        and_term: Option<&str>,
+    ) -> String {
+        let fmt = Markup::plain();
+        let b = render_institution(&fmt, el, &oxford(), has_persons, and_term);
+        fmt.output(b, false)
+    }
+
+    fn render(el: Option<&InstitutionEl>, has_persons: bool) -> String {
+        render_with_and(el, has_persons, Some("and"))
+    }
+
+    #[test]
+    fn institution_units() {
+        assert_eq!(
+            render(None, false),
+            "Dept. of Physics, Faculty of Science, University of Oxford"
+        );
+        let reversed = InstitutionEl {
+            reverse_order: true,
+            delimiter: Some(". ".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            render(Some(&reversed), false),
+            "University of Oxford. Faculty of Science. Dept. of Physics"
+        );
+        let first_last = InstitutionEl {
+            use_first: Some(InstitutionUseFirst::Normal(1)),
+            use_last: Some(1),
+            and: Some(NameAnd::Text),
+            ..Default::default()
+        };
+        assert_eq!(
+            render(Some(&first_last), false),
+            "Dept. of Physics and University of Oxford"
+        );
+        assert_eq!(
+            render_with_and(Some(&first_last), false, Some("und")),
+            "Dept. of Physics und University of Oxford"
+        );
+        assert_eq!(
+            render_with_and(Some(&first_last), false, None),
+            "Dept. of Physics, University of Oxford"
+        );
+        let substitute = InstitutionEl {
+            use_first: Some(InstitutionUseFirst::Substitute(1)),
+            use_last: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            render(Some(&substitute), false),
+            "Dept. of Physics, University of Oxford"
+        );
+        assert_eq!(render(Some(&substitute), true), "University of Oxford");
+    }
+
+    #[test]
+    fn institution_parts() {
+        let short = InstitutionEl {
+            parts_selector: InstitutionParts::Short,
+            use_last: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(render(Some(&short), false), "Oxford");
+        let long_short = InstitutionEl {
+            parts_selector: InstitutionParts::LongShort,
+            use_last: Some(2),
+            institution_parts: vec![
+                InstitutionPart {
+                    name: InstitutionPartName::Short,
+                    affixes: Some(Affixes {
+                        prefix: "(".into(),
+                        suffix: ")".into(),
+                    }),
+                    ..Default::default()
+                },
+                InstitutionPart {
+                    name: InstitutionPartName::Long(false),
+                    strip_periods: true,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            render(Some(&long_short), false),
+            "Faculty of Science, University of Oxford (Oxford)"
+        );
+    }
+
+    #[test]
+    fn institution_sort() {
+        assert_eq!(
+            institution_sort_string(&oxford()),
+            "University of Oxford, Faculty of Science, Dept. of Physics"
+        );
+    }
+}
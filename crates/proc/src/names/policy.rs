@@ -0,0 +1,257 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! Which way round a person's name goes, whether its given name can be initialized, and so on.
+//! This depends on the script the name is written in, and for Latin-script names that were input
+//! as one string, on the language of the reference.
+
+use super::ord::{self, DisplayOrdering, SortOrdering};
+use crate::prelude::*;
+use citeproc_io::unicode::is_cjk;
+use citeproc_io::{lang_puts_family_first, Name, PersonName, Reference};
+use csl::{DemoteNonDroppingParticle, IsoLang, Lang, Locale, LocaleSource};
+use std::sync::Arc;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NamePolicy {
+    /// Names in every script but Chinese, Japanese and Korean. Given name first unless
+    /// `name-as-sort-order` applies, in which case the family name comes first followed by the
+    /// `sort-separator`.
+    Western,
+    /// Latin-script literal names in a reference whose language puts the family name first
+    /// (Hungarian, Vietnamese). The family name always comes first, with a space and no
+    /// `sort-separator` after it.
+    FamilyFirst,
+    /// Chinese, Japanese and Korean names. Family name first with no space, no `sort-separator`,
+    /// and never initialized.
+    EastAsian,
+}
+
+impl NamePolicy {
+    /// `lang` is the language of the reference the name comes from. It only affects names input
+    /// as `{ "literal": "..." }`, which are written the way that language writes them. A
+    /// structured name says nothing about its bearer's naming convention.
+    pub fn of(pn: &PersonName, lang: Option<&Lang>) -> Self {
+        if !pn.is_latin_cyrillic && is_cjk_name(pn) {
+            return NamePolicy::EastAsian;
+        }
+        if pn.is_literal && lang.map_or(false, lang_puts_family_first) {
+            return NamePolicy::FamilyFirst;
+        }
+        NamePolicy::Western
+    }
+
+    pub fn initializes(self) -> bool {
+        self != NamePolicy::EastAsian
+    }
+
+    pub(super) fn display_order(
+        self,
+        long: bool,
+        naso: bool,
+        demote: DemoteNonDroppingParticle,
+    ) -> DisplayOrdering {
+        match self {
+            NamePolicy::Western => ord::get_display_order(true, long, naso, demote),
+            NamePolicy::FamilyFirst if long => ord::LATIN_FAMILY_FIRST_LONG,
+            NamePolicy::FamilyFirst => ord::get_display_order(true, false, naso, demote),
+            NamePolicy::EastAsian => ord::get_display_order(false, long, naso, demote),
+        }
+    }
+
+    pub(super) fn sort_order(self, long: bool, demote: DemoteNonDroppingParticle) -> SortOrdering {
+        ord::get_sort_order(self != NamePolicy::EastAsian, long, demote)
+    }
+}
+
+fn is_cjk_name(pn: &PersonName) -> bool {
+    pn.family.iter().chain(pn.given.iter()).any(|s| is_cjk(s))
+}
+
+/// Chinese, Japanese or Korean. References in these languages may use the terms of their own
+/// locale, so the processor fetches the locales of references in these languages too.
+pub fn is_east_asian_lang(lang: &Lang) -> bool {
+    match lang {
+        Lang::Iso(IsoLang::Chinese, _) | Lang::Iso(IsoLang::Japanese, _) => true,
+        Lang::Iso(IsoLang::Other(code), _) => code == "ko",
+        _ => false,
+    }
+}
+
+/// Chinese, Japanese and Korean have their own "et al." and "and" terms (等, ほか, 외). When every
+/// name in a list is East Asian and the reference is in one of those languages, and that locale
+/// is available, this returns it so its terms can be used instead of the style locale's. If it
+/// hasn't been fetched yet, the style locale's terms are used until it has.
+pub(crate) fn east_asian_terms_locale(
+    db: &dyn IrDatabase,
+    refr: &Reference,
+    names: &[Name],
+) -> Option<Arc<Locale>> {
+    let lang = refr.language.as_ref().filter(|l| is_east_asian_lang(l))?;
+    let all_east_asian = !names.is_empty()
+        && names.iter().all(|name| match name {
+            Name::Person(pn) => NamePolicy::of(pn, Some(lang)) == NamePolicy::EastAsian,
+            Name::Literal { literal, .. } => is_cjk(literal),
+            Name::Institution(institution) => institution.units.iter().any(|u| is_cjk(&u.long)),
+        });
+    if !all_east_asian {
+        return None;
+    }
+    db.parsed_locale(LocaleSource::File(lang.clone()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::OneNameVar;
+    use super::*;
+    use crate::sort::Natural;
+    use csl::{Name as NameEl, NameAsSortOrder, NameForm};
+
+    fn latin(family: &str, given: &str) -> PersonName {
+        PersonName {
+            family: Some(family.into()),
+            given: Some(given.into()),
+            is_latin_cyrillic: true,
+            ..Default::default()
+        }
+    }
+
+    /// Parsed from a `{ "literal": "..." }` name.
+    fn literal(family: &str, given: &str) -> PersonName {
+        PersonName {
+            is_literal: true,
+            ..latin(family, given)
+        }
+    }
+
+    fn cjk(family: &str, given: &str) -> PersonName {
+        PersonName {
+            is_latin_cyrillic: false,
+            ..latin(family, given)
+        }
+    }
+
+    fn render(pn: &PersonName, lang: Option<&str>, name_el: NameEl) -> String {
+        let lang: Option<Lang> = lang.map(|l| l.parse().unwrap());
+        let fmt = Markup::plain();
+        let runner = OneNameVar {
+            name_el: &name_el,
+            bump_name_count: 0,
+            demote_non_dropping_particle: DemoteNonDroppingParticle::DisplayAndSort,
+            initialize_with_hyphen: true,
+            lang: lang.as_ref(),
+            fmt: &fmt,
+        };
+        let built = runner.render_person_name(pn, false);
+        fmt.output(built, false).to_string()
+    }
+
+    fn sort_keys(pn: &PersonName, lang: Option<&str>) -> Vec<Natural<SmartString>> {
+        let lang: Option<Lang> = lang.map(|l| l.parse().unwrap());
+        let fmt = Markup::plain();
+        let name_el = NameEl {
+            form: Some(NameForm::Long),
+            ..Default::default()
+        };
+        let runner = OneNameVar {
+            name_el: &name_el,
+            bump_name_count: 0,
+            demote_non_dropping_particle: DemoteNonDroppingParticle::DisplayAndSort,
+            initialize_with_hyphen: true,
+            lang: lang.as_ref(),
+            fmt: &fmt,
+        };
+        let mut out = Vec::new();
+        runner.person_name_sort_keys(pn, &mut out);
+        out
+    }
+
+    fn natural(keys: &[&str]) -> Vec<Natural<SmartString>> {
+        keys.iter().map(|&k| Natural::new(k.into())).collect()
+    }
+
+    #[test]
+    fn policy_table() {
+        let long = || NameEl {
+            form: Some(NameForm::Long),
+            ..Default::default()
+        };
+        let naso = || NameEl {
+            name_as_sort_order: Some(NameAsSortOrder::All),
+            ..long()
+        };
+        let initials = || NameEl {
+            initialize_with: Some(". ".into()),
+            ..long()
+        };
+        let short = || NameEl {
+            form: Some(NameForm::Short),
+            ..Default::default()
+        };
+        let smith = latin("Smith", "John");
+        let kovacs = literal("Kovács", "János");
+        // the same name as kovacs, input with separate family and given names
+        let janos = latin("Kovács", "János");
+        let nguyen = literal("Nguyễn", "Văn An");
+        let mao = cjk("毛", "泽东");
+        let yamada = cjk("山田", "太郎");
+        let kim = cjk("김", "철수");
+        // neither Latin nor CJK
+        let cohen = PersonName {
+            is_latin_cyrillic: false,
+            ..latin("כהן", "דוד")
+        };
+        #[rustfmt::skip]
+        let table: &[(&PersonName, Option<&str>, NamePolicy, NameEl, &str)] = &[
+            (&smith,  None,          NamePolicy::Western,     long(),     "John Smith"),
+            (&smith,  None,          NamePolicy::Western,     naso(),     "Smith, John"),
+            (&smith,  None,          NamePolicy::Western,     initials(), "J. Smith"),
+            (&smith,  Some("hu-HU"), NamePolicy::Western,     long(),     "John Smith"),
+            (&janos,  Some("hu-HU"), NamePolicy::Western,     long(),     "János Kovács"),
+            (&kovacs, Some("hu-HU"), NamePolicy::FamilyFirst, long(),     "Kovács János"),
+            (&kovacs, Some("hu-HU"), NamePolicy::FamilyFirst, naso(),     "Kovács János"),
+            (&kovacs, Some("hu-HU"), NamePolicy::FamilyFirst, initials(), "Kovács J."),
+            (&kovacs, Some("hu-HU"), NamePolicy::FamilyFirst, short(),    "Kovács"),
+            (&nguyen, Some("vi"),    NamePolicy::FamilyFirst, long(),     "Nguyễn Văn An"),
+            (&nguyen, Some("en-US"), NamePolicy::Western,     long(),     "Văn An Nguyễn"),
+            (&mao,    Some("zh-CN"), NamePolicy::EastAsian,   long(),     "毛泽东"),
+            (&mao,    None,          NamePolicy::EastAsian,   naso(),     "毛泽东"),
+            (&mao,    Some("zh-CN"), NamePolicy::EastAsian,   initials(), "毛泽东"),
+            (&mao,    Some("zh-CN"), NamePolicy::EastAsian,   short(),    "毛"),
+            (&yamada, Some("ja-JP"), NamePolicy::EastAsian,   initials(), "山田太郎"),
+            (&kim,    Some("ko-KR"), NamePolicy::EastAsian,   naso(),     "김철수"),
+            (&cohen,  Some("he-IL"), NamePolicy::Western,     long(),     "דוד כהן"),
+            (&cohen,  Some("he-IL"), NamePolicy::Western,     naso(),     "כהן, דוד"),
+        ];
+        for (pn, lang, policy, name_el, expected) in table.iter().cloned() {
+            let parsed: Option<Lang> = lang.map(|l| l.parse().unwrap());
+            assert_eq!(NamePolicy::of(pn, parsed.as_ref()), policy, "{:?}", pn);
+            assert_eq!(
+                render(pn, lang, name_el.clone()),
+                expected,
+                "{:?} {:?}",
+                pn,
+                name_el
+            );
+        }
+    }
+
+    #[test]
+    fn policy_sort_keys() {
+        assert_eq!(
+            sort_keys(&latin("Smith", "John"), None),
+            natural(&["Smith", "John"])
+        );
+        assert_eq!(
+            sort_keys(&literal("Kovács", "János"), Some("hu-HU")),
+            natural(&["Kovács", "János"])
+        );
+        assert_eq!(
+            sort_keys(&cjk("毛", "泽东"), Some("zh-CN")),
+            natural(&["毛", "泽东"])
+        );
+    }
+}
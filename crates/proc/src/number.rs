@@ -111,15 +111,26 @@ enum NumBefore {
     SeenNumHyphen(u32),
     SeenRoman(u32),
     SeenRomanHyphen(u32),
+    /// A number with a suffix, like `2nd`. Never the start of a page range.
+    SeenSuffixed,
+    SeenSuffixedHyphen,
 }
 impl NumBefore {
-    fn matching_for_crop(&self, _is_roman: bool) -> Option<u32> {
+    /// Only an arabic number can be the start of a range ending in an arabic number, and likewise
+    /// for Roman numerals.
+    fn matching_for_crop(&self, is_roman: bool) -> Option<u32> {
         match *self {
-            NumBefore::SeenNumHyphen(n) => Some(n),
-            NumBefore::SeenRomanHyphen(n) => Some(n),
+            NumBefore::SeenNumHyphen(n) if !is_roman => Some(n),
+            NumBefore::SeenRomanHyphen(n) if is_roman => Some(n),
             _ => None,
         }
     }
+    fn is_range_start(&self) -> bool {
+        matches!(
+            self,
+            NumBefore::SeenNumHyphen(_) | NumBefore::SeenRomanHyphen(_)
+        )
+    }
     fn see_num(num: u32, is_roman: bool) -> Self {
         if is_roman {
             NumBefore::SeenRoman(num)
@@ -159,8 +170,16 @@ impl<'a> State<'a> {
                     last: NumBefore::see_num(num, is_roman),
                 },
             ),
-            State::Normal => (pfx, num, HyphenInsert::None, State::Normal),
-            State::Hyphenating { prefix, last } if pfx == prefix => {
+            State::Normal => (
+                pfx,
+                num,
+                HyphenInsert::None,
+                State::Hyphenating {
+                    prefix: pfx,
+                    last: NumBefore::SeenSuffixed,
+                },
+            ),
+            State::Hyphenating { prefix, last } if pfx == prefix && sfx.is_empty() => {
                 // Prefixes match, we're going to crop it
                 if let Some(last_num) = last.matching_for_crop(is_roman) {
                     if let Some(prf) = prf {
@@ -175,6 +194,9 @@ impl<'a> State<'a> {
                         // ~ a hundred CSL tests, so the spec is to be ignored...
                         (pfx, num, HyphenInsert::Locale, State::Normal)
                     }
+                } else if last.is_range_start() {
+                    // e.g. 1998-VIII, a range but not one that can be cropped
+                    (pfx, num, HyphenInsert::Locale, State::Normal)
                 } else {
                     (pfx, num, HyphenInsert::Simple, State::Normal)
                 }
@@ -191,6 +213,7 @@ impl<'a> State<'a> {
                 let neu = match *last {
                     NumBefore::SeenNum(n) => NumBefore::SeenNumHyphen(n),
                     NumBefore::SeenRoman(n) => NumBefore::SeenRomanHyphen(n),
+                    NumBefore::SeenSuffixed => NumBefore::SeenSuffixedHyphen,
                     a => a,
                 };
                 State::Hyphenating { last: neu, prefix }
@@ -200,7 +223,9 @@ impl<'a> State<'a> {
     fn non_num_should_push_hyphen(&self) -> HyphenInsert {
         match self {
             State::Hyphenating { last, .. } => match last {
-                NumBefore::SeenNumHyphen(_) | NumBefore::SeenRomanHyphen(_) => HyphenInsert::Simple,
+                NumBefore::SeenNumHyphen(_)
+                | NumBefore::SeenRomanHyphen(_)
+                | NumBefore::SeenSuffixedHyphen => HyphenInsert::Simple,
                 _ => HyphenInsert::None,
             },
             _ => HyphenInsert::None,
@@ -303,6 +328,36 @@ fn tokens_to_string(
     s
 }
 
+#[test]
+fn test_page_ranges() {
+    use citeproc_io::{NumberLike, NumericValue};
+    fn go(input: &str, prf: Option<PageRangeFormat>) -> SmartString {
+        let like = NumberLike::Str(input.into());
+        let val = NumericValue::from_localized("and")(&like);
+        arabic_number(&val, &Locale::default(), NumberVariable::Page, prf)
+    }
+    let chicago = Some(PageRangeFormat::Chicago);
+    let expanded = Some(PageRangeFormat::Expanded);
+    let minimal = Some(PageRangeFormat::Minimal);
+    let minimal_two = Some(PageRangeFormat::MinimalTwo);
+    assert_eq!(go("321-28", expanded), "321\u{2013}328");
+    assert_eq!(go("S12-S15", minimal), "S12\u{2013}5");
+    assert_eq!(go("S12-S15", expanded), "S12\u{2013}S15");
+    assert_eq!(go("S12-S15", None), "S12\u{2013}S15");
+    assert_eq!(go("e1023-e1030", chicago), "e1023\u{2013}30");
+    assert_eq!(go("e1023-e1030", minimal_two), "e1023\u{2013}30");
+    assert_eq!(go("A-3-A-7", minimal), "A-3\u{2013}7");
+    assert_eq!(go("A-3\u{2013}A-7", expanded), "A-3\u{2013}A-7");
+    // Roman numerals are not cropped
+    assert_eq!(go("xiv-xix", minimal), "xiv\u{2013}xix");
+    assert_eq!(go("xiv-19", minimal), "xiv\u{2013}19");
+    // Prefixes don't match, so not a page range
+    assert_eq!(go("S12-T15", minimal), "S12-T15");
+    assert_eq!(go("2nd-4th", minimal), "2nd-4th");
+    // Backwards, left alone
+    assert_eq!(go("115-112", chicago), "115\u{2013}112");
+}
+
 /// Numbers bigger than 3999 are too cumbersome anyway
 pub fn roman_representable(val: &NumericValue) -> bool {
     match val {
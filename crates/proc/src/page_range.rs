@@ -1,8 +1,15 @@
 use csl::PageRangeFormat;
 
-/// Returns the second number with the page range format applied.
-pub fn truncate_prf(prf: PageRangeFormat, first: u32, mut second: u32) -> u32 {
-    second = expand(first, second);
+/// Returns the second number with the page range format applied. Ranges that go backwards are
+/// returned as-is.
+pub fn truncate_prf(prf: PageRangeFormat, first: u32, second: u32) -> u32 {
+    if second == 0 {
+        return second;
+    }
+    let second = match expand(first, second) {
+        expanded if expanded < first => return second,
+        expanded => expanded,
+    };
     match prf {
         PageRangeFormat::Chicago => {
             let mod100 = first % 100;
@@ -58,6 +65,13 @@ fn page_range_chicago() {
     assert_eq!(go(1486, 1496), 96);
 }
 
+#[test]
+fn page_range_backwards() {
+    assert_eq!(truncate_prf(PageRangeFormat::Chicago, 115, 112), 112);
+    assert_eq!(truncate_prf(PageRangeFormat::Minimal, 115, 12), 12);
+    assert_eq!(truncate_prf(PageRangeFormat::Expanded, 5, 0), 0);
+}
+
 #[test]
 fn test_truncate_diff() {
     assert_eq!(truncate_diff(101, 105, 1), 5);
@@ -2,17 +2,17 @@ use crate::cite_context::RenderContext;
 use crate::number::{arabic_number, render_ordinal, roman_lower, roman_representable};
 use crate::prelude::*;
 use citeproc_io::output::LocalizedQuotes;
-use citeproc_io::{Name, NumericToken, NumericValue, Reference};
+use citeproc_io::{CaseLanguage, Name, NumericToken, NumericValue, Reference};
 use csl::{
     Features, GenderedTermSelector, LabelElement, Lang, Locale, LocatorType, NameLabel,
     NameVariable, NumberElement, NumberVariable, NumericForm, PageRangeFormat, Plural,
     RoleTermSelector, SortKey, StandardVariable, Style, TextElement, TextTermSelector, Variable,
-    VariableForm,
+    VariableForm, WordList,
 };
 
 use crate::choose::CondChecker;
 use citeproc_io::DateOrRange;
-use csl::{AnyVariable, DateVariable};
+use csl::{AnyVariable, Context, DateVariable};
 use csl::{CslType, Position};
 use std::borrow::Cow;
 
@@ -56,6 +56,10 @@ impl<'a, O: OutputFormat, I: OutputFormat> CondChecker for GenericContext<'a, O,
         fn locator_type(&self) -> Option<LocatorType>;
         fn get_date(&self, dvar: DateVariable) -> Option<&DateOrRange>;
         fn position(&self) -> Option<Position>;
+        fn context(&self) -> Context;
+        fn is_plural(&self, var: AnyVariable) -> bool;
+        fn page_label(&self) -> Option<LocatorType>;
+        fn genre(&self) -> Option<&str>;
         fn features(&self) -> &Features;
         fn has_year_only(&self, dvar: DateVariable) -> bool;
         fn has_month_or_season(&self, dvar: DateVariable) -> bool;
@@ -72,13 +76,28 @@ impl<'a, O: OutputFormat, I: OutputFormat> GenericContext<'a, O, I> {
     }
 
     /// https://docs.citationstyles.org/en/stable/specification.html#non-english-items
-    pub fn is_english(&self) -> bool {
+    ///
+    /// Without the `language-text-case` feature, only English items are title-cased, and
+    /// sentence case is the same for every language.
+    pub fn case_language(&self) -> CaseLanguage {
         let sty = self.style();
-        let cite = self.cite_lang();
-        // Bit messy but matches the spec wording
         // If a style doesn't have a default, it's en-US, which is English.
-        let default_is_english = sty.default_locale.as_ref().map_or(true, |x| x.is_english());
-        cite.map_or(default_is_english, |l| l.is_english())
+        let language = self
+            .cite_lang()
+            .or(sty.default_locale.as_ref())
+            .map_or(CaseLanguage::English, CaseLanguage::from_lang);
+        if language != CaseLanguage::English && !self.features().language_text_case {
+            return CaseLanguage::Other;
+        }
+        language
+    }
+
+    /// CSL-M `skip-words` from the locale, if the style enables them.
+    pub fn skip_words(&self) -> Option<WordList> {
+        if !self.features().skip_words {
+            return None;
+        }
+        self.locale().options_node.skip_words.clone()
     }
 
     /// For setting display="X" on elements, where this should only take effect in the
@@ -163,25 +182,38 @@ impl<'c, O: OutputFormat, I: OutputFormat> Renderer<'c, O, I> {
         let fmt = self.fmt();
         let prf = self.page_range_format(var);
         use crate::sort::natural_sort;
-        let affixes = Some(if var == NumberVariable::CitationNumber {
+        let affixes = if var == NumberVariable::CitationNumber {
             natural_sort::citation_number_affixes()
         } else {
             natural_sort::num_affixes()
-        });
+        };
         match (val, form) {
             (NumericValue::Tokens(_, ts, is_numeric), _) if *is_numeric => {
+                // Each number is delimited separately, so page prefixes like the S in S12 sort
+                // as text before the number.
                 let mut s = SmartString::new();
+                let push_num = |s: &mut SmartString, n: u32| {
+                    s.push_str(&affixes.prefix);
+                    s.push_str(&format!("{:08}", n));
+                    s.push_str(&affixes.suffix);
+                };
                 for t in ts {
                     if !s.is_empty() {
                         s.push(',');
                     }
-                    if let NumericToken::Num(n) = t {
-                        s.push_str(&format!("{:08}", n));
+                    match t {
+                        NumericToken::Num(n) | NumericToken::Roman(n, _) => push_num(&mut s, *n),
+                        NumericToken::Affixed(pre, n, suf) => {
+                            s.push_str(pre);
+                            push_num(&mut s, *n);
+                            s.push_str(suf);
+                        }
+                        _ => {}
                     }
                 }
-                fmt.affixed_text(s, None, affixes.as_ref())
+                fmt.affixed_text(s, None, None)
             }
-            _ => fmt.affixed_text(arabic_number(val, locale, var, prf), None, affixes.as_ref()),
+            _ => fmt.affixed_text(arabic_number(val, locale, var, prf), None, Some(&affixes)),
         }
     }
 
@@ -217,7 +249,8 @@ impl<'c, O: OutputFormat, I: OutputFormat> Renderer<'c, O, I> {
         let options = IngestOptions {
             text_case: number.text_case,
             quotes: self.quotes(),
-            is_english: self.ctx.is_english(),
+            language: self.ctx.case_language(),
+            skip_words: self.ctx.skip_words(),
             ..Default::default()
         };
         let b = fmt.ingest(&string, &options);
@@ -243,9 +276,7 @@ impl<'c, O: OutputFormat, I: OutputFormat> Renderer<'c, O, I> {
         variable: NumberVariable,
         val: &NumericValue<'_>,
     ) -> O::Build {
-        let style = self.ctx.style();
-        let _mod_page = style.page_range_format.is_some();
-        if variable == NumberVariable::Locator || variable == NumberVariable::Page {
+        if variable.is_page_like() {
             let number = csl::NumberElement {
                 variable,
                 form: csl::NumericForm::default(),
@@ -274,7 +305,8 @@ impl<'c, O: OutputFormat, I: OutputFormat> Renderer<'c, O, I> {
             text_case: text.text_case,
             quotes: self.quotes(),
             strip_periods: text.strip_periods,
-            is_english: self.ctx.is_english(),
+            language: self.ctx.case_language(),
+            skip_words: self.ctx.skip_words(),
             ..Default::default()
         };
         let hyper = match var {
@@ -292,7 +324,8 @@ impl<'c, O: OutputFormat, I: OutputFormat> Renderer<'c, O, I> {
             text_case: text.text_case,
             quotes: self.quotes(),
             strip_periods: text.strip_periods,
-            is_english: self.ctx.is_english(),
+            language: self.ctx.case_language(),
+            skip_words: self.ctx.skip_words(),
             ..Default::default()
         };
         Some(self.render_text_el(value, text, &options, None))
@@ -313,7 +346,8 @@ impl<'c, O: OutputFormat, I: OutputFormat> Renderer<'c, O, I> {
                     text_case: text.text_case,
                     quotes: self.quotes(),
                     strip_periods: text.strip_periods,
-                    is_english: self.ctx.is_english(),
+                    language: self.ctx.case_language(),
+                    skip_words: self.ctx.skip_words(),
                     ..Default::default()
                 };
                 self.render_text_el(val, text, &options, None)
@@ -391,7 +425,8 @@ impl<'c, O: OutputFormat, I: OutputFormat> Renderer<'c, O, I> {
                         text_case,
                         strip_periods,
                         quotes: self.quotes(),
-                        is_english: self.ctx.is_english(),
+                        language: self.ctx.case_language(),
+                        skip_words: self.ctx.skip_words(),
                         ..Default::default()
                     };
                     let b = fmt.ingest(term_text, &options);
@@ -421,7 +456,8 @@ impl<'c, O: OutputFormat, I: OutputFormat> Renderer<'c, O, I> {
             let options = IngestOptions {
                 text_case: label.text_case,
                 quotes: self.quotes(),
-                is_english: self.ctx.is_english(),
+                language: self.ctx.case_language(),
+                skip_words: self.ctx.skip_words(),
                 ..Default::default()
             };
             self.ctx
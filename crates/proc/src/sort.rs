@@ -1,9 +1,10 @@
 use crate::db::{with_bib_context, with_cite_context};
 use crate::prelude::*;
-use citeproc_db::{ClusterData, ClusterId, ClusterNumber};
-use citeproc_io::{ClusterMode, DateOrRange};
-use csl::{style::*, terms::*, variables::*, Atom};
+use citeproc_db::{CiteData, ClusterData, ClusterId, ClusterNumber};
+use citeproc_io::{ClusterMode, DateOrRange, DateSortKey};
+use csl::{style::*, terms::*, variables::*, Atom, Lang};
 use fnv::FnvHashMap;
+use serde_derive::{Deserialize, Serialize};
 use std::sync::Arc;
 
 mod lexical;
@@ -35,6 +36,43 @@ fn ctx_sort_string(
     string
 }
 
+/// With the CSL-M `leading-noise-words` feature, drops a leading article from a title, so that
+/// "The Economist" sorts under E. The words come from the locale, or else a built-in list for the
+/// reference's language.
+fn strip_leading_noise_words<'s, O: OutputFormat, I: OutputFormat>(
+    ctx: &CiteContext<'_, O, I>,
+    var: Variable,
+    title: &'s str,
+) -> &'s str {
+    if !ctx.style.features.leading_noise_words || !var.is_title() {
+        return title;
+    }
+    let strip = |word: &str| {
+        let len = word.len();
+        if title.get(..len)?.to_lowercase() != word {
+            return None;
+        }
+        let rest = &title[len..];
+        let elided = word.ends_with('\'') || word.ends_with('\u{2019}');
+        if !elided && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        Some(rest.trim_start()).filter(|rest| !rest.is_empty())
+    };
+    let stripped = match &ctx.locale.options_node.leading_noise_words {
+        Some(words) => words.iter().find_map(|w| strip(w.as_str())),
+        None => ctx
+            .reference
+            .language
+            .as_ref()
+            .or(ctx.locale.lang.as_ref())
+            .map_or(&[][..], Lang::default_leading_noise_words)
+            .iter()
+            .find_map(|w| strip(*w)),
+    };
+    stripped.unwrap_or(title)
+}
+
 /// Distinguish between uncited and cited items for sorting the `citation-number` variable or
 /// macro.
 ///
@@ -49,7 +87,8 @@ fn ctx_sort_string(
 /// key variable="citation-number"      =>  uncited items go last
 /// key variable="citation-number" desc =>  uncited items STILL go last
 /// citation-number, title              =>  uncited items AT END are sorted by title among themselves
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum BibNumber {
     Cited(u32),
     Uncited(u32),
@@ -167,6 +206,66 @@ pub fn sorted_refs(db: &dyn IrDatabase) -> Arc<(Vec<Atom>, FnvHashMap<Atom, BibN
     Arc::new((now_sorted, citation_numbers))
 }
 
+/// The bibliography order restricted to one section, numbered from 1 within it. None if there is
+/// no section with that name.
+///
+/// With a `cs:sort`, the section keeps the style's order, which filtering the full bibliography
+/// preserves. Without one, a section with a cluster range is in the order its references are first
+/// cited within that range, not in the whole document.
+pub fn section_sorted_refs(
+    db: &dyn IrDatabase,
+    name: SmartString,
+) -> Option<Arc<(Vec<Atom>, FnvHashMap<Atom, BibNumber>)>> {
+    let keys = db.section_keys(name.clone())?;
+    let sorted_refs = db.sorted_refs();
+    let (global_order, global_numbers) = &*sorted_refs;
+    let style = db.style();
+    let has_sort = !db.bibliography_no_sort()
+        && style
+            .bibliography
+            .as_ref()
+            .map_or(false, |bib| bib.sort.is_some());
+    let has_range = db
+        .bibliography_sections()
+        .iter()
+        .any(|s| s.name == name && s.clusters.is_some());
+    let refs: Vec<Atom> = if has_range && !has_sort {
+        keys.iter()
+            .filter(|id| global_numbers.contains_key(*id))
+            .cloned()
+            .collect()
+    } else {
+        global_order
+            .iter()
+            .filter(|id| keys.contains(*id))
+            .cloned()
+            .collect()
+    };
+    // A descending citation-number sort numbers the whole bibliography backwards, so the section
+    // does the same.
+    let reverse = global_order
+        .first()
+        .and_then(|id| global_numbers.get(id))
+        .map_or(false, |bn| bn.get() != 1);
+    let len = refs.len() as u32;
+    let numbers = refs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, id)| {
+            let mut i = i as u32 + 1;
+            if reverse {
+                i = len + 1 - i;
+            }
+            let number = match global_numbers.get(id)? {
+                BibNumber::Cited(_) => BibNumber::Cited(i),
+                BibNumber::Uncited(_) => BibNumber::Uncited(i),
+            };
+            Some((id.clone(), number))
+        })
+        .collect();
+    Some(Arc::new((refs, numbers)))
+}
+
 pub fn clusters_cites_sorted(db: &dyn IrDatabase) -> Arc<Vec<ClusterData>> {
     let cluster_ids = db.clusters_ordered();
     let mut clusters: Vec<_> = cluster_ids
@@ -241,11 +340,36 @@ pub fn cluster_data_sorted(db: &dyn IrDatabase, id: ClusterId) -> Option<Cluster
 /// May be None if the cite's reference does not exist.
 pub fn bib_number(db: &dyn IrDatabase, id: CiteId) -> Option<BibNumber> {
     let cite = id.lookup(db);
+    if let CiteData::RealCite { cluster, .. } = db.lookup_cite(id) {
+        if let Some(number) = section_bib_number(db, cluster, &cite.ref_id) {
+            return Some(number);
+        }
+    }
     let arc = db.sorted_refs();
     let (_, ref lookup_ref_ids) = &*arc;
     lookup_ref_ids.get(&cite.ref_id).cloned()
 }
 
+/// A cite in a section's cluster range gets its number from that section's bibliography, so the
+/// two agree. If the ranges of several sections hold the cite, the first one set wins. Sections
+/// defined only by a filter have no clusters of their own, so cites keep the numbers from the
+/// full bibliography.
+fn section_bib_number(db: &dyn IrDatabase, cluster: ClusterId, ref_id: &Atom) -> Option<BibNumber> {
+    let sections = db.bibliography_sections();
+    sections
+        .iter()
+        .filter(|section| {
+            section
+                .clusters
+                .as_ref()
+                .map_or(false, |range| range.contains(db, cluster))
+        })
+        .find_map(|section| {
+            let section_refs = db.section_sorted_refs(section.name.clone())?;
+            section_refs.1.get(ref_id).cloned()
+        })
+}
+
 #[derive(Debug, PartialEq, Eq)]
 struct SortItem {
     direction: Option<SortDirection>,
@@ -258,7 +382,7 @@ enum SortValue {
     OrdinaryVariable(Option<Natural<SmartString>>),
     Number(Option<citeproc_io::NumericValueOwned>),
     Names(Option<Vec<Natural<SmartString>>>),
-    Date(Option<DateOrRange>),
+    Date(Option<DateSortKey>),
 }
 
 use std::cmp::Ordering;
@@ -406,7 +530,7 @@ fn ctx_sort_items(
                     }
                     let got = a_ctx
                         .get_ordinary(v, VariableForm::default())
-                        .map(strip_markup)
+                        .map(|title| strip_markup(strip_leading_noise_words(&*a_ctx, v, &title)))
                         .map(Natural::new);
                     SortValue::OrdinaryVariable(got)
                 }
@@ -424,10 +548,9 @@ fn ctx_sort_items(
                     );
                     SortValue::Names(a_strings)
                 }
-                // TODO: compare dates, using details from spec for ranges
                 AnyVariable::Date(v) => {
                     let a_date = a_ctx.reference.date.get(&v);
-                    SortValue::Date(a_date.cloned())
+                    SortValue::Date(a_date.map(DateOrRange::sort_key))
                 }
             },
         };
@@ -536,16 +659,16 @@ impl<'a, O: OutputFormat> StyleWalker for SortingWalker<'a, O> {
         let renderer = self.renderer();
         let res = match svar {
             StandardVariable::Number(nvar) => self.ctx.get_number(nvar).map(|nval| {
-                if nvar == NumberVariable::CitationNumber {
+                if nvar == NumberVariable::CitationNumber || nvar.is_page_like() {
                     renderer.number_sort_string(nvar, NumericForm::Numeric, &nval)
                 } else {
                     renderer.text_variable(text, svar, nval.verbatim())
                 }
             }),
-            StandardVariable::Ordinary(var) => self
-                .ctx
-                .get_ordinary(var, form)
-                .map(|val| renderer.text_variable(text, svar, &val)),
+            StandardVariable::Ordinary(var) => self.ctx.get_ordinary(var, form).map(|val| {
+                let val = strip_leading_noise_words(&self.ctx, var, &val);
+                renderer.text_variable(text, svar, val)
+            }),
         };
         let gv = GroupVars::rendered_if(res.is_some());
         (res.unwrap_or_default(), gv)
@@ -617,14 +740,11 @@ impl<'a, O: OutputFormat> StyleWalker for SortingWalker<'a, O> {
     }
 
     fn text_macro(&mut self, text: &TextElement, name: &SmartString) -> Self::Output {
-        // TODO: same todos as in Proc
         let style = self.ctx.style;
-        let macro_elements = style
-            .macros
-            .get(name)
-            .expect("undefined macro should not be valid CSL");
-
-        self.state.push_macro(name);
+        let macro_elements = match style.macros.get(name) {
+            Some(els) if self.state.push_macro(name) => els,
+            _ => return self.default(),
+        };
         let ret = self.fold(macro_elements, WalkerFoldType::Macro(text));
         self.state.pop_macro(name);
         ret
@@ -46,6 +46,19 @@ pub enum Error {
         #[serde(skip_serializing)]
         citeproc::ReorderingError,
     ),
+    #[error("Could not restore state: {0}")]
+    RestoreError(String),
+}
+
+impl From<citeproc::RestoreError> for Error {
+    fn from(e: citeproc::RestoreError) -> Self {
+        match e {
+            citeproc::RestoreError::Style(se) => Error::StyleError(se),
+            citeproc::RestoreError::UnknownFeature(name) => Error::UnknownCSLFeature(name),
+            citeproc::RestoreError::Reordering(e) => Error::ReorderingError(e),
+            other => Error::RestoreError(other.to_string()),
+        }
+    }
 }
 
 fn style_error_to_js_err(se: &StyleError) -> JsValue {
@@ -146,6 +159,9 @@ type DriverError = {
     tag: "ReorderingError"
 } | {
     tag: "ReorderingErrorNumericId"
+} | {
+    tag: "RestoreError",
+    content: string,
 };
 
 declare global {
@@ -72,6 +72,7 @@ impl Driver {
             format: options.format,
             format_options: options.format_options,
             bibliography_no_sort: options.bibliography_no_sort,
+            parse_literal_names: options.parse_literal_names,
             locale_override: options.locale_override,
             test_mode: false,
             csl_features: Some(csl_features),
@@ -232,6 +233,30 @@ impl Driver {
         Ok(built)
     }
 
+    /// Like `builtCluster`, but also returns which part of the output each cite produced, e.g. for
+    /// making each cite separately clickable. A cite can have more than one span, and cites
+    /// collapsed into a range like `[1–3]` are given the span of the whole range.
+    ///
+    /// * returns a `ClusterSpans`
+    #[wasm_bindgen(js_name = "builtClusterSpans")]
+    pub fn built_cluster_spans(&self, id: &str) -> Result<typescript::ClusterSpans, Error> {
+        let eng = self.engine.borrow();
+        let (output, spans) = eng
+            .get_cluster_spans_str(id)
+            .ok_or_else(|| Error::NonExistentCluster(id.into()))?;
+        typescript::JsClusterSpans::new(&output, &spans).serialize_jsvalue()
+    }
+
+    /// Describes, in plain text, how disambiguation treated the `citeIndex`-th cite (in the
+    /// order the cites were inserted) in a cluster: which references it was ambiguous with after
+    /// each pass, and which names were expanded. Returns undefined if the cite does not exist.
+    #[wasm_bindgen(js_name = "explainDisambiguation")]
+    pub fn explain_disambiguation(&self, id: &str, cite_index: u32) -> Option<String> {
+        let eng = self.engine.borrow();
+        eng.explain_disambiguation_str(id, cite_index)
+            .map(|explanation| explanation.to_string())
+    }
+
     /// @deprecated Use `previewCluster` instead
     #[wasm_bindgen(js_name = "previewCitationCluster")]
     pub fn preview_citation_cluster(
@@ -288,6 +313,18 @@ impl Driver {
         Ok(preview.to_string())
     }
 
+    /// Renders the whole document with another style, without changing this driver, and returns
+    /// the clusters and bibliography entries that would change.
+    #[wasm_bindgen(js_name = "previewStyleSwitch")]
+    pub fn preview_style_switch(
+        &self,
+        style_text: &str,
+    ) -> Result<typescript::StylePreview, Error> {
+        let eng = self.engine.borrow();
+        let preview = eng.preview_style_switch_str(style_text)?;
+        preview.serialize_jsvalue()
+    }
+
     #[wasm_bindgen(js_name = "makeBibliography")]
     pub fn make_bibliography(&self) -> Result<typescript::BibEntries, Error> {
         let eng = self.engine.borrow();
@@ -302,6 +339,28 @@ impl Driver {
         meta.serialize_jsvalue()
     }
 
+    /// Defines named bibliography sections, each sorted and numbered on its own. Replaces any
+    /// sections set previously.
+    #[wasm_bindgen(js_name = "setBibliographySections")]
+    pub fn set_bibliography_sections(
+        &self,
+        sections: typescript::BibliographySections,
+    ) -> Result<(), Error> {
+        let sections = sections.ts_deserialize()?;
+        self.engine.borrow_mut().set_bibliography_sections_str(sections);
+        Ok(())
+    }
+
+    /// Returns null if there is no section with that name.
+    #[wasm_bindgen(js_name = "makeBibliographySection")]
+    pub fn make_bibliography_section(
+        &self,
+        name: &str,
+    ) -> Result<typescript::SectionBibEntries, Error> {
+        let eng = self.engine.borrow();
+        eng.get_bibliography_section(name).serialize_jsvalue()
+    }
+
     /// Specifies which clusters are actually considered to be in the document, and sets their
     /// order. You may insert as many clusters as you like, but the ones provided here are the only
     /// ones used.
@@ -333,6 +392,49 @@ impl Driver {
         Ok(())
     }
 
+    /// Applies a list of edits all at once. If any of them fails, none of them are applied.
+    /// Returns the changes since the last call to `batchedUpdates`, like `batchedUpdates` does.
+    ///
+    /// Transactions can be undone with `undo`. Edits made with the other methods can't be.
+    #[wasm_bindgen(js_name = "transaction")]
+    pub fn transaction(
+        &self,
+        edits: typescript::Edits,
+    ) -> Result<typescript::UpdateSummary, Error> {
+        let edits = edits.ts_deserialize()?;
+        let mut eng = self.engine.borrow_mut();
+        let summary = eng.transaction_str(edits)?;
+        summary.serialize_jsvalue()
+    }
+
+    /// Reverts the last transaction. Returns null if there is nothing to undo.
+    #[wasm_bindgen(js_name = "undo")]
+    pub fn undo(&self) -> Result<typescript::OptionalUpdateSummary, Error> {
+        let mut eng = self.engine.borrow_mut();
+        eng.undo_str().serialize_jsvalue()
+    }
+
+    /// Applies the last undone transaction again. Returns null if there is nothing to redo.
+    #[wasm_bindgen(js_name = "redo")]
+    pub fn redo(&self) -> Result<typescript::OptionalUpdateSummary, Error> {
+        let mut eng = self.engine.borrow_mut();
+        eng.redo_str().serialize_jsvalue()
+    }
+
+    /// The number of transactions that can be undone. Pass it to `rollbackTo` later to get back
+    /// to the current state.
+    #[wasm_bindgen(js_name = "revision")]
+    pub fn revision(&self) -> usize {
+        self.engine.borrow().revision()
+    }
+
+    /// Undoes transactions until `revision()` returns `revision`.
+    #[wasm_bindgen(js_name = "rollbackTo")]
+    pub fn rollback_to(&self, revision: usize) -> Result<typescript::UpdateSummary, Error> {
+        let mut eng = self.engine.borrow_mut();
+        eng.rollback_to_str(revision).serialize_jsvalue()
+    }
+
     /// Retrieve any clusters that have been touched since last time `batchedUpdates` was
     /// called. Intended to be called every time an edit has been made. Every cluster in the
     /// returned summary should then be reflected in any UI.
@@ -347,6 +449,18 @@ impl Driver {
         summary.serialize_jsvalue()
     }
 
+    /// Retrieve any problems that have appeared since the last time `takeDiagnostics` was called:
+    /// cites whose references are missing, a locale that couldn't be fetched, and broken or
+    /// unsupported parts of the style. Call it alongside `batchedUpdates`.
+    ///
+    /// * returns a `Diagnostic[]`
+    #[wasm_bindgen(js_name = "takeDiagnostics")]
+    pub fn take_diagnostics(&self) -> Result<typescript::Diagnostics, Error> {
+        let eng = self.engine.borrow();
+        let diagnostics = eng.take_diagnostics_str();
+        diagnostics.serialize_jsvalue()
+    }
+
     /// Returns all the clusters and bibliography entries in the document.
     /// Also drains the queue, just like batchedUpdates().
     /// Use this to rehydrate a document or run non-interactively.
@@ -9,6 +9,8 @@ use wasm_bindgen::prelude::*;
 pub(crate) struct JsFormatOptions {
     #[serde(default = "bool_true")]
     link_anchors: bool,
+    #[serde(default)]
+    left_margin_width: Option<u32>,
 }
 
 fn bool_true() -> bool {
@@ -47,6 +49,9 @@ pub struct WasmInitOptions {
     /// Disables sorting on the bibliography
     #[serde(default)]
     pub bibliography_no_sort: bool,
+    /// Splits literal names into family and given names
+    #[serde(default)]
+    pub parse_literal_names: bool,
 }
 
 #[wasm_bindgen]
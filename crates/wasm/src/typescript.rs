@@ -90,6 +90,8 @@ typescript_deserialize!(
 const TS_APPEND_CONTENT_1: &'static str = r#"
 interface FormatOptions {
     linkAnchors?: boolean;
+    /** In plain text, pads left-margin fields with spaces to this many characters. */
+    leftMarginWidth?: number;
 }
 
 interface InitOptions {
@@ -119,6 +121,12 @@ interface InitOptions {
 
     /** Disables sorting in the bibliography; items appear in cited order. */
     bibliographyNoSort?: boolean;
+
+    /**
+      * Splits `{ literal: "Smith, John A." }` names into family and given names, particles and
+      * suffixes. Names that look like institutions are left alone. Off by default.
+      */
+    parseLiteralNames?: boolean;
 }
 
 /** This interface lets citeproc retrieve locales or modules asynchronously,
@@ -142,9 +150,31 @@ interface BibliographyUpdate {
 type UpdateSummary<Output = string> = {
     clusters: [string, Output][];
     bibliography?: BibliographyUpdate;
+    /** Changes to each bibliography section, keyed by section name */
+    bibliographySections?: Map<string, BibliographyUpdate>;
+    /** Present if rendering was cancelled; the rest comes in the next summary */
+    interrupted?: boolean;
 };
 "#
 );
+typescript_serialize!(
+    Option<citeproc::string_id::UpdateSummary>,
+    OptionalUpdateSummary,
+    "UpdateSummary | null"
+);
+typescript_deserialize!(
+    Vec<citeproc::string_id::Edit>,
+    Edits,
+    "Edit[]",
+    r#"
+type Edit
+    = { insertReference: Reference }
+    | { removeReference: string }
+    | { insertCluster: Cluster }
+    | { removeCluster: string }
+    | { setClusterOrder: ClusterPosition[] };
+"#
+);
 typescript_serialize!(
     Vec<citeproc::BibEntry>,
     BibEntries,
@@ -156,6 +186,37 @@ interface BibEntry {
 }
 "#
 );
+typescript_serialize!(
+    Option<Vec<citeproc::BibEntry>>,
+    SectionBibEntries,
+    "BibEntry[] | null"
+);
+typescript_deserialize!(
+    Vec<citeproc::string_id::BibliographySection>,
+    BibliographySections,
+    "BibliographySection[]",
+    r#"
+interface FieldMatch {
+    /** "type", "keyword", or the name of an ordinary or number variable */
+    field: string;
+    value: string;
+}
+
+interface BibliographySection {
+    name: string;
+    /** Only references cited in this range of clusters, in document order */
+    clusters?: { first: string; last: string };
+    /** Must match all of these */
+    select?: FieldMatch[];
+    /** Must match at least one of these */
+    include?: FieldMatch[];
+    /** Must not match any of these */
+    exclude?: FieldMatch[];
+    /** Must not match all of these */
+    quash?: FieldMatch[];
+}
+"#
+);
 typescript_serialize!(
     citeproc::string_id::FullRender,
     FullRender,
@@ -167,6 +228,21 @@ interface FullRender {
 }
 "#
 );
+typescript_serialize!(
+    citeproc::string_id::StylePreview,
+    StylePreview,
+    "StylePreview",
+    r#"
+interface StylePreview {
+    /** Only the clusters whose output would change, in document order */
+    clusters: { id: string; before: string; after: string }[];
+    /** Only the entries that would change; before is missing for new entries, after for removed ones */
+    bibliography: { id: string; before?: string; after?: string }[];
+    /** Present if the bibliography's entries or their order would change */
+    entryIds?: string[];
+}
+"#
+);
 typescript_serialize!(
     Option<citeproc::BibliographyMeta>,
     BibliographyMeta,
@@ -184,6 +260,86 @@ interface BibliographyMeta {
 }
 "#
 );
+typescript_serialize!(
+    Vec<citeproc::string_id::Diagnostic>,
+    Diagnostics,
+    "Diagnostic[]",
+    r#"
+type DiagnosticKind
+    = "MissingReference"
+    | "LocaleUnavailable"
+    | "UndefinedMacro"
+    | "RecursiveMacro"
+    | "UnsupportedCondition"
+    | "UnparsedRawDate";
+interface Diagnostic {
+    kind: DiagnosticKind;
+    message: string;
+    /** Present when the problem is with one cite */
+    clusterId?: string;
+    /** Index of the cite within its cluster */
+    citeIndex?: number;
+    refId?: string;
+    /** "citation", "bibliography", or 'macro "name"', for problems with the style */
+    styleLocation?: string;
+}
+"#
+);
+/// A cluster's output and its `CiteSpan`s, with offsets counted in UTF-16 code units so they can
+/// be used with JS string methods directly.
+#[derive(Serialize)]
+pub struct JsClusterSpans {
+    pub output: String,
+    pub spans: Vec<JsCiteSpan>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsCiteSpan {
+    pub cite_index: u32,
+    pub ref_id: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl JsClusterSpans {
+    pub fn new(output: &str, spans: &[citeproc::CiteSpan]) -> Self {
+        let utf16 = |byte: usize| output[..byte].encode_utf16().count();
+        JsClusterSpans {
+            output: output.to_owned(),
+            spans: spans
+                .iter()
+                .map(|span| JsCiteSpan {
+                    cite_index: span.cite_index,
+                    ref_id: span.ref_id.to_string(),
+                    start: utf16(span.range.start),
+                    end: utf16(span.range.end),
+                })
+                .collect(),
+        }
+    }
+}
+
+typescript_serialize!(
+    JsClusterSpans,
+    ClusterSpans,
+    "ClusterSpans",
+    r#"
+interface CiteSpan {
+    /** Index of the cite within its cluster, in the order the cites were given */
+    citeIndex: number;
+    refId: string;
+    /** UTF-16 offsets into `output`, as used by `String.prototype.slice`. Includes the cite's
+     * prefix and suffix. */
+    start: number;
+    end: number;
+}
+interface ClusterSpans {
+    output: string;
+    spans: CiteSpan[];
+}
+"#
+);
 typescript_serialize!(Vec<String>, StringArray, "string[]");
 
 typescript_serialize!(
@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+
+# This Source Code Form is subject to the terms of the Mozilla Public License,
+# v. 2.0. If a copy of the MPL was not distributed with this file, You can
+# obtain one at http://mozilla.org/MPL/2.0/.
+#
+# Copyright © 2021 Corporation for Digital Scholarship
+
+# update-locales.sh
+#
+# Replaces the locale snapshot in crates/db/locales, which the citeproc-db
+# `bundled-locales` feature embeds, with the master branch of the CSL locales
+# repository.
+
+set -euo pipefail
+
+GIT_ROOT=$(git rev-parse --show-toplevel)
+DEST="$GIT_ROOT/crates/db/locales"
+TMP=$(mktemp -d)
+trap 'rm -rf "$TMP"' EXIT
+
+git clone --depth 1 https://github.com/citation-style-language/locales "$TMP/locales"
+rm -f "$DEST"/locales-*.xml
+cp "$TMP"/locales/locales-*.xml "$DEST/"
+echo "$(git -C "$TMP/locales" rev-parse HEAD)" > "$DEST/SNAPSHOT"
+echo "copied $(ls "$DEST"/locales-*.xml | wc -l) locales"